@@ -0,0 +1,85 @@
+//! Command line tool for common operations on CSAF documents, built on the [csaf] crate
+
+use std::{error::Error, path::PathBuf, process::ExitCode, str::FromStr};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use csaf::Csaf;
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Validate a CSAF document, printing every error found
+    Validate { file: PathBuf },
+    /// Convert an advisory in another format to a CSAF document, printed as JSON
+    Convert {
+        #[arg(long, value_enum)]
+        from: Format,
+        file: PathBuf,
+    },
+    /// Print a summary of a CSAF document
+    Info { file: PathBuf },
+    /// Merge CSAF documents, typically per-advisory VEX documents, printing the result as JSON
+    Merge {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+/// Advisory formats that can be converted to CSAF
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    /// A RustSec advisory in Markdown
+    Rustsec,
+}
+
+fn main() -> Result<ExitCode, Box<dyn Error>> {
+    match Cli::parse().command {
+        Command::Validate { file } => {
+            let document = Csaf::from_json_file(&file)?;
+            match document.validate() {
+                Ok(()) => {
+                    println!("{}: valid", file.display());
+                    Ok(ExitCode::SUCCESS)
+                }
+                Err(errors) => {
+                    for error in errors {
+                        println!("{}: {}", file.display(), error);
+                    }
+                    Ok(ExitCode::FAILURE)
+                }
+            }
+        }
+        Command::Convert { from, file } => {
+            let input = std::fs::read_to_string(&file)?;
+            let document = match from {
+                Format::Rustsec => Csaf::from(rustsec::Advisory::from_str(&input)?),
+            };
+            println!("{}", document.to_pretty_json()?);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Info { file } => {
+            let document = Csaf::from_json_file(&file)?;
+            println!("Title: {}", document.document.title);
+            println!("ID: {}", document.document.tracking.id);
+            println!(
+                "Vulnerabilities: {}",
+                document.vulnerabilities.map_or(0, |v| v.len())
+            );
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Merge { files } => {
+            let documents = files
+                .iter()
+                .map(Csaf::from_json_file)
+                .collect::<Result<Vec<_>, _>>()?;
+            println!("{}", Csaf::merge(documents)?.to_pretty_json()?);
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
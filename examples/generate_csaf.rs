@@ -4,8 +4,8 @@ use chrono::Utc;
 use csaf::{
     definitions::{Branch, BranchCategory, BranchesT, ProductIdT},
     document::{
-        Category, CsafVersion, Distribution, Document, Generator, Publisher, PublisherCategory,
-        Revision, Status, Tlp, TlpLabel, Tracking,
+        Category, CsafVersion, Distribution, Document, Publisher, PublisherCategory, Revision,
+        Status, Tlp, TlpLabel, TrackingBuilder,
     },
     product_tree::ProductTree,
     vulnerability::{Flag, FlagLabel, ProductStatus, Threat, ThreatCategory, Vulnerability},
@@ -24,14 +24,10 @@ use url::Url;
 // but it feels semantically incorrect. current_release_date: Utc::today() feels more correct than
 // current_release_date: Utc::now()
 //
-// Generator shouldn't really be up to the user to provide, so tracking defo needs to be covered by a constructor
-//
 // Default Revision?
 //
 // Should be able to provide just a Tlp Label and get a full Tlp with the default first Tlp url
 //
-// I bet there's a library for the lang tags https://www.iana.org/assignments/language-subtag-registry/language-subtag-registry
-//
 // Product Tree is not clear
 //   Provide with_branches(impl Into<BranchesT>) && (impl From<T> for BranchesT where T is collection of Branch) constructor? (one with_x constructor for each member enforces that at least one is set)
 //
@@ -77,14 +73,8 @@ fn main() {
         vuln.scores.take();
 
         vuln.product_status = Some(ProductStatus {
-            first_affected: None,
-            first_fixed: None,
-            fixed: None,
-            known_affected: None,
             known_not_affected: product_id_list.clone(),
-            last_affected: None,
-            recommended: None,
-            under_investigation: None,
+            ..Default::default()
         });
 
         vuln.flags = Some(vec![Flag {
@@ -115,35 +105,31 @@ fn main() {
                 issuing_authority: None,
             },
             title: "Csaf Crate Unaffected by time and chrono Vulnerabilities".to_string(),
-            tracking: Tracking {
-                current_release_date: now,
-                id: "CSAF-001".to_string(),
-                initial_release_date: now,
-                revision_history: vec![Revision {
+            tracking: TrackingBuilder::new()
+                .id("CSAF-001")
+                .status(Status::Draft)
+                .version("1")
+                .initial_release_date(now)
+                .revision(Revision {
                     date: now,
                     number: "1".to_string(),
                     summary: "Initial release".to_string(),
                     legacy_version: None,
-                }],
-                status: Status::Draft,
-                version: "1".to_string(),
-                aliases: None,
-                generator: Some(Generator::default()),
-            },
+                })
+                .build()
+                .unwrap(),
             csaf_version: CsafVersion::TwoDotZero,
             acknowledgments: None,
             aggregate_severity: None,
-            distribution: Some(Distribution {
-                text: None,
-                tlp: Some(Tlp {
-                    label: TlpLabel::WHITE,
-                    url: Url::parse("https://www.first.org/tlp/").ok(),
-                }),
-            }),
-            lang: Some("en".to_string()),
+            distribution: Some(Distribution::new_with_tlp(Tlp {
+                label: TlpLabel::WHITE,
+                url: Url::parse("https://www.first.org/tlp/").ok(),
+            })),
+            lang: Some("en".parse().unwrap()),
             notes: None,
             references: None,
             source_lang: None,
+            license_expression: None,
         },
         product_tree: Some(ProductTree {
             branches: Some(BranchesT(vec![Branch {
@@ -154,12 +140,10 @@ fn main() {
                     csaf::interop::rustsec::product_version_branch("0.3.0", "csaf", 1),
                 ])),
             }])),
-            full_product_names: None,
-            product_groups: None,
-            relationships: None,
+            ..Default::default()
         }),
         vulnerabilities: Some(vulns),
     };
 
-    println!("{}", serde_json::to_string_pretty(&c).unwrap());
+    println!("{}", c.to_pretty_json().unwrap());
 }
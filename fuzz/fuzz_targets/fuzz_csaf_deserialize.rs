@@ -0,0 +1,11 @@
+#![no_main]
+
+use csaf::Csaf;
+use libfuzzer_sys::fuzz_target;
+
+// Malformed documents must be rejected with an error, never a panic
+fuzz_target!(|data: &[u8]| {
+    if let Ok(json) = std::str::from_utf8(data) {
+        let _ = serde_json::from_str::<Csaf>(json);
+    }
+});
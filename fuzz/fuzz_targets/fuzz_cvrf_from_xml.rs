@@ -0,0 +1,9 @@
+#![no_main]
+
+use csaf::Csaf;
+use libfuzzer_sys::fuzz_target;
+
+// Malformed CVRF must be rejected with an error, never a panic
+fuzz_target!(|data: &[u8]| {
+    let _ = Csaf::from_cvrf_xml(data);
+});
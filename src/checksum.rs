@@ -0,0 +1,208 @@
+//! Verifying files against the [hashes](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#31332-full-product-name-type---product-identification-helper---hashes)
+//! a document gives for its products
+//!
+//! Only `md5`, `sha1`, `sha224`, `sha256`, `sha384` and `sha512` digests can be computed. Other algorithms listed for a
+//! file are skipped, as long as at least one supported algorithm is given for it.
+
+use std::{
+    fmt::Display,
+    fs::File,
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use sha2::Digest;
+
+use crate::{definitions::HashCollection, Csaf};
+
+/// The first file that failed verification and why
+#[derive(Debug)]
+pub enum ChecksumError {
+    /// The file name is absolute or leaves the base directory, so it was not read
+    InvalidFileName { file_name: String },
+    /// The file could not be read, usually because it is missing
+    Io { path: PathBuf, source: io::Error },
+    /// None of the algorithms given for the file can be computed
+    UnsupportedAlgorithm {
+        path: PathBuf,
+        algorithms: Vec<String>,
+    },
+    /// The file's digest does not match the document
+    Mismatch {
+        path: PathBuf,
+        algorithm: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl Display for ChecksumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFileName { file_name } => {
+                write!(f, "file name {:?} is outside the base directory", file_name)
+            }
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::UnsupportedAlgorithm { path, algorithms } => write!(
+                f,
+                "{}: no supported hash algorithm in {}",
+                path.display(),
+                algorithms.join(", ")
+            ),
+            Self::Mismatch {
+                path,
+                algorithm,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{}: {} is {}, expected {}",
+                path.display(),
+                algorithm,
+                actual,
+                expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChecksumError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Csaf {
+    /// Checks every file named in the product tree's `hashes` against its digests, reading the files relative to
+    /// `base_path`. Stops at the first file that is missing or does not match.
+    pub fn verify_checksums(&self, base_path: &Path) -> Result<(), ChecksumError> {
+        let collections = self
+            .product_tree
+            .iter()
+            .flat_map(|tree| tree.defined_products())
+            .filter_map(|product| product.product_identification_helper.as_ref())
+            .flat_map(|helper| helper.hashes.iter().flatten());
+        for collection in collections {
+            verify_collection(collection, base_path)?;
+        }
+        Ok(())
+    }
+}
+
+fn verify_collection(collection: &HashCollection, base_path: &Path) -> Result<(), ChecksumError> {
+    let file_name = Path::new(&collection.file_name);
+    if !file_name
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(ChecksumError::InvalidFileName {
+            file_name: collection.file_name.clone(),
+        });
+    }
+    let path = base_path.join(file_name);
+
+    let mut verified = false;
+    for hash in &collection.file_hashes {
+        let actual = match digest(&hash.algorithm, &path) {
+            Some(actual) => actual.map_err(|source| ChecksumError::Io {
+                path: path.clone(),
+                source,
+            })?,
+            None => continue,
+        };
+        if !actual.eq_ignore_ascii_case(&hash.value) {
+            return Err(ChecksumError::Mismatch {
+                path,
+                algorithm: hash.algorithm.clone(),
+                expected: hash.value.clone(),
+                actual,
+            });
+        }
+        verified = true;
+    }
+
+    if verified {
+        Ok(())
+    } else {
+        Err(ChecksumError::UnsupportedAlgorithm {
+            path,
+            algorithms: collection
+                .file_hashes
+                .iter()
+                .map(|hash| hash.algorithm.clone())
+                .collect(),
+        })
+    }
+}
+
+/// The lowercase hex digest of the file, or `None` if the algorithm, matched ignoring case, is not supported
+fn digest(algorithm: &str, path: &Path) -> Option<io::Result<String>> {
+    fn hash<D: Digest + io::Write>(path: &Path) -> io::Result<String> {
+        let mut hasher = D::new();
+        io::copy(&mut File::open(path)?, &mut hasher)?;
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+
+    match algorithm.to_ascii_lowercase().as_str() {
+        "md5" => Some(hash::<md5::Md5>(path)),
+        "sha1" => Some(hash::<sha1::Sha1>(path)),
+        "sha224" => Some(hash::<sha2::Sha224>(path)),
+        "sha256" => Some(hash::<sha2::Sha256>(path)),
+        "sha384" => Some(hash::<sha2::Sha384>(path)),
+        "sha512" => Some(hash::<sha2::Sha512>(path)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::HashValue;
+
+    #[test]
+    fn verifies_files_against_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("product.bin"), b"hello world").unwrap();
+
+        let mut collection = HashCollection {
+            file_hashes: vec![
+                HashValue {
+                    algorithm: "blake2b512".to_string(),
+                    value: "00".repeat(64),
+                },
+                HashValue {
+                    algorithm: "SHA256".to_string(),
+                    value: "B94D27B9934D3E08A52E52D7DA7DABFAC484EFE37A5380EE9088F7ACE2EFCDE9"
+                        .to_string(),
+                },
+            ],
+            file_name: "product.bin".to_string(),
+        };
+        verify_collection(&collection, dir.path()).unwrap();
+
+        collection.file_hashes[1].value = "00".repeat(32);
+        assert!(matches!(
+            verify_collection(&collection, dir.path()),
+            Err(ChecksumError::Mismatch { .. })
+        ));
+
+        collection.file_name = "missing.bin".to_string();
+        assert!(matches!(
+            verify_collection(&collection, dir.path()),
+            Err(ChecksumError::Io { .. })
+        ));
+
+        collection.file_name = "../product.bin".to_string();
+        assert!(matches!(
+            verify_collection(&collection, dir.path()),
+            Err(ChecksumError::InvalidFileName { .. })
+        ));
+    }
+}
@@ -0,0 +1,820 @@
+//! [CVSS v2](https://www.first.org/cvss/v2/guide) metrics and scoring
+//!
+//! The `cvss` crate used for CVSS v3 does not support v2, which is still common in older advisories. A [Vector] holds
+//! the base metric group along with the temporal and environmental groups when the vector string includes them.
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// CVSS v2 base metric group, parsed from a vector string like `AV:N/AC:L/Au:N/C:P/I:P/A:P`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Base {
+    pub access_vector: AccessVector,
+    pub access_complexity: AccessComplexity,
+    pub authentication: Authentication,
+    pub confidentiality_impact: Impact,
+    pub integrity_impact: Impact,
+    pub availability_impact: Impact,
+}
+
+impl Base {
+    /// Base score as defined in [section 3.2.1](https://www.first.org/cvss/v2/guide#3-2-1-Base-Equation) of the guide
+    pub fn score(&self) -> f64 {
+        let impact = 10.41
+            * (1.0
+                - (1.0 - self.confidentiality_impact.weight())
+                    * (1.0 - self.integrity_impact.weight())
+                    * (1.0 - self.availability_impact.weight()));
+        self.score_with_impact(impact)
+    }
+
+    /// The base equation for the given impact, which the environmental equation adjusts
+    fn score_with_impact(&self, impact: f64) -> f64 {
+        let exploitability = 20.0
+            * self.access_vector.weight()
+            * self.access_complexity.weight()
+            * self.authentication.weight();
+        let f_impact = if impact == 0.0 { 0.0 } else { 1.176 };
+
+        round_to_1_decimal(((0.6 * impact) + (0.4 * exploitability) - 1.5) * f_impact)
+    }
+}
+
+fn round_to_1_decimal(score: f64) -> f64 {
+    (score * 10.0).round() / 10.0
+}
+
+fn set<T>(slot: &mut Option<T>, metric: &str, value: T) -> Result<(), ParseError> {
+    if slot.replace(value).is_some() {
+        Err(ParseError::DuplicateMetric(metric.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+impl FromStr for Base {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut access_vector = None;
+        let mut access_complexity = None;
+        let mut authentication = None;
+        let mut confidentiality_impact = None;
+        let mut integrity_impact = None;
+        let mut availability_impact = None;
+
+        for component in s.split('/') {
+            let (metric, value) = component
+                .split_once(':')
+                .ok_or_else(|| ParseError::MalformedComponent(component.to_string()))?;
+            let invalid = || ParseError::InvalidValue {
+                metric: metric.to_string(),
+                value: value.to_string(),
+            };
+            match metric {
+                "AV" => set(
+                    &mut access_vector,
+                    metric,
+                    match value {
+                        "L" => AccessVector::Local,
+                        "A" => AccessVector::AdjacentNetwork,
+                        "N" => AccessVector::Network,
+                        _ => return Err(invalid()),
+                    },
+                )?,
+                "AC" => set(
+                    &mut access_complexity,
+                    metric,
+                    match value {
+                        "H" => AccessComplexity::High,
+                        "M" => AccessComplexity::Medium,
+                        "L" => AccessComplexity::Low,
+                        _ => return Err(invalid()),
+                    },
+                )?,
+                "Au" => set(
+                    &mut authentication,
+                    metric,
+                    match value {
+                        "M" => Authentication::Multiple,
+                        "S" => Authentication::Single,
+                        "N" => Authentication::None,
+                        _ => return Err(invalid()),
+                    },
+                )?,
+                "C" | "I" | "A" => {
+                    let impact = match value {
+                        "N" => Impact::None,
+                        "P" => Impact::Partial,
+                        "C" => Impact::Complete,
+                        _ => return Err(invalid()),
+                    };
+                    let slot = match metric {
+                        "C" => &mut confidentiality_impact,
+                        "I" => &mut integrity_impact,
+                        _ => &mut availability_impact,
+                    };
+                    set(slot, metric, impact)?
+                }
+                _ => return Err(ParseError::UnknownMetric(metric.to_string())),
+            }
+        }
+
+        Ok(Self {
+            access_vector: access_vector.ok_or(ParseError::MissingMetric("AV"))?,
+            access_complexity: access_complexity.ok_or(ParseError::MissingMetric("AC"))?,
+            authentication: authentication.ok_or(ParseError::MissingMetric("Au"))?,
+            confidentiality_impact: confidentiality_impact.ok_or(ParseError::MissingMetric("C"))?,
+            integrity_impact: integrity_impact.ok_or(ParseError::MissingMetric("I"))?,
+            availability_impact: availability_impact.ok_or(ParseError::MissingMetric("A"))?,
+        })
+    }
+}
+
+impl Display for Base {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "AV:{}/AC:{}/Au:{}/C:{}/I:{}/A:{}",
+            match self.access_vector {
+                AccessVector::Local => "L",
+                AccessVector::AdjacentNetwork => "A",
+                AccessVector::Network => "N",
+            },
+            match self.access_complexity {
+                AccessComplexity::High => "H",
+                AccessComplexity::Medium => "M",
+                AccessComplexity::Low => "L",
+            },
+            match self.authentication {
+                Authentication::Multiple => "M",
+                Authentication::Single => "S",
+                Authentication::None => "N",
+            },
+            self.confidentiality_impact.abbreviation(),
+            self.integrity_impact.abbreviation(),
+            self.availability_impact.abbreviation(),
+        )
+    }
+}
+
+/// A CVSS v2 vector, parsed from a vector string like `AV:N/AC:L/Au:N/C:N/I:N/A:C/E:F/RL:OF/RC:C`. The temporal and
+/// environmental groups are only present when the vector string includes at least one of their metrics, and any
+/// metric of a group that is left out is taken to be not defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Vector {
+    pub base: Base,
+    pub temporal: Option<Temporal>,
+    pub environmental: Option<Environmental>,
+}
+
+impl Vector {
+    /// The base score, see [Base::score]
+    pub fn base_score(&self) -> f64 {
+        self.base.score()
+    }
+
+    /// Temporal score as defined in [section 3.2.2](https://www.first.org/cvss/v2/guide#3-2-2-Temporal-Equation) of
+    /// the guide, if the vector has temporal metrics
+    pub fn temporal_score(&self) -> Option<f64> {
+        self.temporal
+            .map(|temporal| temporal.adjust(self.base.score()))
+    }
+
+    /// Environmental score as defined in
+    /// [section 3.2.3](https://www.first.org/cvss/v2/guide#3-2-3-Environmental-Equation) of the guide, if the vector
+    /// has environmental metrics
+    pub fn environmental_score(&self) -> Option<f64> {
+        let environmental = self.environmental?;
+        let adjusted_impact = (10.41
+            * (1.0
+                - (1.0
+                    - self.base.confidentiality_impact.weight()
+                        * environmental.confidentiality_requirement.weight())
+                    * (1.0
+                        - self.base.integrity_impact.weight()
+                            * environmental.integrity_requirement.weight())
+                    * (1.0
+                        - self.base.availability_impact.weight()
+                            * environmental.availability_requirement.weight())))
+        .min(10.0);
+        let adjusted_base = self.base.score_with_impact(adjusted_impact);
+        let adjusted_temporal = self.temporal.unwrap_or_default().adjust(adjusted_base);
+        Some(round_to_1_decimal(
+            (adjusted_temporal
+                + (10.0 - adjusted_temporal) * environmental.collateral_damage_potential.weight())
+                * environmental.target_distribution.weight(),
+        ))
+    }
+}
+
+impl From<Base> for Vector {
+    fn from(base: Base) -> Self {
+        Self {
+            base,
+            temporal: None,
+            environmental: None,
+        }
+    }
+}
+
+impl FromStr for Vector {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut base = Vec::new();
+        let mut exploitability = None;
+        let mut remediation_level = None;
+        let mut report_confidence = None;
+        let mut collateral_damage_potential = None;
+        let mut target_distribution = None;
+        let mut confidentiality_requirement = None;
+        let mut integrity_requirement = None;
+        let mut availability_requirement = None;
+
+        for component in s.split('/') {
+            let (metric, value) = component
+                .split_once(':')
+                .ok_or_else(|| ParseError::MalformedComponent(component.to_string()))?;
+            let invalid = || ParseError::InvalidValue {
+                metric: metric.to_string(),
+                value: value.to_string(),
+            };
+            match metric {
+                "E" => set(
+                    &mut exploitability,
+                    metric,
+                    Exploitability::from_abbreviation(value).ok_or_else(invalid)?,
+                )?,
+                "RL" => set(
+                    &mut remediation_level,
+                    metric,
+                    RemediationLevel::from_abbreviation(value).ok_or_else(invalid)?,
+                )?,
+                "RC" => set(
+                    &mut report_confidence,
+                    metric,
+                    ReportConfidence::from_abbreviation(value).ok_or_else(invalid)?,
+                )?,
+                "CDP" => set(
+                    &mut collateral_damage_potential,
+                    metric,
+                    CollateralDamagePotential::from_abbreviation(value).ok_or_else(invalid)?,
+                )?,
+                "TD" => set(
+                    &mut target_distribution,
+                    metric,
+                    TargetDistribution::from_abbreviation(value).ok_or_else(invalid)?,
+                )?,
+                "CR" | "IR" | "AR" => {
+                    let requirement = Requirement::from_abbreviation(value).ok_or_else(invalid)?;
+                    let slot = match metric {
+                        "CR" => &mut confidentiality_requirement,
+                        "IR" => &mut integrity_requirement,
+                        _ => &mut availability_requirement,
+                    };
+                    set(slot, metric, requirement)?
+                }
+                _ => base.push(component),
+            }
+        }
+
+        let temporal = if exploitability.is_some()
+            || remediation_level.is_some()
+            || report_confidence.is_some()
+        {
+            Some(Temporal {
+                exploitability: exploitability.unwrap_or_default(),
+                remediation_level: remediation_level.unwrap_or_default(),
+                report_confidence: report_confidence.unwrap_or_default(),
+            })
+        } else {
+            None
+        };
+        let environmental = if collateral_damage_potential.is_some()
+            || target_distribution.is_some()
+            || confidentiality_requirement.is_some()
+            || integrity_requirement.is_some()
+            || availability_requirement.is_some()
+        {
+            Some(Environmental {
+                collateral_damage_potential: collateral_damage_potential.unwrap_or_default(),
+                target_distribution: target_distribution.unwrap_or_default(),
+                confidentiality_requirement: confidentiality_requirement.unwrap_or_default(),
+                integrity_requirement: integrity_requirement.unwrap_or_default(),
+                availability_requirement: availability_requirement.unwrap_or_default(),
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            base: base.join("/").parse()?,
+            temporal,
+            environmental,
+        })
+    }
+}
+
+impl Display for Vector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.base)?;
+        if let Some(temporal) = &self.temporal {
+            write!(
+                f,
+                "/E:{}/RL:{}/RC:{}",
+                temporal.exploitability.abbreviation(),
+                temporal.remediation_level.abbreviation(),
+                temporal.report_confidence.abbreviation(),
+            )?;
+        }
+        if let Some(environmental) = &self.environmental {
+            write!(
+                f,
+                "/CDP:{}/TD:{}/CR:{}/IR:{}/AR:{}",
+                environmental.collateral_damage_potential.abbreviation(),
+                environmental.target_distribution.abbreviation(),
+                environmental.confidentiality_requirement.abbreviation(),
+                environmental.integrity_requirement.abbreviation(),
+                environmental.availability_requirement.abbreviation(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// CVSS v2 temporal metric group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Temporal {
+    pub exploitability: Exploitability,
+    pub remediation_level: RemediationLevel,
+    pub report_confidence: ReportConfidence,
+}
+
+impl Temporal {
+    fn adjust(self, base_score: f64) -> f64 {
+        round_to_1_decimal(
+            base_score
+                * self.exploitability.weight()
+                * self.remediation_level.weight()
+                * self.report_confidence.weight(),
+        )
+    }
+}
+
+/// CVSS v2 environmental metric group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Environmental {
+    pub collateral_damage_potential: CollateralDamagePotential,
+    pub target_distribution: TargetDistribution,
+    pub confidentiality_requirement: Requirement,
+    pub integrity_requirement: Requirement,
+    pub availability_requirement: Requirement,
+}
+
+/// Access Vector (AV)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccessVector {
+    Local,
+    AdjacentNetwork,
+    Network,
+}
+
+impl AccessVector {
+    fn weight(self) -> f64 {
+        match self {
+            Self::Local => 0.395,
+            Self::AdjacentNetwork => 0.646,
+            Self::Network => 1.0,
+        }
+    }
+}
+
+/// Access Complexity (AC)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccessComplexity {
+    High,
+    Medium,
+    Low,
+}
+
+impl AccessComplexity {
+    fn weight(self) -> f64 {
+        match self {
+            Self::High => 0.35,
+            Self::Medium => 0.61,
+            Self::Low => 0.71,
+        }
+    }
+}
+
+/// Authentication (Au)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Authentication {
+    Multiple,
+    Single,
+    None,
+}
+
+impl Authentication {
+    fn weight(self) -> f64 {
+        match self {
+            Self::Multiple => 0.45,
+            Self::Single => 0.56,
+            Self::None => 0.704,
+        }
+    }
+}
+
+/// Confidentiality (C), Integrity (I) and Availability (A) Impact
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Impact {
+    None,
+    Partial,
+    Complete,
+}
+
+impl Impact {
+    fn weight(self) -> f64 {
+        match self {
+            Self::None => 0.0,
+            Self::Partial => 0.275,
+            Self::Complete => 0.660,
+        }
+    }
+
+    fn abbreviation(self) -> &'static str {
+        match self {
+            Self::None => "N",
+            Self::Partial => "P",
+            Self::Complete => "C",
+        }
+    }
+}
+
+/// Exploitability (E)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Exploitability {
+    Unproven,
+    ProofOfConcept,
+    Functional,
+    High,
+    #[default]
+    NotDefined,
+}
+
+impl Exploitability {
+    fn weight(self) -> f64 {
+        match self {
+            Self::Unproven => 0.85,
+            Self::ProofOfConcept => 0.9,
+            Self::Functional => 0.95,
+            Self::High | Self::NotDefined => 1.0,
+        }
+    }
+
+    fn abbreviation(self) -> &'static str {
+        match self {
+            Self::Unproven => "U",
+            Self::ProofOfConcept => "POC",
+            Self::Functional => "F",
+            Self::High => "H",
+            Self::NotDefined => "ND",
+        }
+    }
+
+    fn from_abbreviation(value: &str) -> Option<Self> {
+        match value {
+            "U" => Some(Self::Unproven),
+            "POC" => Some(Self::ProofOfConcept),
+            "F" => Some(Self::Functional),
+            "H" => Some(Self::High),
+            "ND" => Some(Self::NotDefined),
+            _ => None,
+        }
+    }
+}
+
+/// Remediation Level (RL)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RemediationLevel {
+    OfficialFix,
+    TemporaryFix,
+    Workaround,
+    Unavailable,
+    #[default]
+    NotDefined,
+}
+
+impl RemediationLevel {
+    fn weight(self) -> f64 {
+        match self {
+            Self::OfficialFix => 0.87,
+            Self::TemporaryFix => 0.90,
+            Self::Workaround => 0.95,
+            Self::Unavailable | Self::NotDefined => 1.0,
+        }
+    }
+
+    fn abbreviation(self) -> &'static str {
+        match self {
+            Self::OfficialFix => "OF",
+            Self::TemporaryFix => "TF",
+            Self::Workaround => "W",
+            Self::Unavailable => "U",
+            Self::NotDefined => "ND",
+        }
+    }
+
+    fn from_abbreviation(value: &str) -> Option<Self> {
+        match value {
+            "OF" => Some(Self::OfficialFix),
+            "TF" => Some(Self::TemporaryFix),
+            "W" => Some(Self::Workaround),
+            "U" => Some(Self::Unavailable),
+            "ND" => Some(Self::NotDefined),
+            _ => None,
+        }
+    }
+}
+
+/// Report Confidence (RC)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReportConfidence {
+    Unconfirmed,
+    Uncorroborated,
+    Confirmed,
+    #[default]
+    NotDefined,
+}
+
+impl ReportConfidence {
+    fn weight(self) -> f64 {
+        match self {
+            Self::Unconfirmed => 0.90,
+            Self::Uncorroborated => 0.95,
+            Self::Confirmed | Self::NotDefined => 1.0,
+        }
+    }
+
+    fn abbreviation(self) -> &'static str {
+        match self {
+            Self::Unconfirmed => "UC",
+            Self::Uncorroborated => "UR",
+            Self::Confirmed => "C",
+            Self::NotDefined => "ND",
+        }
+    }
+
+    fn from_abbreviation(value: &str) -> Option<Self> {
+        match value {
+            "UC" => Some(Self::Unconfirmed),
+            "UR" => Some(Self::Uncorroborated),
+            "C" => Some(Self::Confirmed),
+            "ND" => Some(Self::NotDefined),
+            _ => None,
+        }
+    }
+}
+
+/// Collateral Damage Potential (CDP)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CollateralDamagePotential {
+    None,
+    Low,
+    LowMedium,
+    MediumHigh,
+    High,
+    #[default]
+    NotDefined,
+}
+
+impl CollateralDamagePotential {
+    fn weight(self) -> f64 {
+        match self {
+            Self::None | Self::NotDefined => 0.0,
+            Self::Low => 0.1,
+            Self::LowMedium => 0.3,
+            Self::MediumHigh => 0.4,
+            Self::High => 0.5,
+        }
+    }
+
+    fn abbreviation(self) -> &'static str {
+        match self {
+            Self::None => "N",
+            Self::Low => "L",
+            Self::LowMedium => "LM",
+            Self::MediumHigh => "MH",
+            Self::High => "H",
+            Self::NotDefined => "ND",
+        }
+    }
+
+    fn from_abbreviation(value: &str) -> Option<Self> {
+        match value {
+            "N" => Some(Self::None),
+            "L" => Some(Self::Low),
+            "LM" => Some(Self::LowMedium),
+            "MH" => Some(Self::MediumHigh),
+            "H" => Some(Self::High),
+            "ND" => Some(Self::NotDefined),
+            _ => None,
+        }
+    }
+}
+
+/// Target Distribution (TD)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TargetDistribution {
+    None,
+    Low,
+    Medium,
+    High,
+    #[default]
+    NotDefined,
+}
+
+impl TargetDistribution {
+    fn weight(self) -> f64 {
+        match self {
+            Self::None => 0.0,
+            Self::Low => 0.25,
+            Self::Medium => 0.75,
+            Self::High | Self::NotDefined => 1.0,
+        }
+    }
+
+    fn abbreviation(self) -> &'static str {
+        match self {
+            Self::None => "N",
+            Self::Low => "L",
+            Self::Medium => "M",
+            Self::High => "H",
+            Self::NotDefined => "ND",
+        }
+    }
+
+    fn from_abbreviation(value: &str) -> Option<Self> {
+        match value {
+            "N" => Some(Self::None),
+            "L" => Some(Self::Low),
+            "M" => Some(Self::Medium),
+            "H" => Some(Self::High),
+            "ND" => Some(Self::NotDefined),
+            _ => None,
+        }
+    }
+}
+
+/// Confidentiality (CR), Integrity (IR) and Availability (AR) Requirement
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Requirement {
+    Low,
+    Medium,
+    High,
+    #[default]
+    NotDefined,
+}
+
+impl Requirement {
+    fn weight(self) -> f64 {
+        match self {
+            Self::Low => 0.5,
+            Self::Medium | Self::NotDefined => 1.0,
+            Self::High => 1.51,
+        }
+    }
+
+    fn abbreviation(self) -> &'static str {
+        match self {
+            Self::Low => "L",
+            Self::Medium => "M",
+            Self::High => "H",
+            Self::NotDefined => "ND",
+        }
+    }
+
+    fn from_abbreviation(value: &str) -> Option<Self> {
+        match value {
+            "L" => Some(Self::Low),
+            "M" => Some(Self::Medium),
+            "H" => Some(Self::High),
+            "ND" => Some(Self::NotDefined),
+            _ => None,
+        }
+    }
+}
+
+/// Reasons a string is not a valid CVSS v2 vector
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// A `/` separated component without a `:`
+    MalformedComponent(String),
+    /// A metric that is not part of any CVSS v2 metric group, or not part of the base group when parsing a [Base]
+    UnknownMetric(String),
+    InvalidValue {
+        metric: String,
+        value: String,
+    },
+    DuplicateMetric(String),
+    MissingMetric(&'static str),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MalformedComponent(c) => write!(f, "malformed CVSS v2 component {:?}", c),
+            Self::UnknownMetric(m) => write!(f, "unknown CVSS v2 metric {:?}", m),
+            Self::InvalidValue { metric, value } => {
+                write!(f, "invalid value {:?} for CVSS v2 metric {}", value, metric)
+            }
+            Self::DuplicateMetric(m) => write!(f, "CVSS v2 metric {} given more than once", m),
+            Self::MissingMetric(m) => write!(f, "CVSS v2 metric {} is missing", m),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_match_nvd() {
+        for (vector, score) in [
+            ("AV:N/AC:L/Au:N/C:P/I:N/A:N", 5.0),
+            ("AV:N/AC:L/Au:N/C:P/I:P/A:P", 7.5),
+            ("AV:N/AC:L/Au:N/C:C/I:C/A:C", 10.0),
+            ("AV:L/AC:H/Au:M/C:N/I:N/A:N", 0.0),
+            ("AV:N/AC:M/Au:N/C:N/I:P/A:N", 4.3),
+            ("AV:L/AC:L/Au:N/C:C/I:C/A:C", 7.2),
+        ] {
+            let base: Base = vector.parse().unwrap();
+            assert_eq!(base.score(), score, "{}", vector);
+            assert_eq!(base.to_string(), vector);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_vectors() {
+        assert_eq!(
+            "AV:N/AC:L/Au:N/C:P/I:N".parse::<Base>(),
+            Err(ParseError::MissingMetric("A"))
+        );
+        assert_eq!(
+            "AV:X/AC:L/Au:N/C:P/I:N/A:N".parse::<Base>(),
+            Err(ParseError::InvalidValue {
+                metric: "AV".to_string(),
+                value: "X".to_string()
+            })
+        );
+        assert_eq!(
+            "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H".parse::<Base>(),
+            Err(ParseError::UnknownMetric("CVSS".to_string()))
+        );
+        assert!("AV:N/AV:N/AC:L/Au:N/C:P/I:N/A:N".parse::<Base>().is_err());
+    }
+    #[test]
+    fn temporal_and_environmental_scores_match_guide() {
+        // CVE-2002-0392 from section 3.3.1 of the guide
+        let vector: Vector = "AV:N/AC:L/Au:N/C:N/I:N/A:C/E:F/RL:OF/RC:C/CDP:H/TD:H/CR:M/IR:M/AR:H"
+            .parse()
+            .unwrap();
+        assert_eq!(vector.base_score(), 7.8);
+        assert_eq!(vector.temporal_score(), Some(6.4));
+        assert_eq!(vector.environmental_score(), Some(9.2));
+        assert_eq!(
+            vector.to_string(),
+            "AV:N/AC:L/Au:N/C:N/I:N/A:C/E:F/RL:OF/RC:C/CDP:H/TD:H/CR:M/IR:M/AR:H"
+        );
+
+        let vector: Vector = "AV:N/AC:L/Au:N/C:N/I:N/A:C/CDP:N/TD:N".parse().unwrap();
+        assert_eq!(vector.temporal_score(), None);
+        assert_eq!(vector.environmental_score(), Some(0.0));
+        assert_eq!(
+            vector.to_string(),
+            "AV:N/AC:L/Au:N/C:N/I:N/A:C/CDP:N/TD:N/CR:ND/IR:ND/AR:ND"
+        );
+
+        assert_eq!(
+            "AV:N/AC:L/Au:N/C:N/I:N/A:C/E:X".parse::<Vector>(),
+            Err(ParseError::InvalidValue {
+                metric: "E".to_string(),
+                value: "X".to_string()
+            })
+        );
+    }
+}
@@ -1,16 +1,19 @@
-use std::convert::{TryFrom, TryInto};
+use std::{collections::HashMap, convert::TryFrom, fmt::Display, str::FromStr};
 
+use language_tags::LanguageTag;
 use packageurl::PackageUrl;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use url::Url;
 
+use crate::validation::ValidationError;
+
 pub(crate) type AcknowledgmentsT = Vec<Acknowledgment>;
 
-// TODO: with at least 1 and at most 4 properties
 /// [Acknowledgment](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#311-acknowledgments-type)
 #[serde_with::skip_serializing_none]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Acknowledgment {
     pub names: Option<Vec<String>>,
     pub organization: Option<String>,
@@ -18,11 +21,88 @@ pub struct Acknowledgment {
     pub urls: Option<Vec<Url>>,
 }
 
+impl Acknowledgment {
+    pub fn builder() -> AcknowledgmentBuilder {
+        AcknowledgmentBuilder::default()
+    }
+
+    /// Who to credit in a single string: the `organization`, or else the first of `names`
+    pub fn primary_contact(&self) -> Option<&str> {
+        self.organization
+            .as_deref()
+            .or_else(|| self.names.as_ref()?.first().map(String::as_str))
+    }
+
+    /// Checks that at least one property is set, as the spec requires
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.names.is_none()
+            && self.organization.is_none()
+            && self.summary.is_none()
+            && self.urls.is_none()
+        {
+            Err(ValidationError::EmptyAcknowledgment)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Builder for [Acknowledgment] which requires at least one property to be set
+///
+/// ```
+/// # use csaf::definitions::Acknowledgment;
+/// let acknowledgment = Acknowledgment::builder()
+///     .name("Jane Doe")
+///     .organization("Example Security")
+///     .build()
+///     .unwrap();
+/// assert_eq!(acknowledgment.names, Some(vec!["Jane Doe".to_string()]));
+/// assert!(Acknowledgment::builder().build().is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AcknowledgmentBuilder {
+    acknowledgment: Acknowledgment,
+}
+
+impl AcknowledgmentBuilder {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.acknowledgment
+            .names
+            .get_or_insert_with(Vec::new)
+            .push(name.into());
+        self
+    }
+
+    pub fn organization(mut self, organization: impl Into<String>) -> Self {
+        self.acknowledgment.organization = Some(organization.into());
+        self
+    }
+
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.acknowledgment.summary = Some(summary.into());
+        self
+    }
+
+    pub fn url(mut self, url: Url) -> Self {
+        self.acknowledgment
+            .urls
+            .get_or_insert_with(Vec::new)
+            .push(url);
+        self
+    }
+
+    pub fn build(self) -> Result<Acknowledgment, ValidationError> {
+        self.acknowledgment.validate()?;
+        Ok(self.acknowledgment)
+    }
+}
+
 /// [Branches](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#312-branches-type)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct BranchesT(pub Vec<Branch>);
 
 impl BranchesT {
+    #[cfg(feature = "rustsec-interop")]
     pub(crate) fn product_ids(&self) -> Option<Vec<ProductIdT>> {
         if self.0.is_empty() {
             None
@@ -30,6 +110,94 @@ impl BranchesT {
             Some(self.0.iter().map(|x| x.try_into().unwrap()).collect())
         }
     }
+
+    /// The `product_version` branches whose name is a semantic version, sorted from lowest to highest version
+    pub fn sorted_by_version(&self) -> Vec<&Branch> {
+        let mut versions: Vec<_> = self
+            .0
+            .iter()
+            .filter_map(|branch| branch.semver_version().map(|version| (version, branch)))
+            .collect();
+        versions.sort_by(|(a, _), (b, _)| a.cmp(b));
+        versions.into_iter().map(|(_, branch)| branch).collect()
+    }
+
+    /// Every branch, including nested branches, in depth-first order
+    fn depth_first(&self) -> Vec<&Branch> {
+        fn collect<'a>(branches: &'a BranchesT, output: &mut Vec<&'a Branch>) {
+            for branch in branches {
+                output.push(branch);
+                if let Some(children) = &branch.branches {
+                    collect(children, output);
+                }
+            }
+        }
+
+        let mut output = Vec::new();
+        collect(self, &mut output);
+        output
+    }
+
+    /// The first branch with the given name, searching nested branches depth-first
+    pub fn find_by_name(&self, name: &str) -> Option<&Branch> {
+        self.depth_first()
+            .into_iter()
+            .find(|branch| branch.name == name)
+    }
+
+    /// The first branch with the given category, searching nested branches depth-first
+    pub fn find_by_category(&self, category: &BranchCategory) -> Option<&Branch> {
+        self.depth_first()
+            .into_iter()
+            .find(|branch| branch.category == *category)
+    }
+
+    /// Every branch with the given category, including nested branches, in depth-first order
+    pub fn find_all_by_category<'a>(
+        &'a self,
+        category: &'a BranchCategory,
+    ) -> impl Iterator<Item = &'a Branch> + 'a {
+        self.depth_first()
+            .into_iter()
+            .filter(move |branch| branch.category == *category)
+    }
+
+    /// The top level branches, without descending into nested branches
+    pub fn iter(&self) -> std::slice::Iter<'_, Branch> {
+        self.0.iter()
+    }
+
+    /// The top level branches, mutably, without descending into nested branches
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Branch> {
+        self.0.iter_mut()
+    }
+}
+
+impl<'a> IntoIterator for &'a BranchesT {
+    type Item = &'a Branch;
+    type IntoIter = std::slice::Iter<'a, Branch>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut BranchesT {
+    type Item = &'a mut Branch;
+    type IntoIter = std::slice::IterMut<'a, Branch>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl IntoIterator for BranchesT {
+    type Item = Branch;
+    type IntoIter = std::vec::IntoIter<Branch>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
 }
 
 impl TryFrom<&Branch> for ProductIdT {
@@ -46,6 +214,7 @@ impl TryFrom<&Branch> for ProductIdT {
 /// [Branch](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3121-branches-type---branches)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Branch {
     pub name: String,
     pub category: BranchCategory,
@@ -54,9 +223,46 @@ pub struct Branch {
     pub branches: Option<BranchesT>,
 }
 
+impl Branch {
+    /// The name of a `product_version` branch parsed as a semantic version, if it is one
+    pub fn semver_version(&self) -> Option<SemverVersion> {
+        match self.category {
+            BranchCategory::ProductVersion => self.name.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// A [semantic version](https://semver.org/), as used in the name of `product_version` branches
+///
+/// Store it in [Branch::name] with `to_string()` and read it back with [Branch::semver_version].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SemverVersion(pub semver::Version);
+
+impl FromStr for SemverVersion {
+    type Err = semver::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        semver::Version::parse(s).map(Self)
+    }
+}
+
+impl Display for SemverVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<semver::Version> for SemverVersion {
+    fn from(version: semver::Version) -> Self {
+        Self(version)
+    }
+}
+
 /// [Branch Category](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3122-branches-type---category)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum BranchCategory {
     Architecture,
     HostName,
@@ -72,9 +278,27 @@ pub enum BranchCategory {
     Vendor,
 }
 
+impl BranchCategory {
+    /// Whether branches of this category are expected to be leaves carrying a [FullProductName]: `product_version`,
+    /// `product_version_range`, `patch_level` and `service_pack`
+    pub fn is_leaf_category(&self) -> bool {
+        matches!(
+            self,
+            Self::ProductVersion | Self::ProductVersionRange | Self::PatchLevel | Self::ServicePack
+        )
+    }
+
+    /// Whether the category names the product rather than a version of it: `vendor`, `product_family` and
+    /// `product_name`
+    pub fn is_identification_category(&self) -> bool {
+        matches!(self, Self::Vendor | Self::ProductFamily | Self::ProductName)
+    }
+}
+
 /// [Full Product Name](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#313-full-product-name-type)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct FullProductName {
     pub name: String,
     pub product_id: ProductIdT,
@@ -84,44 +308,626 @@ pub struct FullProductName {
 /// [Product Identification Helper](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3133-full-product-name-type---product-identification-helper)
 #[serde_as]
 #[serde_with::skip_serializing_none]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct ProductIdentificationHelper {
     #[serde_as(as = "Option<DisplayFromStr>")]
-    pub cpe: Option<cpe::uri::OwnedUri>,
+    pub cpe: Option<Cpe>,
     pub hashes: Option<Vec<HashCollection>>,
     pub model_numbers: Option<Vec<String>>, // TODO: No empty strings, enforce unique
     #[serde_as(as = "Option<DisplayFromStr>")]
-    pub purl: Option<PackageUrl<'static>>,
+    pub purl: Option<Purl>,
     pub sbom_urls: Option<Vec<Url>>,
     pub serial_numbers: Option<Vec<String>>, // TODO: No empty strings, enforce unique
     pub skus: Option<Vec<String>>,
     pub x_generic_uris: Option<Vec<Url>>,
 }
 
+impl ProductIdentificationHelper {
+    /// A helper with only `purl` set, to `pkg:cargo/{name}@{version}`. Like the other package helpers, fails with
+    /// [packageurl::Error::MissingName] if `name` is empty.
+    pub fn for_cargo_crate(name: &str, version: &str) -> Result<Self, PurlParseError> {
+        Self::for_package("cargo", None, name, version)
+    }
+
+    /// A helper with only `purl` set, to `pkg:npm/{name}@{version}`. A scope in the name, such as `@angular/core`, becomes
+    /// the namespace.
+    pub fn for_npm_package(name: &str, version: &str) -> Result<Self, PurlParseError> {
+        match name.split_once('/') {
+            Some((scope, name)) if scope.starts_with('@') => {
+                Self::for_package("npm", Some(scope), name, version)
+            }
+            _ => Self::for_package("npm", None, name, version),
+        }
+    }
+
+    /// A helper with only `purl` set, to `pkg:pypi/{name}@{version}`
+    pub fn for_pypi_package(name: &str, version: &str) -> Result<Self, PurlParseError> {
+        Self::for_package("pypi", None, name, version)
+    }
+
+    /// A helper with only `purl` set, to `pkg:maven/{group_id}/{artifact_id}@{version}`
+    pub fn for_maven_artifact(
+        group_id: &str,
+        artifact_id: &str,
+        version: &str,
+    ) -> Result<Self, PurlParseError> {
+        Self::for_package("maven", Some(group_id), artifact_id, version)
+    }
+
+    fn for_package(
+        ty: &'static str,
+        namespace: Option<&str>,
+        name: &str,
+        version: &str,
+    ) -> Result<Self, PurlParseError> {
+        // PackageUrl::new accepts an empty name, though the purl spec requires one
+        if name.is_empty() {
+            return Err(PurlParseError(packageurl::Error::MissingName));
+        }
+        let mut purl = PackageUrl::new(ty, name.to_string()).map_err(PurlParseError)?;
+        if let Some(namespace) = namespace {
+            purl.with_namespace(namespace.to_string());
+        }
+        purl.with_version(version.to_string());
+        Ok(Self {
+            purl: Some(Purl(purl)),
+            ..Default::default()
+        })
+    }
+}
+
+/// [CPE](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#31331-full-product-name-type---product-identification-helper---cpe)
+///
+/// Either a CPE 2.3 formatted string, or a CPE 2.2 URI which the spec still permits. Validated against the pattern
+/// from the spec on parsing, and displayed exactly as it was parsed so existing documents round-trip unchanged.
+///
+/// Component accessors return `None` for the logical value ANY (`*`, or an empty CPE 2.2 URI component), and the
+/// component as bound in the string otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cpe {
+    value: String,
+    binding: CpeBinding,
+}
+
+/// The string binding a [Cpe] was expressed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CpeBinding {
+    /// CPE 2.3 formatted string, `cpe:2.3:...`
+    FormattedString,
+    /// CPE 2.2 URI, `cpe:/...`
+    Uri,
+}
+
+const CPE_FS_PREFIX: &str = "cpe:2.3:";
+const CPE_URI_PREFIX_LEN: usize = "cpe:/".len();
+const CPE_COMPONENT_NAMES: [&str; 11] = [
+    "part",
+    "vendor",
+    "product",
+    "version",
+    "update",
+    "edition",
+    "language",
+    "sw_edition",
+    "target_sw",
+    "target_hw",
+    "other",
+];
+
+impl Cpe {
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    pub fn binding(&self) -> CpeBinding {
+        self.binding
+    }
+
+    pub fn part(&self) -> Option<&str> {
+        self.component(0)
+    }
+
+    pub fn vendor(&self) -> Option<&str> {
+        self.component(1)
+    }
+
+    pub fn product(&self) -> Option<&str> {
+        self.component(2)
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.component(3)
+    }
+
+    pub fn update(&self) -> Option<&str> {
+        self.component(4)
+    }
+
+    pub fn edition(&self) -> Option<&str> {
+        self.component(5)
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.component(6)
+    }
+
+    fn component(&self, index: usize) -> Option<&str> {
+        let components = match self.binding {
+            CpeBinding::FormattedString => {
+                split_unescaped_colons(&self.value[CPE_FS_PREFIX.len()..])
+            }
+            CpeBinding::Uri => self.value[CPE_URI_PREFIX_LEN..].split(':').collect(),
+        };
+        components
+            .get(index)
+            .copied()
+            .filter(|c| !c.is_empty() && *c != "*")
+    }
+}
+
+impl FromStr for Cpe {
+    type Err = CpeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(rest) = s.strip_prefix(CPE_FS_PREFIX) {
+            let components = split_unescaped_colons(rest);
+            if components.len() != CPE_COMPONENT_NAMES.len() {
+                return Err(CpeParseError::ComponentCount {
+                    expected: CPE_COMPONENT_NAMES.len(),
+                    found: components.len(),
+                });
+            }
+            for (index, component) in components.iter().enumerate() {
+                let valid = match index {
+                    0 => matches!(*component, "a" | "h" | "o" | "*" | "-"),
+                    6 => is_valid_cpe_language(component),
+                    _ => is_valid_cpe_avstring(component),
+                };
+                if !valid {
+                    return Err(CpeParseError::InvalidComponent {
+                        name: CPE_COMPONENT_NAMES[index],
+                        value: component.to_string(),
+                    });
+                }
+            }
+            Ok(Self {
+                value: s.to_string(),
+                binding: CpeBinding::FormattedString,
+            })
+        } else if s.len() >= CPE_URI_PREFIX_LEN
+            && s.is_char_boundary(CPE_URI_PREFIX_LEN)
+            && s[..CPE_URI_PREFIX_LEN].eq_ignore_ascii_case("cpe:/")
+            && s.starts_with('c')
+        {
+            let components: Vec<&str> = s[CPE_URI_PREFIX_LEN..].split(':').collect();
+            // Part, followed by at most 6 further components
+            if components.len() > 7 {
+                return Err(CpeParseError::ComponentCount {
+                    expected: 7,
+                    found: components.len(),
+                });
+            }
+            for (index, component) in components.iter().enumerate() {
+                let valid = if index == 0 {
+                    matches!(*component, "" | "a" | "h" | "o" | "A" | "H" | "O")
+                } else {
+                    component.chars().all(|c| {
+                        c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '~' | '%')
+                    })
+                };
+                if !valid {
+                    return Err(CpeParseError::InvalidComponent {
+                        name: CPE_COMPONENT_NAMES[index],
+                        value: component.to_string(),
+                    });
+                }
+            }
+            Ok(Self {
+                value: s.to_string(),
+                binding: CpeBinding::Uri,
+            })
+        } else {
+            Err(CpeParseError::InvalidPrefix)
+        }
+    }
+}
+
+impl Display for Cpe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+/// Binds a CPE built with the `cpe` crate as a CPE 2.3 formatted string
+impl TryFrom<cpe::uri::OwnedUri> for Cpe {
+    type Error = CpeParseError;
+
+    fn try_from(uri: cpe::uri::OwnedUri) -> Result<Self, Self::Error> {
+        use cpe::{
+            component::Component,
+            cpe::{Cpe as _, CpeType, Language},
+        };
+
+        fn bind(component: Component) -> String {
+            match component {
+                Component::Any => "*".to_string(),
+                Component::NotApplicable => "-".to_string(),
+                Component::Value(value) => {
+                    let mut output = String::with_capacity(value.len());
+                    for c in value.chars() {
+                        if !(c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_')) {
+                            output.push('\\');
+                        }
+                        output.push(c);
+                    }
+                    output
+                }
+            }
+        }
+
+        let part = match uri.part() {
+            CpeType::Application => "a",
+            CpeType::Hardware => "h",
+            CpeType::OperatingSystem => "o",
+            CpeType::Any | CpeType::Empty => "*",
+        };
+        let language = match uri.language() {
+            Language::Any => "*".to_string(),
+            Language::Language(tag) => tag.to_string(),
+        };
+
+        format!(
+            "{}{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}",
+            CPE_FS_PREFIX,
+            part,
+            bind(uri.vendor()),
+            bind(uri.product()),
+            bind(uri.version()),
+            bind(uri.update()),
+            bind(uri.edition()),
+            language,
+            bind(uri.sw_edition()),
+            bind(uri.target_sw()),
+            bind(uri.target_hw()),
+            bind(uri.other()),
+        )
+        .parse()
+    }
+}
+
+/// Splits a CPE 2.3 formatted string on `:`, ignoring any escaped with `\`
+fn split_unescaped_colons(s: &str) -> Vec<&str> {
+    let mut output = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ':' => {
+                output.push(&s[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    output.push(&s[start..]);
+    output
+}
+
+fn is_valid_cpe_avstring(s: &str) -> bool {
+    if s == "*" || s == "-" {
+        return true;
+    }
+
+    let bytes = s.as_bytes();
+    let mut index = 0;
+    // Leading wildcard is either a single `*` or any number of `?`
+    if bytes.first() == Some(&b'*') {
+        index = 1;
+    } else {
+        while bytes.get(index) == Some(&b'?') {
+            index += 1;
+        }
+    }
+
+    let body_start = index;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => match bytes.get(index + 1) {
+                Some(c) if b"\\*?!\"#$%&'()+,/:;<=>@[]^`{|}~".contains(c) => index += 2,
+                _ => return false,
+            },
+            c if c.is_ascii_alphanumeric() || matches!(c, b'-' | b'.' | b'_') => index += 1,
+            _ => break,
+        }
+    }
+    if index == body_start {
+        return false;
+    }
+
+    let trailing = &bytes[index..];
+    trailing == b"*" || trailing.iter().all(|c| *c == b'?')
+}
+
+fn is_valid_cpe_language(s: &str) -> bool {
+    if s == "*" || s == "-" {
+        return true;
+    }
+    let (language, region) = match s.split_once('-') {
+        Some((language, region)) => (language, Some(region)),
+        None => (s, None),
+    };
+    (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && region.map_or(true, |r| {
+            (r.len() == 2 && r.chars().all(|c| c.is_ascii_alphabetic()))
+                || (r.len() == 3 && r.chars().all(|c| c.is_ascii_digit()))
+        })
+}
+
+/// Reasons a string is not a valid [Cpe]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CpeParseError {
+    /// Neither `cpe:2.3:` nor `cpe:/`
+    InvalidPrefix,
+    /// The wrong number of `:` separated components
+    ComponentCount { expected: usize, found: usize },
+    /// A component that does not match the grammar for its position
+    InvalidComponent { name: &'static str, value: String },
+}
+
+impl Display for CpeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidPrefix => write!(f, "CPE must start with `cpe:2.3:` or `cpe:/`"),
+            Self::ComponentCount { expected, found } => write!(
+                f,
+                "expected {} CPE components but found {}",
+                expected, found
+            ),
+            Self::InvalidComponent { name, value } => {
+                write!(f, "invalid CPE {} component {:?}", name, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CpeParseError {}
+
+/// [PURL](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#31336-full-product-name-type---product-identification-helper---purl)
+///
+/// A [Package URL](https://github.com/package-url/purl-spec) of the form `pkg:type/namespace/name@version?qualifiers#subpath`,
+/// validated on parsing and displayed in canonical form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Purl(PackageUrl<'static>);
+
+impl Purl {
+    pub fn ty(&self) -> &str {
+        self.0.ty()
+    }
+
+    pub fn namespace(&self) -> Option<&str> {
+        self.0.namespace()
+    }
+
+    pub fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.0.version()
+    }
+
+    pub fn qualifiers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .qualifiers()
+            .iter()
+            .map(|(k, v)| (k.as_ref(), v.as_ref()))
+    }
+
+    pub fn subpath(&self) -> Option<&str> {
+        self.0.subpath()
+    }
+
+    pub fn as_package_url(&self) -> &PackageUrl<'static> {
+        &self.0
+    }
+}
+
+impl From<PackageUrl<'static>> for Purl {
+    fn from(purl: PackageUrl<'static>) -> Self {
+        Self(purl)
+    }
+}
+
+impl FromStr for Purl {
+    type Err = PurlParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PackageUrl::from_str(s).map(Self).map_err(PurlParseError)
+    }
+}
+
+impl Display for Purl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A string that is not a valid [Purl]
+#[derive(Debug, PartialEq)]
+pub struct PurlParseError(pub packageurl::Error);
+
+impl Display for PurlParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid package URL: {}", self.0)
+    }
+}
+
+impl std::error::Error for PurlParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
 /// [Hashes](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#31332-full-product-name-type---product-identification-helper---hashes)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct HashCollection {
     pub file_hashes: Vec<HashValue>,
     pub file_name: String,
 }
 
+impl HashCollection {
+    /// The value of the first hash using `algorithm`, such as `"sha256"`, ignoring case
+    pub fn get(&self, algorithm: &str) -> Option<&str> {
+        self.file_hashes
+            .iter()
+            .find(|hash| hash.algorithm.eq_ignore_ascii_case(algorithm))
+            .map(|hash| hash.value.as_str())
+    }
+}
+
+/// Maps each algorithm to its value, keeping the first value for an algorithm listed more than once
+impl From<&HashCollection> for HashMap<String, String> {
+    fn from(hashes: &HashCollection) -> Self {
+        let mut map = HashMap::new();
+        for hash in &hashes.file_hashes {
+            map.entry(hash.algorithm.clone())
+                .or_insert_with(|| hash.value.clone());
+        }
+        map
+    }
+}
+
+/// Digest names supported by OpenSSL, with leading dashes removed, which the spec permits as a hash algorithm
+pub const HASH_ALGORITHMS: &[&str] = &[
+    "blake2b512",
+    "blake2s256",
+    "md4",
+    "md5",
+    "md5-sha1",
+    "mdc2",
+    "ripemd",
+    "ripemd160",
+    "rmd160",
+    "sha1",
+    "sha224",
+    "sha256",
+    "sha3-224",
+    "sha3-256",
+    "sha3-384",
+    "sha3-512",
+    "sha384",
+    "sha512",
+    "sha512-224",
+    "sha512-256",
+    "shake128",
+    "shake256",
+    "sm3",
+    "ssl3-md5",
+    "ssl3-sha1",
+    "whirlpool",
+];
+
 /// [Hashes](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#31332-full-product-name-type---product-identification-helper---hashes)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct HashValue {
-    // TODO: Validation - These values are derived from the currently supported digests OpenSSL [OPENSSL]. Leading dashs were removed.
     pub algorithm: String,
-    // TODO: Validation  ^[0-9a-fA-F]{32,}$
     pub value: String,
 }
 
+impl HashValue {
+    /// Checks that `algorithm` is one of [HASH_ALGORITHMS], ignoring case, and `value` matches `^[0-9a-fA-F]{32,}$`
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if !HASH_ALGORITHMS
+            .iter()
+            .any(|algorithm| algorithm.eq_ignore_ascii_case(&self.algorithm))
+        {
+            Err(ValidationError::UnknownHashAlgorithm {
+                algorithm: self.algorithm.clone(),
+            })
+        } else if self.value.len() < 32 || !self.value.chars().all(|c| c.is_ascii_hexdigit()) {
+            Err(ValidationError::InvalidHashValue {
+                value: self.value.clone(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
 /// [LangT](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#314-language-type)
-pub(crate) type LangT = String; // TODO: Constrain/validate
+///
+/// An [IETF BCP 47](https://www.rfc-editor.org/info/bcp47) language tag, checked for well-formedness on parsing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Lang(LanguageTag);
+
+impl Lang {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn primary_language(&self) -> &str {
+        self.0.primary_language()
+    }
+
+    pub fn region(&self) -> Option<&str> {
+        self.0.region()
+    }
+}
+
+impl FromStr for Lang {
+    type Err = LangParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LanguageTag::parse(s)
+            .map(Self)
+            .map_err(|source| LangParseError {
+                value: s.to_string(),
+                source,
+            })
+    }
+}
+
+impl Display for Lang {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A string that is not a well-formed [Lang]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LangParseError {
+    pub value: String,
+    pub source: language_tags::ParseError,
+}
+
+impl Display for LangParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} is not a BCP 47 language tag: {}",
+            self.value, self.source
+        )
+    }
+}
+
+impl std::error::Error for LangParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
 
 pub(crate) type NotesT = Vec<Note>;
 
 /// [Notes](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#315-notes-type)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Note {
     pub category: NoteCategory,
     pub text: String,
@@ -129,9 +935,26 @@ pub struct Note {
     pub title: Option<String>,
 }
 
+impl Note {
+    /// Checks that `text` is not empty, and that `audience` and `title` are not empty if they are set
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let empty = |value: &str| value.trim().is_empty();
+        if empty(&self.text) {
+            Err(ValidationError::EmptyNoteField { field: "text" })
+        } else if self.audience.as_deref().map_or(false, empty) {
+            Err(ValidationError::EmptyNoteField { field: "audience" })
+        } else if self.title.as_deref().map_or(false, empty) {
+            Err(ValidationError::EmptyNoteField { field: "title" })
+        } else {
+            Ok(())
+        }
+    }
+}
+
 /// [Notes](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#315-notes-type)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum NoteCategory {
     Description,
     Details,
@@ -149,9 +972,37 @@ pub(crate) type ProductGroupIdT = String;
 pub(crate) type ProductGroupsT = Vec<ProductGroupIdT>;
 
 /// [Product IDs](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#318-product-id-type)
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ProductIdT(pub String);
 
+impl Display for ProductIdT {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for ProductIdT {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl AsRef<str> for ProductIdT {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::ops::Deref for ProductIdT {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
 /// [Products](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#319-products-type)
 pub(crate) type ProductsT = Vec<ProductIdT>;
 
@@ -160,6 +1011,7 @@ pub(crate) type ReferencesT = Vec<Reference>;
 /// [References](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3110-references-type)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Reference {
     pub url: Url,
     pub summary: String,
@@ -169,6 +1021,7 @@ pub struct Reference {
 /// [References](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3110-references-type)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum ReferenceCategory {
     External,
     #[serde(rename = "self")]
@@ -178,3 +1031,398 @@ pub enum ReferenceCategory {
 // TODO: Contraint/validation
 /// [Version](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3111-version-type)
 pub(crate) type VersionT = String;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_lookup_and_validation() {
+        let sha256 = "a".repeat(64);
+        let hashes = HashCollection {
+            file_hashes: vec![
+                HashValue {
+                    algorithm: "sha256".to_string(),
+                    value: sha256.clone(),
+                },
+                HashValue {
+                    algorithm: "md5".to_string(),
+                    value: "not hex".to_string(),
+                },
+            ],
+            file_name: "product.tar.gz".to_string(),
+        };
+        assert_eq!(hashes.get("sha256"), Some(sha256.as_str()));
+        assert_eq!(hashes.get("SHA256"), Some(sha256.as_str()));
+        assert_eq!(hashes.get("sha1"), None);
+        let map = HashMap::from(&hashes);
+        assert_eq!(map["md5"], "not hex");
+
+        assert_eq!(hashes.file_hashes[0].validate(), Ok(()));
+        let uppercase = HashValue {
+            algorithm: "SHA256".to_string(),
+            value: sha256.clone(),
+        };
+        assert_eq!(uppercase.validate(), Ok(()));
+        assert_eq!(
+            hashes.file_hashes[1].validate(),
+            Err(ValidationError::InvalidHashValue {
+                value: "not hex".to_string()
+            })
+        );
+        let unknown = HashValue {
+            algorithm: "-sha256".to_string(),
+            value: sha256,
+        };
+        assert_eq!(
+            unknown.validate(),
+            Err(ValidationError::UnknownHashAlgorithm {
+                algorithm: "-sha256".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn cpe_formatted_string() {
+        let cpe: Cpe = "cpe:2.3:a:microsoft:internet_explorer:8.0.6001:beta:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+        assert_eq!(cpe.binding(), CpeBinding::FormattedString);
+        assert_eq!(cpe.part(), Some("a"));
+        assert_eq!(cpe.vendor(), Some("microsoft"));
+        assert_eq!(cpe.product(), Some("internet_explorer"));
+        assert_eq!(cpe.version(), Some("8.0.6001"));
+        assert_eq!(cpe.update(), Some("beta"));
+        assert_eq!(cpe.edition(), None);
+
+        let escaped: Cpe =
+            r"cpe:2.3:a:hp:insight_diagnostics:7.4.0.1570:-:*:en-us:online:win2003\:x64:*:*"
+                .parse()
+                .unwrap();
+        assert_eq!(escaped.language(), Some("en-us"));
+        assert_eq!(
+            escaped.to_string(),
+            r"cpe:2.3:a:hp:insight_diagnostics:7.4.0.1570:-:*:en-us:online:win2003\:x64:*:*"
+        );
+    }
+
+    #[test]
+    fn cpe_uri() {
+        let cpe: Cpe = "cpe:/a:redhat:openshift:4.11::el8".parse().unwrap();
+        assert_eq!(cpe.binding(), CpeBinding::Uri);
+        assert_eq!(cpe.product(), Some("openshift"));
+        assert_eq!(cpe.update(), None);
+        assert_eq!(cpe.edition(), Some("el8"));
+    }
+
+    #[test]
+    fn cpe_from_uri_builder() {
+        let uri = cpe::uri::Uri::builder()
+            .part("a")
+            .vendor("csaf_project")
+            .product("csaf")
+            .version("0.3.0")
+            .validate()
+            .unwrap()
+            .to_owned();
+        let cpe = Cpe::try_from(uri).unwrap();
+        assert_eq!(
+            cpe.as_str(),
+            "cpe:2.3:a:csaf_project:csaf:0.3.0:*:*:*:*:*:*:*"
+        );
+    }
+
+    #[test]
+    fn cpe_rejects_malformed() {
+        assert_eq!(
+            "cpe:2.4:a:vendor".parse::<Cpe>(),
+            Err(CpeParseError::InvalidPrefix)
+        );
+        assert_eq!(
+            "cpe:2.3:a:vendor:product".parse::<Cpe>(),
+            Err(CpeParseError::ComponentCount {
+                expected: 11,
+                found: 3
+            })
+        );
+        assert_eq!(
+            "cpe:2.3:x:vendor:product:*:*:*:*:*:*:*:*".parse::<Cpe>(),
+            Err(CpeParseError::InvalidComponent {
+                name: "part",
+                value: "x".to_string()
+            })
+        );
+        assert!("cpe:2.3:a:ven dor:product:*:*:*:*:*:*:*:*"
+            .parse::<Cpe>()
+            .is_err());
+        assert!("cpe:2.3:a:vendor:product:1.0+1:*:*:*:*:*:*:*"
+            .parse::<Cpe>()
+            .is_err());
+        assert!("cpe:/a:vendor:product:1.0 beta".parse::<Cpe>().is_err());
+    }
+
+    #[test]
+    fn purl_components() {
+        let purl: Purl = "pkg:cargo/serde@1.0.0".parse().unwrap();
+        assert_eq!(purl.ty(), "cargo");
+        assert_eq!(purl.namespace(), None);
+        assert_eq!(purl.name(), "serde");
+        assert_eq!(purl.version(), Some("1.0.0"));
+        assert_eq!(purl.to_string(), "pkg:cargo/serde@1.0.0");
+
+        let purl: Purl = "pkg:maven/org.apache.commons/io@1.3.4?classifier=sources#docs"
+            .parse()
+            .unwrap();
+        assert_eq!(purl.namespace(), Some("org.apache.commons"));
+        assert_eq!(
+            purl.qualifiers().collect::<Vec<_>>(),
+            vec![("classifier", "sources")]
+        );
+        assert_eq!(purl.subpath(), Some("docs"));
+    }
+
+    #[test]
+    fn purl_rejects_invalid() {
+        assert!("serde@1.0.0".parse::<Purl>().is_err());
+        assert!("pkg:cargo".parse::<Purl>().is_err());
+        assert!("pkg:car go/serde".parse::<Purl>().is_err());
+        assert!(serde_json::from_str::<ProductIdentificationHelper>(
+            r#"{"purl":"https://crates.io/serde"}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn lang_parses_bcp47() {
+        let lang: Lang = "en-US".parse().unwrap();
+        assert_eq!(lang.primary_language(), "en");
+        assert_eq!(lang.region(), Some("US"));
+        assert_eq!(lang.to_string(), "en-US");
+
+        let error = "english please".parse::<Lang>().unwrap_err();
+        assert_eq!(error.value, "english please");
+        assert!("en-".parse::<Lang>().is_err());
+    }
+
+    #[test]
+    fn cpe_roundtrips_json() {
+        let json = r#"{"cpe":"cpe:2.3:o:linux:linux_kernel:5.10:*:*:*:*:*:*:*"}"#;
+        let helper: ProductIdentificationHelper = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&helper).unwrap(), json);
+    }
+
+    #[test]
+    fn branches_sorted_by_version() {
+        let branch = |name: &str, category| Branch {
+            name: name.to_string(),
+            category,
+            product: None,
+            branches: None,
+        };
+        let branches = BranchesT(vec![
+            branch("1.10.0", BranchCategory::ProductVersion),
+            branch("1.2.0", BranchCategory::ProductVersion),
+            branch("2.0.0", BranchCategory::ProductName),
+            branch("not a version", BranchCategory::ProductVersion),
+            branch("1.2.0-rc.1", BranchCategory::ProductVersion),
+        ]);
+
+        let names: Vec<_> = branches
+            .sorted_by_version()
+            .into_iter()
+            .map(|b| b.name.as_str())
+            .collect();
+        assert_eq!(names, ["1.2.0-rc.1", "1.2.0", "1.10.0"]);
+        assert!(
+            "1.10.0".parse::<SemverVersion>().unwrap() > "1.9.0".parse::<SemverVersion>().unwrap()
+        );
+    }
+
+    #[test]
+    fn finds_nested_branches() {
+        let branch = |name: &str, category, children: Vec<Branch>| Branch {
+            name: name.to_string(),
+            category,
+            product: None,
+            branches: if children.is_empty() {
+                None
+            } else {
+                Some(BranchesT(children))
+            },
+        };
+        let branches = BranchesT(vec![
+            branch(
+                "Example",
+                BranchCategory::Vendor,
+                vec![
+                    branch(
+                        "Widget",
+                        BranchCategory::ProductName,
+                        vec![branch("1.0", BranchCategory::ProductVersion, vec![])],
+                    ),
+                    branch(
+                        "Gadget",
+                        BranchCategory::ProductName,
+                        vec![branch("2.0", BranchCategory::ProductVersion, vec![])],
+                    ),
+                ],
+            ),
+            branch("1.0", BranchCategory::Legacy, vec![]),
+        ]);
+
+        let found = branches.find_by_name("1.0").unwrap();
+        assert_eq!(found.category, BranchCategory::ProductVersion);
+        assert!(branches.find_by_name("Missing").is_none());
+        assert_eq!(
+            branches
+                .find_by_category(&BranchCategory::ProductName)
+                .unwrap()
+                .name,
+            "Widget"
+        );
+        let versions: Vec<_> = branches
+            .find_all_by_category(&BranchCategory::ProductVersion)
+            .map(|b| b.name.as_str())
+            .collect();
+        assert_eq!(versions, ["1.0", "2.0"]);
+
+        let mut branches = branches;
+        for branch in &mut branches {
+            branch.name.make_ascii_uppercase();
+        }
+        let top_level: Vec<_> = (&branches).into_iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(top_level, ["EXAMPLE", "1.0"]);
+        assert_eq!(branches.into_iter().count(), 2);
+    }
+
+    #[test]
+    fn empty_acknowledgment_is_invalid() {
+        let empty = Acknowledgment {
+            names: None,
+            organization: None,
+            summary: None,
+            urls: None,
+        };
+        assert_eq!(empty.validate(), Err(ValidationError::EmptyAcknowledgment));
+        assert_eq!(
+            Acknowledgment::builder().build(),
+            Err(ValidationError::EmptyAcknowledgment)
+        );
+        assert!(Acknowledgment::builder()
+            .url("https://example.com/".parse().unwrap())
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn empty_note_fields_are_invalid() {
+        let mut note = Note {
+            category: NoteCategory::Summary,
+            text: "Example summary".to_string(),
+            audience: None,
+            title: Some("Summary".to_string()),
+        };
+        assert_eq!(note.validate(), Ok(()));
+
+        note.audience = Some(String::new());
+        assert_eq!(
+            note.validate(),
+            Err(ValidationError::EmptyNoteField { field: "audience" })
+        );
+
+        note.text = " \n".to_string();
+        assert_eq!(
+            note.validate(),
+            Err(ValidationError::EmptyNoteField { field: "text" })
+        );
+    }
+
+    #[test]
+    fn branch_category_classifiers() {
+        assert!(BranchCategory::ProductVersion.is_leaf_category());
+        assert!(BranchCategory::ServicePack.is_leaf_category());
+        assert!(!BranchCategory::Vendor.is_leaf_category());
+        assert!(BranchCategory::ProductFamily.is_identification_category());
+        assert!(!BranchCategory::PatchLevel.is_identification_category());
+        assert!(!BranchCategory::Architecture.is_leaf_category());
+        assert!(!BranchCategory::Architecture.is_identification_category());
+    }
+
+    #[test]
+    fn package_helpers() {
+        let purl = |helper: Result<ProductIdentificationHelper, _>| {
+            helper.unwrap().purl.unwrap().to_string()
+        };
+        assert_eq!(
+            purl(ProductIdentificationHelper::for_cargo_crate(
+                "serde", "1.0.0"
+            )),
+            "pkg:cargo/serde@1.0.0"
+        );
+        assert_eq!(
+            purl(ProductIdentificationHelper::for_npm_package(
+                "@angular/core",
+                "16.0.0"
+            )),
+            "pkg:npm/%40angular/core@16.0.0"
+        );
+        assert_eq!(
+            purl(ProductIdentificationHelper::for_maven_artifact(
+                "org.apache.logging.log4j",
+                "log4j-core",
+                "2.17.1"
+            )),
+            "pkg:maven/org.apache.logging.log4j/log4j-core@2.17.1"
+        );
+        assert_eq!(
+            ProductIdentificationHelper::for_pypi_package("Django", "4.2")
+                .unwrap()
+                .purl
+                .unwrap()
+                .name(),
+            "django"
+        );
+
+        let missing_name = Err(PurlParseError(packageurl::Error::MissingName));
+        assert_eq!(
+            ProductIdentificationHelper::for_cargo_crate("", "1.0.0"),
+            missing_name
+        );
+        assert_eq!(
+            ProductIdentificationHelper::for_npm_package("@angular/", "16.0.0"),
+            missing_name
+        );
+    }
+
+    #[test]
+    fn product_ids_act_as_strings() {
+        let id: ProductIdT = "CSAFPID-0001".parse().unwrap();
+        assert_eq!(id.to_string(), "CSAFPID-0001");
+        assert_eq!(id.as_ref(), "CSAFPID-0001");
+        assert!(id.starts_with("CSAFPID-"));
+
+        let mut counts = HashMap::new();
+        *counts.entry(id.clone()).or_insert(0) += 1;
+        *counts.entry(id).or_insert(0) += 1;
+        assert_eq!(counts.len(), 1);
+    }
+
+    #[test]
+    fn primary_contact_prefers_organization() {
+        let acknowledgment = Acknowledgment::builder()
+            .name("Jane Doe")
+            .organization("Example Corp")
+            .build()
+            .unwrap();
+        assert_eq!(acknowledgment.primary_contact(), Some("Example Corp"));
+
+        let acknowledgment = Acknowledgment::builder().name("Jane Doe").build().unwrap();
+        assert_eq!(acknowledgment.primary_contact(), Some("Jane Doe"));
+
+        let acknowledgment = Acknowledgment::builder()
+            .summary("Reported")
+            .build()
+            .unwrap();
+        assert_eq!(acknowledgment.primary_contact(), None);
+    }
+}
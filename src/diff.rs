@@ -0,0 +1,311 @@
+//! Structured differences between two versions of a CSAF document, as returned by [Csaf::diff]
+//!
+//! Intended as the basis for the summary of a new [Revision](crate::document::Revision).
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    definitions::ProductIdT,
+    vulnerability::{ProductStatus, ProductStatusKind, Score, Vulnerability},
+    Csaf,
+};
+
+/// Changes from one document to another
+///
+/// Vulnerabilities are matched by their CVE, falling back to their first ID, then their title.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct CsafDiff {
+    pub added_vulnerabilities: Vec<String>,
+    pub removed_vulnerabilities: Vec<String>,
+    pub changed_vulnerabilities: Vec<VulnerabilityDiff>,
+    pub added_product_ids: Vec<ProductIdT>,
+    pub removed_product_ids: Vec<ProductIdT>,
+}
+
+impl CsafDiff {
+    /// True if no differences were found
+    pub fn is_empty(&self) -> bool {
+        self.added_vulnerabilities.is_empty()
+            && self.removed_vulnerabilities.is_empty()
+            && self.changed_vulnerabilities.is_empty()
+            && self.added_product_ids.is_empty()
+            && self.removed_product_ids.is_empty()
+    }
+}
+
+/// Changes to a vulnerability present in both documents
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VulnerabilityDiff {
+    pub vulnerability: String,
+    pub score_changes: Vec<ScoreChange>,
+    pub status_changes: Vec<StatusChange>,
+}
+
+/// A change to the CVSS vector scoring a product. The most recent CVSS version given for the product is compared.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoreChange {
+    pub product_id: ProductIdT,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// A change to the product status lists a product is listed under
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusChange {
+    pub product_id: ProductIdT,
+    pub before: Vec<ProductStatusKind>,
+    pub after: Vec<ProductStatusKind>,
+}
+
+pub(crate) fn diff(before: &Csaf, after: &Csaf) -> CsafDiff {
+    let mut output = CsafDiff::default();
+
+    let before_vulnerabilities = keyed_vulnerabilities(before);
+    let after_vulnerabilities = keyed_vulnerabilities(after);
+    for (key, old) in &before_vulnerabilities {
+        match after_vulnerabilities.get(key) {
+            None => output.removed_vulnerabilities.push(key.clone()),
+            Some(new) => {
+                let changes = VulnerabilityDiff {
+                    vulnerability: key.clone(),
+                    score_changes: changed(scores(old), scores(new))
+                        .map(|(product_id, before, after)| ScoreChange {
+                            product_id,
+                            before,
+                            after,
+                        })
+                        .collect(),
+                    status_changes: changed(statuses(old), statuses(new))
+                        .map(|(product_id, before, after)| StatusChange {
+                            product_id,
+                            before: before.unwrap_or_default(),
+                            after: after.unwrap_or_default(),
+                        })
+                        .collect(),
+                };
+                if !changes.score_changes.is_empty() || !changes.status_changes.is_empty() {
+                    output.changed_vulnerabilities.push(changes);
+                }
+            }
+        }
+    }
+    output.added_vulnerabilities = after_vulnerabilities
+        .keys()
+        .filter(|key| !before_vulnerabilities.contains_key(*key))
+        .cloned()
+        .collect();
+
+    let before_products = product_ids(before);
+    let after_products = product_ids(after);
+    output.added_product_ids = after_products
+        .difference(&before_products)
+        .map(|id| (*id).clone())
+        .collect();
+    output.removed_product_ids = before_products
+        .difference(&after_products)
+        .map(|id| (*id).clone())
+        .collect();
+
+    output
+}
+
+fn keyed_vulnerabilities(csaf: &Csaf) -> BTreeMap<String, &Vulnerability> {
+    csaf.vulnerabilities
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(index, vulnerability)| {
+            let key = vulnerability
+                .key()
+                .unwrap_or_else(|| format!("#{}", index + 1));
+            (key, vulnerability)
+        })
+        .collect()
+}
+
+fn product_ids(csaf: &Csaf) -> BTreeSet<&ProductIdT> {
+    csaf.all_product_ids().collect()
+}
+
+fn scores(vulnerability: &Vulnerability) -> BTreeMap<ProductIdT, String> {
+    let mut output = BTreeMap::new();
+    for score in vulnerability.scores.iter().flatten() {
+        if let Some(vector) = latest_vector(score) {
+            for product in &score.products {
+                output.insert(product.clone(), vector.clone());
+            }
+        }
+    }
+    output
+}
+
+fn latest_vector(score: &Score) -> Option<String> {
+    score
+        .cvss_v4
+        .as_ref()
+        .map(|v| v.to_string())
+        .or_else(|| score.cvss_v3.as_ref().map(|v| v.to_string()))
+        .or_else(|| score.cvss_v2.map(|v| v.to_string()))
+}
+
+fn statuses(vulnerability: &Vulnerability) -> BTreeMap<ProductIdT, Vec<ProductStatusKind>> {
+    vulnerability
+        .product_status
+        .as_ref()
+        .map(ProductStatus::by_product)
+        .unwrap_or_default()
+}
+
+/// Pairs up the values for each product that differ between `before` and `after`
+fn changed<T: PartialEq>(
+    mut before: BTreeMap<ProductIdT, T>,
+    mut after: BTreeMap<ProductIdT, T>,
+) -> impl Iterator<Item = (ProductIdT, Option<T>, Option<T>)> {
+    let products: BTreeSet<ProductIdT> = before.keys().chain(after.keys()).cloned().collect();
+    products.into_iter().filter_map(move |product| {
+        let old = before.remove(&product);
+        let new = after.remove(&product);
+        if old == new {
+            None
+        } else {
+            Some((product, old, new))
+        }
+    })
+}
+
+impl Display for CsafDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for vulnerability in &self.added_vulnerabilities {
+            writeln!(f, "Added vulnerability {}", vulnerability)?;
+        }
+        for vulnerability in &self.removed_vulnerabilities {
+            writeln!(f, "Removed vulnerability {}", vulnerability)?;
+        }
+        for product in &self.added_product_ids {
+            writeln!(f, "Added product {}", product.0)?;
+        }
+        for product in &self.removed_product_ids {
+            writeln!(f, "Removed product {}", product.0)?;
+        }
+        for vulnerability in &self.changed_vulnerabilities {
+            for change in &vulnerability.score_changes {
+                writeln!(
+                    f,
+                    "{}: score of {} changed from {} to {}",
+                    vulnerability.vulnerability,
+                    change.product_id.0,
+                    change.before.as_deref().unwrap_or("none"),
+                    change.after.as_deref().unwrap_or("none"),
+                )?;
+            }
+            for change in &vulnerability.status_changes {
+                writeln!(
+                    f,
+                    "{}: status of {} changed from {} to {}",
+                    vulnerability.vulnerability,
+                    change.product_id.0,
+                    join_statuses(&change.before),
+                    join_statuses(&change.after),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn join_statuses(statuses: &[ProductStatusKind]) -> String {
+    if statuses.is_empty() {
+        "none".to_string()
+    } else {
+        statuses
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::example;
+
+    #[test]
+    fn identical_documents() {
+        let diff = example().diff(&example());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn reports_status_and_score_changes() {
+        let before = example();
+        let mut after = example();
+        let vulnerability = &mut after.vulnerabilities.as_mut().unwrap()[0];
+        let status = vulnerability.product_status.as_mut().unwrap();
+        let moved = status.known_affected.as_mut().unwrap().remove(0);
+        status.fixed = Some(vec![moved.clone()]);
+        vulnerability.scores.as_mut().unwrap()[0].cvss_v3 = Some(
+            "CVSS:3.1/AV:L/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+                .parse()
+                .unwrap(),
+        );
+
+        let diff = before.diff(&after);
+        assert!(diff.added_vulnerabilities.is_empty());
+        assert_eq!(diff.changed_vulnerabilities.len(), 1);
+        let changes = &diff.changed_vulnerabilities[0];
+        assert_eq!(changes.vulnerability, "CVE-2018-0171");
+        assert_eq!(
+            changes.status_changes,
+            vec![StatusChange {
+                product_id: moved.clone(),
+                before: vec![ProductStatusKind::KnownAffected],
+                after: vec![ProductStatusKind::Fixed],
+            }]
+        );
+        assert!(!changes.score_changes.is_empty());
+        assert!(diff.to_string().contains(&format!(
+            "CVE-2018-0171: status of {} changed from known_affected to fixed",
+            moved.0
+        )));
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(
+            json["changed_vulnerabilities"][0]["status_changes"][0]["after"][0],
+            "fixed"
+        );
+    }
+
+    #[test]
+    fn reports_added_and_removed_items() {
+        let before = example();
+        let mut after = example();
+        after.vulnerabilities.as_mut().unwrap()[0].cve = Some("CVE-2099-0001".to_string());
+        after
+            .product_tree
+            .as_mut()
+            .unwrap()
+            .full_product_names
+            .get_or_insert_with(Vec::new)
+            .push(crate::definitions::FullProductName {
+                name: "New product".to_string(),
+                product_id: ProductIdT("CSAFPID-NEW".to_string()),
+                product_identification_helper: None,
+            });
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added_vulnerabilities, vec!["CVE-2099-0001"]);
+        assert_eq!(diff.removed_vulnerabilities, vec!["CVE-2018-0171"]);
+        assert_eq!(
+            diff.added_product_ids,
+            vec![ProductIdT("CSAFPID-NEW".to_string())]
+        );
+        assert!(diff.removed_product_ids.is_empty());
+    }
+}
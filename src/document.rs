@@ -1,16 +1,22 @@
-use std::{fmt::Display, str::FromStr};
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use url::Url;
 
-use crate::definitions::{AcknowledgmentsT, LangT, NotesT, ReferencesT, VersionT};
+use crate::{
+    definitions::{AcknowledgmentsT, Lang, NotesT, ReferencesT, VersionT},
+    validation::{
+        compare_versions, ValidationError, STRICT_TRACKING_ID_PATTERN, TRACKING_ID_PATTERN,
+    },
+};
 
 /// [Document level meta-data](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#321-document-property)
 #[serde_as]
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Document {
     /// [See Category specification](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3213-document-property---category)
     #[serde_as(as = "DisplayFromStr")]
@@ -18,19 +24,27 @@ pub struct Document {
     pub publisher: Publisher,
     pub title: String,
     pub tracking: Tracking,
+    #[serde_as(as = "DisplayFromStr")]
     pub csaf_version: CsafVersion,
     pub acknowledgments: Option<AcknowledgmentsT>,
     pub aggregate_severity: Option<AggregateSeverity>,
     pub distribution: Option<Distribution>,
-    pub lang: Option<LangT>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub lang: Option<Lang>,
     pub notes: Option<NotesT>,
     pub references: Option<ReferencesT>,
-    pub source_lang: Option<LangT>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub source_lang: Option<Lang>,
+    /// SPDX license expression for the document, added in CSAF 2.1
+    pub license_expression: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum Category {
     Base,
+    SecurityIncidentResponse,
+    InformationalAdvisory,
     SecurityAdvisory,
     Vex,
     Other(String),
@@ -38,17 +52,42 @@ pub enum Category {
 
 // TODO: Following feels repetitive, may be a more direct way to represent
 
+/// Category values written by CVRF-era tooling, compared ignoring case, and the variant each stands for
+const LEGACY_CATEGORIES: &[(&str, Category)] = &[
+    ("Security Advisory", Category::SecurityAdvisory),
+    ("Security_Advisory", Category::SecurityAdvisory),
+    ("Security Bulletin", Category::SecurityAdvisory),
+    ("Base", Category::Base),
+    ("VEX", Category::Vex),
+];
+
 impl FromStr for Category {
     type Err = std::convert::Infallible;
     // TODO: Should actually check regex for other since I'm doing this whole song and dance now anyway
     // ^[^\\s\\-_\\.](.*[^\\s\\-_\\.])?$
 
+    /// Legacy values such as `"Security Advisory"` are normalized to their variant, logging a deprecation warning
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
             "csaf_base" => Self::Base,
+            "csaf_security_incident_response" => Self::SecurityIncidentResponse,
+            "csaf_informational_advisory" => Self::InformationalAdvisory,
             "csaf_security_advisory" => Self::SecurityAdvisory,
             "csaf_vex" => Self::Vex,
-            _ => Self::Other(s.to_owned()),
+            _ => match LEGACY_CATEGORIES
+                .iter()
+                .find(|(legacy, _)| legacy.eq_ignore_ascii_case(s))
+            {
+                Some((_, category)) => {
+                    tracing::warn!(
+                        "deprecated document category {:?}, use {:?} instead",
+                        s,
+                        category.to_string()
+                    );
+                    category.clone()
+                }
+                None => Self::Other(s.to_owned()),
+            },
         })
     }
 }
@@ -57,6 +96,8 @@ impl Display for Category {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Base => write!(f, "csaf_base"),
+            Self::SecurityIncidentResponse => write!(f, "csaf_security_incident_response"),
+            Self::InformationalAdvisory => write!(f, "csaf_informational_advisory"),
             Self::SecurityAdvisory => write!(f, "csaf_security_advisory"),
             Self::Vex => write!(f, "csaf_vex"),
             Self::Other(s) => write!(f, "{}", s),
@@ -64,16 +105,66 @@ impl Display for Category {
     }
 }
 
+/// The [profiles](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#4-profiles)
+/// a document can conform to, see [Csaf::profile](crate::Csaf::profile)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsafProfile {
+    Base,
+    SecurityIncidentResponse,
+    InformationalAdvisory,
+    SecurityAdvisory,
+    Vex,
+}
+
 /// [CSAF Version](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3214-document-property---csaf-version)
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum CsafVersion {
-    #[serde(rename = "2.0")]
     TwoDotZero,
+    /// The CSAF 2.1 draft, enabled by the `csaf_2_1` feature
+    #[cfg(feature = "csaf_2_1")]
+    TwoDotOne,
 }
 
+impl FromStr for CsafVersion {
+    type Err = UnknownCsafVersion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2.0" => Ok(Self::TwoDotZero),
+            #[cfg(feature = "csaf_2_1")]
+            "2.1" => Ok(Self::TwoDotOne),
+            _ => Err(UnknownCsafVersion(s.to_string())),
+        }
+    }
+}
+
+impl Display for CsafVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TwoDotZero => write!(f, "2.0"),
+            #[cfg(feature = "csaf_2_1")]
+            Self::TwoDotOne => write!(f, "2.1"),
+        }
+    }
+}
+
+/// A `csaf_version` this crate does not support
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownCsafVersion(pub String);
+
+impl Display for UnknownCsafVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown CSAF version {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownCsafVersion {}
+
 /// [Publisher property](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3218-document-property---publisher)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Publisher {
     pub category: PublisherCategory,
     pub name: String,
@@ -85,6 +176,7 @@ pub struct Publisher {
 /// [Publisher category](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#32181-document-property---publisher---category)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
+#[non_exhaustive]
 pub enum PublisherCategory {
     Coordinator,
     Discoverer,
@@ -97,6 +189,7 @@ pub enum PublisherCategory {
 /// [Tracking metadata](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#32112-document-property---tracking)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Tracking {
     pub current_release_date: DateTime<Utc>,
     pub id: String,
@@ -108,9 +201,256 @@ pub struct Tracking {
     pub generator: Option<Generator>,
 }
 
+impl Tracking {
+    /// Records a new revision dated now, updating `version` and `current_release_date` to match.
+    ///
+    /// Integer versions are incremented by one. Semantic versions get a new major version once the document is final,
+    /// or only drop their pre-release if they have one, and a new minor version while it is a draft or interim. A
+    /// `version` that is neither is replaced by the number of revisions, as an integer version.
+    pub fn bump_revision(&mut self, summary: impl Into<String>) -> &mut Revision {
+        let now = Utc::now();
+        let number = match (
+            self.version.parse::<u64>(),
+            semver::Version::parse(&self.version),
+        ) {
+            (Ok(version), _) => (version + 1).to_string(),
+            (_, Ok(version)) => match self.status {
+                Status::Final if !version.pre.is_empty() => {
+                    semver::Version::new(version.major, version.minor, version.patch)
+                }
+                Status::Final => semver::Version::new(version.major + 1, 0, 0),
+                Status::Draft | Status::Interim => {
+                    semver::Version::new(version.major, version.minor + 1, 0)
+                }
+            }
+            .to_string(),
+            _ => (self.revision_history.len() + 1).to_string(),
+        };
+
+        self.version = number.clone();
+        self.current_release_date = now;
+        self.revision_history.push(Revision {
+            date: now,
+            legacy_version: None,
+            number,
+            summary: summary.into(),
+        });
+        self.revision_history.last_mut().expect("Was just pushed")
+    }
+
+    /// The revision with the highest version number, which is not necessarily the last one listed. Versions that
+    /// cannot be compared, such as an integer and a semantic version, are treated as equal.
+    pub fn latest_revision(&self) -> Option<&Revision> {
+        self.revision_history
+            .iter()
+            .max_by(|a, b| compare_versions(&a.number, &b.number).unwrap_or(Ordering::Equal))
+    }
+
+    /// The revision whose number is exactly `version`
+    pub fn revision_at(&self, version: &str) -> Option<&Revision> {
+        self.revision_history
+            .iter()
+            .find(|revision| revision.number == version)
+    }
+
+    pub fn is_draft(&self) -> bool {
+        self.status == Status::Draft
+    }
+
+    pub fn is_interim(&self) -> bool {
+        self.status == Status::Interim
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.status == Status::Final
+    }
+
+    /// Whether the document is final and one of its `aliases` has the form `ID<sep>VERSION`, where `ID` is the tracking
+    /// `id`, `<sep>` is one of `:`, `@`, `/` or a space, and `VERSION` is newer than `version`
+    pub fn is_superseded(&self) -> bool {
+        self.is_final()
+            && self.aliases.iter().flatten().any(|alias| {
+                alias
+                    .strip_prefix(self.id.as_str())
+                    .filter(|rest| rest.starts_with([':', '@', '/', ' ']))
+                    .map(|rest| rest[1..].trim_start())
+                    .and_then(|version| compare_versions(version, &self.version))
+                    == Some(Ordering::Greater)
+            })
+    }
+
+    /// Checks `id` against the mandatory pattern [TRACKING_ID_PATTERN]: non-empty, with no whitespace
+    pub fn validate_id_format(&self) -> Result<(), ValidationError> {
+        if self.id.is_empty() || self.id.chars().any(char::is_whitespace) {
+            return Err(ValidationError::InvalidTrackingId {
+                found: self.id.clone(),
+                expected_pattern: TRACKING_ID_PATTERN,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks `id` against the stricter, recommended pattern [STRICT_TRACKING_ID_PATTERN], after the mandatory one
+    ///
+    /// Many publishers use lowercase IDs, so this is only applied by [crate::Csaf::validate_with] when
+    /// [crate::validation::ValidationConfig::strict_tracking_id] is set.
+    pub fn validate_id_format_strict(&self) -> Result<(), ValidationError> {
+        self.validate_id_format()?;
+        let mut chars = self.id.chars();
+        let first_ok = chars.next().map_or(false, |c| c.is_ascii_uppercase());
+        let rest_ok =
+            chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' || c == '-');
+        if !first_ok || !rest_ok || self.id.len() > 200 {
+            return Err(ValidationError::InvalidTrackingId {
+                found: self.id.clone(),
+                expected_pattern: STRICT_TRACKING_ID_PATTERN,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Builder for [Tracking] which fills in `generator` and `current_release_date`
+///
+/// `current_release_date` is taken from the most recent [Revision] by date.
+///
+/// ```
+/// # use chrono::Utc;
+/// # use csaf::document::{Revision, Status, TrackingBuilder};
+/// let now = Utc::now();
+/// let tracking = TrackingBuilder::new()
+///     .id("CSAF-001")
+///     .status(Status::Draft)
+///     .version("1")
+///     .initial_release_date(now)
+///     .revision(Revision {
+///         date: now,
+///         legacy_version: None,
+///         number: "1".to_string(),
+///         summary: "Initial release".to_string(),
+///     })
+///     .build()
+///     .unwrap();
+/// assert!(tracking.generator.is_some());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackingBuilder {
+    id: Option<String>,
+    status: Option<Status>,
+    version: Option<VersionT>,
+    initial_release_date: Option<DateTime<Utc>>,
+    revision_history: Vec<Revision>,
+    aliases: Option<Vec<String>>,
+    generator: Option<Generator>,
+}
+
+impl TrackingBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn status(mut self, status: Status) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<VersionT>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn initial_release_date(mut self, date: DateTime<Utc>) -> Self {
+        self.initial_release_date = Some(date);
+        self
+    }
+
+    /// Appends a [Revision] to the revision history
+    pub fn revision(mut self, revision: Revision) -> Self {
+        self.revision_history.push(revision);
+        self
+    }
+
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.get_or_insert_with(Vec::new).push(alias.into());
+        self
+    }
+
+    /// Overrides the default [Generator], which identifies this crate
+    pub fn generator(mut self, generator: Generator) -> Self {
+        self.generator = Some(generator);
+        self
+    }
+
+    pub fn build(self) -> Result<Tracking, TrackingBuilderError> {
+        let id = self.id.ok_or(TrackingBuilderError::MissingField("id"))?;
+        let status = self
+            .status
+            .ok_or(TrackingBuilderError::MissingField("status"))?;
+        let version = self
+            .version
+            .ok_or(TrackingBuilderError::MissingField("version"))?;
+        let initial_release_date = self
+            .initial_release_date
+            .ok_or(TrackingBuilderError::MissingField("initial_release_date"))?;
+
+        let current_release_date = self
+            .revision_history
+            .iter()
+            .map(|r| r.date)
+            .max()
+            .ok_or(TrackingBuilderError::EmptyRevisionHistory)?;
+        if current_release_date < initial_release_date {
+            return Err(TrackingBuilderError::RevisionBeforeInitialRelease);
+        }
+
+        Ok(Tracking {
+            current_release_date,
+            id,
+            initial_release_date,
+            revision_history: self.revision_history,
+            status,
+            version,
+            aliases: self.aliases,
+            generator: Some(self.generator.unwrap_or_default()),
+        })
+    }
+}
+
+/// Reasons a [TrackingBuilder] could not produce a [Tracking]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrackingBuilderError {
+    /// A required field was never set
+    MissingField(&'static str),
+    /// No [Revision] was added, the spec requires at least one
+    EmptyRevisionHistory,
+    /// The latest [Revision] is dated before `initial_release_date`
+    RevisionBeforeInitialRelease,
+}
+
+impl Display for TrackingBuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "required field {} was not set", field),
+            Self::EmptyRevisionHistory => write!(f, "revision history must not be empty"),
+            Self::RevisionBeforeInitialRelease => write!(
+                f,
+                "latest revision is dated before the initial release date"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TrackingBuilderError {}
+
 /// [Document Generator](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#321123-document-property---tracking---generator)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Generator {
     pub engine: Engine,
     pub date: Option<DateTime<Utc>>,
@@ -119,6 +459,7 @@ pub struct Generator {
 /// [Generator Engine](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#321123-document-property---tracking---generator)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Engine {
     pub name: String,
     pub version: Option<String>,
@@ -141,6 +482,7 @@ impl std::default::Default for Generator {
 /// [Revision history](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#321126-document-property---tracking---revision-history)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Revision {
     pub date: DateTime<Utc>,
     pub legacy_version: Option<String>,
@@ -151,6 +493,7 @@ pub struct Revision {
 /// [Document status](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#321127-document-property---tracking---status)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
+#[non_exhaustive]
 pub enum Status {
     Draft,
     Final,
@@ -160,6 +503,7 @@ pub enum Status {
 /// [Aggregate severity](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3212-document-property---aggregate-severity)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct AggregateSeverity {
     pub text: String,
     pub namespace: Option<Url>,
@@ -168,25 +512,313 @@ pub struct AggregateSeverity {
 /// [Distribution](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3215-document-property---distribution)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Distribution {
-    // TODO: enforce 'with at least 1 of the 2 properties'
     pub text: Option<String>,
     pub tlp: Option<Tlp>,
 }
 
+impl Distribution {
+    pub fn new(text: impl Into<String>, tlp: Tlp) -> Self {
+        Self {
+            text: Some(text.into()),
+            tlp: Some(tlp),
+        }
+    }
+
+    pub fn new_with_text(text: impl Into<String>) -> Self {
+        Self {
+            text: Some(text.into()),
+            tlp: None,
+        }
+    }
+
+    pub fn new_with_tlp(tlp: Tlp) -> Self {
+        Self {
+            text: None,
+            tlp: Some(tlp),
+        }
+    }
+
+    /// Checks that at least one of `text` and `tlp` is set, as the spec requires. The constructors guarantee this,
+    /// but deserialized values may have neither.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.text.is_none() && self.tlp.is_none() {
+            Err(ValidationError::EmptyDistribution)
+        } else {
+            Ok(())
+        }
+    }
+}
+
 /// [TLP](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#32152-document-property---distribution---tlp)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Tlp {
     pub label: TlpLabel,
     pub url: Option<Url>,
 }
 
 /// [TLP](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#32152-document-property---distribution---tlp)
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+///
+/// Labels from both [TLP v1](https://www.first.org/tlp/v1/) and [TLP v2.0](https://www.first.org/tlp/) are accepted.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum TlpLabel {
     AMBER,
     GREEN,
     RED,
     WHITE,
+    #[serde(rename = "CLEAR")]
+    Clear,
+    #[serde(rename = "AMBER+STRICT")]
+    AmberStrict,
+}
+
+impl TlpLabel {
+    /// Which versions of TLP define this label
+    pub fn tlp_version(&self) -> TlpVersion {
+        match self {
+            Self::WHITE => TlpVersion::V1Only,
+            Self::Clear | Self::AmberStrict => TlpVersion::V2Only,
+            Self::AMBER | Self::GREEN | Self::RED => TlpVersion::Shared,
+        }
+    }
+
+    /// The equivalent TLP v2.0 label. `WHITE` was renamed to `CLEAR`, all other labels are unchanged.
+    pub fn to_tlp_v2(&self) -> TlpLabel {
+        match self {
+            Self::WHITE => Self::Clear,
+            label => *label,
+        }
+    }
+}
+
+/// The TLP versions a [TlpLabel] belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TlpVersion {
+    V1Only,
+    V2Only,
+    /// Defined identically in TLP v1 and v2.0
+    Shared,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn revision(date: DateTime<Utc>, number: &str) -> Revision {
+        Revision {
+            date,
+            legacy_version: None,
+            number: number.to_string(),
+            summary: "Summary".to_string(),
+        }
+    }
+
+    #[test]
+    fn csaf_version_strings() {
+        assert_eq!("2.0".parse::<CsafVersion>(), Ok(CsafVersion::TwoDotZero));
+        assert_eq!(CsafVersion::TwoDotZero.to_string(), "2.0");
+        assert_eq!(
+            "1.2".parse::<CsafVersion>(),
+            Err(UnknownCsafVersion("1.2".to_string()))
+        );
+    }
+
+    #[test]
+    fn legacy_categories_are_normalized() {
+        assert_eq!(
+            "Security Advisory".parse::<Category>().unwrap(),
+            Category::SecurityAdvisory
+        );
+        assert_eq!("vex".parse::<Category>().unwrap(), Category::Vex);
+        assert_eq!(
+            "csaf_informational_advisory".parse::<Category>().unwrap(),
+            Category::InformationalAdvisory
+        );
+        assert_eq!(
+            "x_internal_report".parse::<Category>().unwrap(),
+            Category::Other("x_internal_report".to_string())
+        );
+    }
+
+    #[test]
+    fn finds_latest_revision_by_version() {
+        let now = Utc::now();
+        let mut tracking = TrackingBuilder::new()
+            .id("CSAF-001")
+            .status(Status::Final)
+            .version("10")
+            .initial_release_date(now)
+            .revision(revision(now, "10"))
+            .revision(revision(now, "9"))
+            .build()
+            .unwrap();
+        assert_eq!(tracking.latest_revision().unwrap().number, "10");
+        assert_eq!(tracking.revision_at("9").unwrap().number, "9");
+        assert!(tracking.revision_at("11").is_none());
+
+        tracking.revision_history.clear();
+        assert!(tracking.latest_revision().is_none());
+    }
+
+    #[test]
+    fn superseded_by_newer_alias() {
+        let tracking = TrackingBuilder::new()
+            .id("EXAMPLE-2023-0001")
+            .status(Status::Final)
+            .version("2")
+            .initial_release_date(Utc::now())
+            .revision(revision(Utc::now(), "2"))
+            .build()
+            .unwrap();
+        assert!(tracking.is_final() && !tracking.is_draft() && !tracking.is_interim());
+        assert!(!tracking.is_superseded());
+
+        let mut superseded = tracking.clone();
+        superseded.aliases = Some(vec!["EXAMPLE-2023-0001:3".to_string()]);
+        assert!(superseded.is_superseded());
+        superseded.aliases = Some(vec!["EXAMPLE-2023-0001:1".to_string()]);
+        assert!(!superseded.is_superseded());
+        superseded.aliases = Some(vec!["EXAMPLE-2023-00013".to_string()]);
+        assert!(!superseded.is_superseded());
+        superseded.aliases = Some(vec!["EXAMPLE-2023-0001 3".to_string()]);
+        assert!(superseded.is_superseded());
+        superseded.aliases = Some(vec!["EXAMPLE-2023-0001 v3".to_string()]);
+        assert!(!superseded.is_superseded());
+        superseded.aliases = Some(vec!["EXAMPLE-2023-0001@3.0.0".to_string()]);
+        assert!(!superseded.is_superseded());
+        superseded.aliases = Some(vec!["EXAMPLE-2023-0001/3".to_string()]);
+        superseded.status = Status::Interim;
+        assert!(!superseded.is_superseded());
+    }
+
+    #[test]
+    fn tracking_builder_derives_current_release_date() {
+        let initial = Utc::now();
+        let later = initial + Duration::days(1);
+        let tracking = TrackingBuilder::new()
+            .id("CSAF-001")
+            .status(Status::Final)
+            .version("2")
+            .initial_release_date(initial)
+            .revision(revision(initial, "1"))
+            .revision(revision(later, "2"))
+            .build()
+            .unwrap();
+
+        assert_eq!(tracking.current_release_date, later);
+        assert_eq!(tracking.generator.unwrap().engine.name, "csaf-rs");
+    }
+
+    #[test]
+    fn tracking_builder_errors() {
+        let now = Utc::now();
+        let builder = TrackingBuilder::new()
+            .id("CSAF-001")
+            .status(Status::Final)
+            .initial_release_date(now);
+
+        assert_eq!(
+            builder.clone().build(),
+            Err(TrackingBuilderError::MissingField("version"))
+        );
+        assert_eq!(
+            builder.clone().version("1").build(),
+            Err(TrackingBuilderError::EmptyRevisionHistory)
+        );
+        assert_eq!(
+            builder
+                .version("1")
+                .revision(revision(now - Duration::days(1), "1"))
+                .build(),
+            Err(TrackingBuilderError::RevisionBeforeInitialRelease)
+        );
+    }
+
+    #[test]
+    fn tlp_labels() {
+        for (label, json, version) in [
+            (TlpLabel::WHITE, "\"WHITE\"", TlpVersion::V1Only),
+            (TlpLabel::Clear, "\"CLEAR\"", TlpVersion::V2Only),
+            (
+                TlpLabel::AmberStrict,
+                "\"AMBER+STRICT\"",
+                TlpVersion::V2Only,
+            ),
+            (TlpLabel::AMBER, "\"AMBER\"", TlpVersion::Shared),
+        ] {
+            assert_eq!(serde_json::to_string(&label).unwrap(), json);
+            assert_eq!(serde_json::from_str::<TlpLabel>(json).unwrap(), label);
+            assert_eq!(label.tlp_version(), version);
+        }
+
+        assert_eq!(TlpLabel::WHITE.to_tlp_v2(), TlpLabel::Clear);
+        assert_eq!(TlpLabel::RED.to_tlp_v2(), TlpLabel::RED);
+    }
+
+    #[test]
+    fn bump_revision() {
+        let now = Utc::now();
+        let mut tracking = TrackingBuilder::new()
+            .id("CSAF-001")
+            .status(Status::Final)
+            .version("1")
+            .initial_release_date(now)
+            .revision(revision(now, "1"))
+            .build()
+            .unwrap();
+
+        tracking.bump_revision("Second").legacy_version = Some("legacy".to_string());
+        assert_eq!(tracking.version, "2");
+        assert_eq!(tracking.revision_history.len(), 2);
+        assert_eq!(tracking.revision_history[1].number, "2");
+        assert_eq!(tracking.revision_history[1].summary, "Second");
+        assert_eq!(
+            tracking.revision_history[1].legacy_version.as_deref(),
+            Some("legacy")
+        );
+        assert_eq!(
+            tracking.current_release_date,
+            tracking.revision_history[1].date
+        );
+
+        tracking.version = "1.2.3".to_string();
+        tracking.bump_revision("Major");
+        assert_eq!(tracking.version, "2.0.0");
+        tracking.status = Status::Draft;
+        tracking.bump_revision("Minor");
+        assert_eq!(tracking.version, "2.1.0");
+
+        tracking.version = "1.0.0-rc.1".to_string();
+        tracking.status = Status::Final;
+        tracking.bump_revision("Release");
+        assert_eq!(tracking.version, "1.0.0");
+    }
+
+    #[test]
+    fn distribution_requires_a_property() {
+        assert_eq!(
+            Distribution {
+                text: None,
+                tlp: None
+            }
+            .validate(),
+            Err(ValidationError::EmptyDistribution)
+        );
+        assert_eq!(
+            Distribution::new_with_text("Internal use").validate(),
+            Ok(())
+        );
+        let tlp = Tlp {
+            label: TlpLabel::Clear,
+            url: None,
+        };
+        assert_eq!(Distribution::new_with_tlp(tlp.clone()).validate(), Ok(()));
+        assert_eq!(Distribution::new("Internal use", tlp).validate(), Ok(()));
+    }
 }
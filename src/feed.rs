@@ -0,0 +1,491 @@
+//! Generation of the files a [CSAF provider](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#72-roles)
+//! publishes alongside its documents: `provider-metadata.json`, `index.txt`, `changes.csv` and the ROLIE feed.
+
+use std::{
+    fmt::Display,
+    path::{Component, Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+    definitions::NoteCategory,
+    document::{CsafVersion, Publisher},
+    Csaf,
+};
+
+/// The first file [CsafFeedIndex::write_to_dir] failed to write and why
+#[derive(Debug)]
+pub enum FeedWriteError {
+    /// A document filename is absolute or leaves the directory, so nothing was written
+    InvalidFileName { file_name: String },
+    /// The file or its parent directory could not be written
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The contents of the file could not be serialized
+    Json {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+impl Display for FeedWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFileName { file_name } => {
+                write!(f, "file name {:?} is outside the feed directory", file_name)
+            }
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Json { path, source } => write!(f, "{}: {}", path.display(), source),
+        }
+    }
+}
+
+impl std::error::Error for FeedWriteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidFileName { .. } => None,
+            Self::Io { source, .. } => Some(source),
+            Self::Json { source, .. } => Some(source),
+        }
+    }
+}
+
+/// [provider-metadata.json](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#7120-requirement-20-provider-metadatajson)
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProviderMetadata {
+    pub canonical_url: Url,
+    pub distributions: Option<Vec<DistributionDirectory>>,
+    pub last_updated: DateTime<Utc>,
+    #[serde(rename = "list_on_CSAF_aggregators")]
+    pub list_on_csaf_aggregators: bool,
+    pub metadata_version: MetadataVersion,
+    #[serde(rename = "mirror_on_CSAF_aggregators")]
+    pub mirror_on_csaf_aggregators: bool,
+    pub publisher: Publisher,
+    pub role: Role,
+}
+
+/// A directory of documents listed in [ProviderMetadata]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DistributionDirectory {
+    pub directory_url: Url,
+}
+
+/// Version of the provider metadata format
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum MetadataVersion {
+    #[serde(rename = "2.0")]
+    TwoDotZero,
+}
+
+/// [Roles](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#72-roles) of a party
+/// distributing CSAF documents
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    CsafPublisher,
+    CsafProvider,
+    CsafTrustedProvider,
+}
+
+/// The metadata and index files for a directory of CSAF documents
+///
+/// Filenames are relative to the directory, such as `2018/cisco-sa-20180328-smi2.json`.
+///
+/// ```
+/// # use csaf::{Csaf, feed::CsafFeedIndex};
+/// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../tests/CVE-2018-0171-modified.json")).unwrap();
+/// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+/// let csaf: Csaf = serde_json::from_value(json).unwrap();
+/// let documents = [("2018/cisco-sa-20180328-smi2.json", &csaf)];
+/// let feed = CsafFeedIndex::new(
+///     "https://example.com/.well-known/csaf/provider-metadata.json".parse().unwrap(),
+///     csaf.document.publisher.clone(),
+///     &documents,
+/// );
+/// assert_eq!(feed.index_txt(), "2018/cisco-sa-20180328-smi2.json\n");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsafFeedIndex<'a> {
+    pub provider_metadata: ProviderMetadata,
+    documents: &'a [(&'a str, &'a Csaf)],
+}
+
+impl<'a> CsafFeedIndex<'a> {
+    /// The documents are taken to be in the same directory as `canonical_url`, the URL `provider-metadata.json` will
+    /// be published at. The metadata defaults to the `csaf_provider` role and allows aggregators to list and mirror the
+    /// documents, which can be changed through `provider_metadata`.
+    pub fn new(
+        canonical_url: Url,
+        publisher: Publisher,
+        documents: &'a [(&'a str, &'a Csaf)],
+    ) -> Self {
+        let directory_url = canonical_url.join("./").expect("Relative to a valid URL");
+        let last_updated = documents
+            .iter()
+            .map(|(_, csaf)| csaf.document.tracking.current_release_date)
+            .max()
+            .unwrap_or_else(Utc::now);
+        Self {
+            provider_metadata: ProviderMetadata {
+                canonical_url,
+                distributions: Some(vec![DistributionDirectory { directory_url }]),
+                last_updated,
+                list_on_csaf_aggregators: true,
+                metadata_version: MetadataVersion::TwoDotZero,
+                mirror_on_csaf_aggregators: true,
+                publisher,
+                role: Role::CsafProvider,
+            },
+            documents,
+        }
+    }
+
+    /// [index.txt](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#7112-requirement-12-indextxt),
+    /// listing every filename in sorted order
+    pub fn index_txt(&self) -> String {
+        let mut filenames: Vec<_> = self
+            .documents
+            .iter()
+            .map(|(filename, _)| *filename)
+            .collect();
+        filenames.sort_unstable();
+        filenames
+            .into_iter()
+            .map(|filename| format!("{}\n", filename))
+            .collect()
+    }
+
+    /// [changes.csv](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#7113-requirement-13-changescsv),
+    /// listing every filename with its current release date, most recently changed first
+    pub fn changes_csv(&self) -> String {
+        let mut changes: Vec<_> = self
+            .documents
+            .iter()
+            .map(|(filename, csaf)| (*filename, csaf.document.tracking.current_release_date))
+            .collect();
+        changes.sort_by(|(a_name, a_date), (b_name, b_date)| {
+            b_date.cmp(a_date).then_with(|| a_name.cmp(b_name))
+        });
+        changes
+            .into_iter()
+            .map(|(filename, date)| format!("\"{}\",\"{}\"\n", filename, date.to_rfc3339()))
+            .collect()
+    }
+
+    /// Writes the documents, `provider-metadata.json`, `index.txt` and `changes.csv` to `path`, creating
+    /// subdirectories for the documents as needed
+    ///
+    /// Fails without writing anything if a document filename is absolute or contains `..`.
+    pub fn write_to_dir(&self, path: &Path) -> Result<(), FeedWriteError> {
+        if let Some((filename, _)) = self.documents.iter().find(|(filename, _)| {
+            !Path::new(filename)
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        }) {
+            return Err(FeedWriteError::InvalidFileName {
+                file_name: filename.to_string(),
+            });
+        }
+
+        let io_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| FeedWriteError::Io { path, source }
+        };
+        let json_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| FeedWriteError::Json { path, source }
+        };
+
+        for (filename, csaf) in self.documents {
+            let file = path.join(filename);
+            if let Some(parent) = file.parent() {
+                std::fs::create_dir_all(parent).map_err(io_error(parent))?;
+            }
+            let json = csaf.to_pretty_json().map_err(json_error(&file))?;
+            std::fs::write(&file, json).map_err(io_error(&file))?;
+        }
+
+        let metadata = path.join("provider-metadata.json");
+        let json =
+            serde_json::to_string_pretty(&self.provider_metadata).map_err(json_error(&metadata))?;
+        std::fs::write(&metadata, json).map_err(io_error(&metadata))?;
+
+        let index = path.join("index.txt");
+        std::fs::write(&index, self.index_txt()).map_err(io_error(&index))?;
+        let changes = path.join("changes.csv");
+        std::fs::write(&changes, self.changes_csv()).map_err(io_error(&changes))
+    }
+}
+
+/// A [ROLIE feed](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#7115-requirement-15-rolie-feed)
+/// of CSAF documents, in the JSON format of [RFC 8322](https://www.rfc-editor.org/rfc/rfc8322)
+///
+/// ```
+/// # use csaf::{Csaf, feed::{CsafFeedIndex, CsafRolieFeed}};
+/// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../tests/CVE-2018-0171-modified.json")).unwrap();
+/// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+/// let csaf: Csaf = serde_json::from_value(json).unwrap();
+/// let metadata = CsafFeedIndex::new(
+///     "https://example.com/.well-known/csaf/provider-metadata.json".parse().unwrap(),
+///     csaf.document.publisher.clone(),
+///     &[],
+/// )
+/// .provider_metadata;
+/// let mut feed = CsafRolieFeed::new(metadata);
+/// feed.add_entry(
+///     "https://example.com/.well-known/csaf/2018/cisco-sa-20180328-smi2.json".parse().unwrap(),
+///     &csaf,
+/// );
+/// let json = feed.to_json();
+/// assert_eq!(json["feed"]["entry"][0]["id"], "cisco-sa-20180328-smi2");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsafRolieFeed {
+    pub provider_metadata: ProviderMetadata,
+    /// Defaults to `csaf-feed`
+    pub id: String,
+    /// Defaults to the publisher's name followed by `CSAF feed`
+    pub title: String,
+    /// The URL the feed will be published at, by default `feed.json` next to `provider-metadata.json`
+    pub url: Url,
+    entries: Vec<RolieEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RolieEntry {
+    url: Url,
+    id: String,
+    title: String,
+    published: DateTime<Utc>,
+    updated: DateTime<Utc>,
+    summary: Option<String>,
+    version: CsafVersion,
+}
+
+impl CsafRolieFeed {
+    pub fn new(provider_metadata: ProviderMetadata) -> Self {
+        let url = provider_metadata
+            .canonical_url
+            .join("feed.json")
+            .expect("Relative to a valid URL");
+        Self {
+            id: "csaf-feed".to_string(),
+            title: format!("{} CSAF feed", provider_metadata.publisher.name),
+            url,
+            provider_metadata,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds the document published at `url`. The entry's summary is the first `summary` note of the document, if any.
+    pub fn add_entry(&mut self, url: Url, document: &Csaf) {
+        let tracking = &document.document.tracking;
+        let summary = document
+            .document
+            .notes
+            .iter()
+            .flatten()
+            .find(|note| note.category == NoteCategory::Summary)
+            .map(|note| note.text.clone());
+        self.entries.push(RolieEntry {
+            url,
+            id: tracking.id.clone(),
+            title: document.document.title.clone(),
+            published: tracking.initial_release_date,
+            updated: tracking.current_release_date,
+            summary,
+            version: document.document.csaf_version.clone(),
+        });
+    }
+
+    /// The feed with its entries in the order they were added. The feed is updated as of its most recently updated
+    /// entry, or `last_updated` of the provider metadata when it has none.
+    pub fn to_json(&self) -> Value {
+        let updated = self
+            .entries
+            .iter()
+            .map(|entry| entry.updated)
+            .max()
+            .unwrap_or(self.provider_metadata.last_updated);
+        let entries: Vec<Value> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut json = json!({
+                    "id": entry.id,
+                    "title": entry.title,
+                    "link": [{ "rel": "self", "href": entry.url.as_str() }],
+                    "published": entry.published,
+                    "updated": entry.updated,
+                    "content": { "type": "application/json", "src": entry.url.as_str() },
+                    "format": {
+                        "schema": schema_url(&entry.version),
+                        "version": entry.version.to_string(),
+                    },
+                });
+                if let Some(summary) = &entry.summary {
+                    json["summary"] = json!({ "content": summary });
+                }
+                json
+            })
+            .collect();
+
+        json!({
+            "feed": {
+                "id": self.id,
+                "title": self.title,
+                "link": [{ "rel": "self", "href": self.url.as_str() }],
+                "category": [{
+                    "scheme": "urn:ietf:params:rolie:category:information-type",
+                    "term": "csaf",
+                }],
+                "updated": updated,
+                "entry": entries,
+            }
+        })
+    }
+}
+
+/// The JSON schema of the CSAF version, as given in a ROLIE entry's `format`
+fn schema_url(version: &CsafVersion) -> &'static str {
+    match version {
+        CsafVersion::TwoDotZero => {
+            "https://docs.oasis-open.org/csaf/csaf/v2.0/csaf_json_schema.json"
+        }
+        #[cfg(feature = "csaf_2_1")]
+        CsafVersion::TwoDotOne => "https://docs.oasis-open.org/csaf/csaf/v2.1/schema/csaf.json",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::example;
+    use chrono::Duration;
+
+    #[test]
+    fn generates_feed_files() {
+        let older = example();
+        let mut newer = example();
+        newer.document.tracking.current_release_date += Duration::days(1);
+        let documents = [("2018/b.json", &older), ("2018/a.json", &newer)];
+        let feed = CsafFeedIndex::new(
+            "https://example.com/.well-known/csaf/provider-metadata.json"
+                .parse()
+                .unwrap(),
+            older.document.publisher.clone(),
+            &documents,
+        );
+
+        assert_eq!(feed.index_txt(), "2018/a.json\n2018/b.json\n");
+        assert!(feed.changes_csv().starts_with("\"2018/a.json\","));
+        assert_eq!(
+            feed.provider_metadata.last_updated,
+            newer.document.tracking.current_release_date
+        );
+
+        let json = serde_json::to_value(&feed.provider_metadata).unwrap();
+        assert_eq!(json["metadata_version"], "2.0");
+        assert_eq!(json["role"], "csaf_provider");
+        assert_eq!(json["list_on_CSAF_aggregators"], true);
+        assert_eq!(
+            json["distributions"][0]["directory_url"],
+            "https://example.com/.well-known/csaf/"
+        );
+
+        let directory = tempfile::tempdir().unwrap();
+        feed.write_to_dir(directory.path()).unwrap();
+        for file in [
+            "provider-metadata.json",
+            "index.txt",
+            "changes.csv",
+            "2018/a.json",
+        ] {
+            assert!(directory.path().join(file).is_file(), "{} missing", file);
+        }
+        assert_eq!(
+            Csaf::from_json_file(directory.path().join("2018/a.json")).unwrap(),
+            newer
+        );
+
+        let target = tempfile::tempdir().unwrap();
+        for filename in [
+            "../escaped.json",
+            "/tmp/absolute.json",
+            "2018/../../up.json",
+        ] {
+            let documents = [("2018/a.json", &newer), (filename, &older)];
+            let feed = CsafFeedIndex::new(
+                "https://example.com/.well-known/csaf/provider-metadata.json"
+                    .parse()
+                    .unwrap(),
+                older.document.publisher.clone(),
+                &documents,
+            );
+            let target = target.path().join("feed");
+            assert!(matches!(
+                feed.write_to_dir(&target),
+                Err(FeedWriteError::InvalidFileName { file_name }) if file_name == filename
+            ));
+            assert!(!target.exists());
+        }
+    }
+
+    #[test]
+    fn rolie_feed_entries() {
+        let older = example();
+        let mut newer = example();
+        newer.document.tracking.id = "NEWER".to_string();
+        newer.document.tracking.current_release_date += Duration::days(1);
+        let metadata = CsafFeedIndex::new(
+            "https://example.com/.well-known/csaf/provider-metadata.json"
+                .parse()
+                .unwrap(),
+            older.document.publisher.clone(),
+            &[],
+        )
+        .provider_metadata;
+
+        let mut feed = CsafRolieFeed::new(metadata);
+        let json = feed.to_json();
+        assert_eq!(
+            json["feed"]["link"][0]["href"],
+            "https://example.com/.well-known/csaf/feed.json"
+        );
+        assert_eq!(json["feed"]["entry"].as_array().unwrap().len(), 0);
+
+        feed.add_entry(
+            "https://example.com/.well-known/csaf/2018/older.json"
+                .parse()
+                .unwrap(),
+            &older,
+        );
+        feed.add_entry(
+            "https://example.com/.well-known/csaf/2018/newer.json"
+                .parse()
+                .unwrap(),
+            &newer,
+        );
+        let json = feed.to_json();
+        assert_eq!(json["feed"]["category"][0]["term"], "csaf");
+        assert_eq!(
+            json["feed"]["updated"],
+            json!(newer.document.tracking.current_release_date)
+        );
+        let entry = &json["feed"]["entry"][1];
+        assert_eq!(entry["id"], "NEWER");
+        assert_eq!(
+            entry["content"]["src"],
+            "https://example.com/.well-known/csaf/2018/newer.json"
+        );
+        assert_eq!(entry["format"]["version"], "2.0");
+    }
+}
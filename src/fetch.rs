@@ -0,0 +1,93 @@
+//! Fetching documents from a distributor over HTTP, enabled by the `async` feature
+//!
+//! [Csaf::fetch] is async and needs to be run on a [tokio](https://tokio.rs/) runtime, as required by `reqwest`.
+
+use std::fmt::Display;
+
+use reqwest::{header::CONTENT_TYPE, StatusCode};
+use url::Url;
+
+use crate::Csaf;
+
+/// Failure to fetch a document, separating transport failures from bad responses and invalid documents
+#[derive(Debug)]
+pub enum FetchError {
+    /// The request could not be sent or the body could not be read
+    Network(reqwest::Error),
+    /// The server responded with a status other than 2xx
+    Status(StatusCode),
+    /// The response was not `application/json`, with the content type received if any
+    ContentType(Option<String>),
+    /// The body was not a valid CSAF document
+    Json(serde_json::Error),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Network(source) => write!(f, "failed to fetch document: {}", source),
+            Self::Status(status) => write!(f, "server responded with {}", status),
+            Self::ContentType(Some(content_type)) => {
+                write!(f, "expected application/json, got {}", content_type)
+            }
+            Self::ContentType(None) => write!(f, "expected application/json, got no content type"),
+            Self::Json(source) => write!(f, "invalid CSAF JSON: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Network(source) => Some(source),
+            Self::Json(source) => Some(source),
+            Self::Status(_) | Self::ContentType(_) => None,
+        }
+    }
+}
+
+impl Csaf {
+    /// Downloads and deserializes the document at `url`, which must be served as `application/json` with a 2xx status
+    pub async fn fetch(url: &Url) -> Result<Csaf, FetchError> {
+        let response = reqwest::get(url.clone())
+            .await
+            .map_err(FetchError::Network)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(FetchError::Status(status));
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        if !content_type.as_deref().map_or(false, is_json) {
+            return Err(FetchError::ContentType(content_type));
+        }
+
+        let body = response.bytes().await.map_err(FetchError::Network)?;
+        serde_json::from_slice(&body).map_err(FetchError::Json)
+    }
+}
+
+/// Whether the media type, ignoring parameters such as `charset`, is `application/json`
+fn is_json(content_type: &str) -> bool {
+    content_type.split(';').next().map_or(false, |media_type| {
+        media_type.trim().eq_ignore_ascii_case("application/json")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_content_types() {
+        assert!(is_json("application/json"));
+        assert!(is_json("Application/JSON; charset=utf-8"));
+        assert!(!is_json("text/html"));
+        assert!(!is_json("application/jsonx"));
+    }
+}
@@ -0,0 +1,122 @@
+//! Lookup tables over a document, for processing large documents or many lookups without repeatedly scanning
+//! `vulnerabilities` and the `product_tree`
+
+use std::collections::HashMap;
+
+use crate::{
+    definitions::{FullProductName, ProductIdT},
+    vulnerability::Vulnerability,
+    Csaf,
+};
+
+/// Products and vulnerabilities of a [Csaf] document, indexed by product ID and CVE
+///
+/// The index borrows from the document, so it reflects the document as it was when the index was built.
+///
+/// ```
+/// # use csaf::{Csaf, definitions::ProductIdT, index::CsafIndex};
+/// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../tests/CVE-2018-0171-modified.json")).unwrap();
+/// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+/// let csaf: Csaf = serde_json::from_value(json).unwrap();
+/// let index = CsafIndex::from_csaf(&csaf);
+/// let vulnerability = index.for_cve("CVE-2018-0171").unwrap();
+/// let product = &vulnerability.product_status.as_ref().unwrap().known_affected.as_ref().unwrap()[0];
+/// assert_eq!(index.for_product(product), [vulnerability]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsafIndex<'a> {
+    products: HashMap<&'a ProductIdT, &'a FullProductName>,
+    vulnerabilities_by_product: HashMap<&'a ProductIdT, Vec<&'a Vulnerability>>,
+    vulnerabilities_by_cve: HashMap<&'a str, &'a Vulnerability>,
+}
+
+impl<'a> CsafIndex<'a> {
+    pub fn from_csaf(csaf: &'a Csaf) -> Self {
+        let mut index = Self::default();
+
+        // The first definition of a product wins, as in ProductTree::lookup_product
+        for product in csaf
+            .product_tree
+            .iter()
+            .flat_map(|tree| tree.defined_products())
+        {
+            index.products.entry(&product.product_id).or_insert(product);
+        }
+
+        for vulnerability in csaf.vulnerabilities.iter().flatten() {
+            if let Some(cve) = &vulnerability.cve {
+                index
+                    .vulnerabilities_by_cve
+                    .entry(cve.as_str())
+                    .or_insert(vulnerability);
+            }
+            for (_, id) in vulnerability
+                .product_status
+                .iter()
+                .flat_map(|status| status.entries())
+            {
+                let vulnerabilities = index.vulnerabilities_by_product.entry(id).or_default();
+                // A product may be listed under several statuses of the same vulnerability
+                if !vulnerabilities
+                    .last()
+                    .map_or(false, |last| std::ptr::eq(*last, vulnerability))
+                {
+                    vulnerabilities.push(vulnerability);
+                }
+            }
+        }
+
+        index
+    }
+
+    /// The product with the given ID, as defined in the product tree
+    pub fn product(&self, id: &ProductIdT) -> Option<&'a FullProductName> {
+        self.products.get(id).copied()
+    }
+
+    /// The vulnerabilities listing the product in their product status, in document order
+    pub fn for_product(&self, id: &ProductIdT) -> &[&'a Vulnerability] {
+        self.vulnerabilities_by_product
+            .get(id)
+            .map_or(&[], |vulnerabilities| vulnerabilities.as_slice())
+    }
+
+    /// The first vulnerability with the given CVE
+    pub fn for_cve(&self, cve: &str) -> Option<&'a Vulnerability> {
+        self.vulnerabilities_by_cve.get(cve).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agrees_with_linear_lookups() {
+        let mut csaf = crate::tests::fixtures::example();
+        let mut second = csaf.vulnerabilities.as_ref().unwrap()[0].clone();
+        second.cve = Some("CVE-2099-0001".to_string());
+        csaf.vulnerabilities.as_mut().unwrap().push(second);
+        let index = CsafIndex::from_csaf(&csaf);
+
+        let tree = csaf.product_tree.as_ref().unwrap();
+        for id in csaf.all_product_ids() {
+            assert_eq!(index.product(id), tree.lookup_product(id));
+            let expected: Vec<_> = csaf
+                .vulnerabilities_for_product(id)
+                .map(|(vulnerability, _)| vulnerability)
+                .collect();
+            assert_eq!(index.for_product(id), expected.as_slice());
+        }
+
+        assert_eq!(
+            index.for_cve("CVE-2099-0001"),
+            csaf.vulnerabilities.as_ref().unwrap().get(1)
+        );
+        assert_eq!(index.for_cve("CVE-2000-0000"), None);
+        assert!(index
+            .for_product(&ProductIdT("CSAFPID-MISSING".to_string()))
+            .is_empty());
+        assert_eq!(index, CsafIndex::from_csaf(&csaf));
+    }
+}
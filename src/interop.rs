@@ -1,3 +1,65 @@
+#[cfg(feature = "osv-interop")]
+pub mod osv;
+
+#[cfg(feature = "ghsa")]
+pub mod ghsa;
+
+#[cfg(feature = "cvrf-interop")]
+pub mod cvrf;
+
+#[cfg(feature = "nvd")]
+pub mod nvd;
+
+#[cfg(feature = "sarif")]
+pub mod sarif;
+
+#[cfg(feature = "markdown-interop")]
+pub mod markdown;
+
+use crate::definitions::{Branch, BranchCategory, BranchesT};
+
+/// Finds or creates the branch with `name` and `category` in `branches`, returning its children
+pub(crate) fn child_branches<'a>(
+    branches: &'a mut Vec<Branch>,
+    name: &str,
+    category: BranchCategory,
+) -> &'a mut Vec<Branch> {
+    let index = match branches
+        .iter()
+        .position(|branch| branch.name == name && branch.category == category)
+    {
+        Some(index) => index,
+        None => {
+            branches.push(Branch {
+                name: name.to_string(),
+                category,
+                product: None,
+                branches: None,
+            });
+            branches.len() - 1
+        }
+    };
+    &mut branches[index]
+        .branches
+        .get_or_insert_with(|| BranchesT(Vec::new()))
+        .0
+}
+
+#[cfg(any(
+    feature = "osv-interop",
+    feature = "ghsa",
+    feature = "cvrf-interop",
+    feature = "nvd",
+    feature = "markdown-interop"
+))]
+pub(crate) fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+    if items.is_empty() {
+        None
+    } else {
+        Some(items)
+    }
+}
+
 #[cfg(feature = "rustsec-interop")]
 pub mod rustsec {
     use std::convert::TryInto;
@@ -13,12 +75,12 @@ pub mod rustsec {
         },
         product_tree::ProductTree,
         vulnerability::{
-            ProductStatus, Remediation, RemediationCategory, Score, Vulnerability, VulnerabilityId,
+            ProductStatus, Remediation, RemediationCategory, Remediations, Score, Vulnerability,
+            VulnerabilityId,
         },
         Csaf,
     };
     use chrono::{TimeZone, Utc};
-    use packageurl::PackageUrl;
     use rustsec::{advisory::Versions, registry::IndexPackage, Advisory};
     use url::Url;
 
@@ -33,6 +95,7 @@ pub mod rustsec {
     /// Currently functioning and passes validation as a CSAF. Is not strictly valid VEX. VEX requires that each `known_not_affected` product
     /// have an impact statement listed as a [Threat](crate::vulnerability::Threat) with [ThreatCategory](crate::vulnerability::ThreatCategory) `Impact`.
     /// RustSec does not have any metadata that "contain(s) a description why the vulnerability cannot be exploited".
+    /// [Vulnerability::is_vex_compliant] reports these products as missing an impact statement.
     impl From<Advisory> for Csaf {
         fn from(input: Advisory) -> Self {
             let advisory_date = input.metadata.date;
@@ -110,6 +173,7 @@ pub mod rustsec {
                         )
                     },
                     source_lang: None,
+                    license_expression: None,
                 },
                 product_tree: Some(ProductTree {
                     branches: Some(BranchesT(vec![Branch {
@@ -118,9 +182,7 @@ pub mod rustsec {
                         product: None,
                         branches: Some(branches.all()),
                     }])),
-                    full_product_names: None,
-                    product_groups: None,
-                    relationships: None,
+                    ..Default::default()
                 }),
                 vulnerabilities: Some(vec![Vulnerability {
                     acknowledgments: None,
@@ -151,19 +213,15 @@ pub mod rustsec {
                         title: None,
                     }]),
                     product_status: Some(ProductStatus {
-                        first_affected: None,
-                        first_fixed: None,
                         fixed: branches.patched.product_ids(),
                         known_affected: branches.vulnerable.product_ids(),
                         known_not_affected: branches.unaffected.product_ids(),
-                        last_affected: None,
-                        recommended: None,
-                        under_investigation: None,
+                        ..Default::default()
                     }),
                     references: None,
                     release_date: None,
                     remediations: if !branches.patched.0.is_empty() {
-                        Some(vec![Remediation {
+                        Some(Remediations(vec![Remediation {
                             category: RemediationCategory::VendorFix,
                             details: "Updated crate versions available".to_string(),
                             date: None,
@@ -172,7 +230,7 @@ pub mod rustsec {
                             product_ids: branches.vulnerable.product_ids(),
                             restart_required: None,
                             url: None,
-                        }])
+                        }]))
                     } else {
                         None
                     },
@@ -183,15 +241,20 @@ pub mod rustsec {
                                 .product_ids()
                                 // Case where no version is actually vulnerable
                                 .unwrap_or_else(|| {
-                                    println!("INVALID Product ID");
+                                    tracing::warn!(
+                                        "no vulnerable versions of the package to score, using INVALID product id"
+                                    );
                                     vec![ProductIdT("INVALID".to_string())]
                                 }),
                             cvss_v2: None,
                             cvss_v3: Some(b),
+                            cvss_v4: None,
                         }]
                     }),
                     threats: None,
                     title: Some(input.metadata.title),
+                    disclosure_date: None,
+                    metrics: None,
                 }]),
             }
         }
@@ -211,14 +274,14 @@ pub mod rustsec {
                 vulnerable: BranchesT(Vec::new()),
             };
 
-            let mut id_counter: usize = 1;
-
             // While unread later, initalize here to prevent it from dropping at end of closure
             // where the directory is created if necessary.
             let mut _index_temp_dir = None;
 
+            let index_span = tracing::debug_span!("crates_index", package).entered();
             let index = crates_index::Index::new_cargo_default()
-                .or_else(|_| {
+                .or_else(|error| {
+                    tracing::debug!(%error, "default crates index unavailable, cloning to a temporary directory");
                     _index_temp_dir = Some(tempfile::tempdir().expect(
                         "Creating a temporary directory for a crates index should succeed.",
                     ));
@@ -230,51 +293,33 @@ pub mod rustsec {
                 .expect(
                     "Either opening the default index or creating a temporary one should work.",
                 );
+            drop(index_span);
 
-            let registry_crate = index
-                .crate_(package)
-                .expect("Package name must match name from crates.io registry");
-
-            let registry_versions = registry_crate.versions();
-
-            // ASSUMPTION: A version can only be one of patched, unaffected, or affected
-            // TODO: When I'm reaching for loop labels something has gone terribly wrong
-            'outer: for version in registry_versions {
-                let rustsec_version = IndexPackage::from(version).version;
-
-                // TODO: DRY
-                for pattern in versions.unaffected() {
-                    if pattern.matches(&rustsec_version) {
-                        output.unaffected.0.push(product_version_branch(
-                            &rustsec_version,
-                            package,
-                            id_counter,
-                        ));
-                        id_counter += 1;
-                        continue 'outer;
-                    }
-                }
-                for pattern in versions.patched() {
-                    if pattern.matches(&rustsec_version) {
-                        output.patched.0.push(product_version_branch(
-                            &rustsec_version,
-                            package,
-                            id_counter,
-                        ));
-                        id_counter += 1;
-                        continue 'outer;
-                    }
-                }
+            let _classify_span = tracing::debug_span!("classify_versions", package).entered();
 
-                // At this point the version has matched none of the unaffected or patched patterns, so can be evaulated
-                // as potentially vulnerable
-                if versions.is_vulnerable(&rustsec_version) {
-                    output.vulnerable.0.push(product_version_branch(
-                        &rustsec_version,
-                        package,
-                        id_counter,
-                    ));
-                    id_counter += 1;
+            // ASSUMPTION: A version can only be one of patched, unaffected, or affected. Versions that have matched
+            // none of the unaffected or patched patterns are evaluated as potentially vulnerable.
+            let mut classes = Vec::new();
+            let classified = BranchesT::from_crate_versions(package, &index, |version| {
+                let class = if versions.unaffected().iter().any(|req| req.matches(version)) {
+                    "unaffected"
+                } else if versions.patched().iter().any(|req| req.matches(version)) {
+                    "patched"
+                } else if versions.is_vulnerable(version) {
+                    "vulnerable"
+                } else {
+                    return false;
+                };
+                tracing::debug!(%version, class);
+                classes.push(class);
+                true
+            });
+
+            for (branch, class) in classified.0.into_iter().zip(classes) {
+                match class {
+                    "unaffected" => output.unaffected.0.push(branch),
+                    "patched" => output.patched.0.push(branch),
+                    _ => output.vulnerable.0.push(branch),
                 }
             }
 
@@ -290,6 +335,56 @@ pub mod rustsec {
         }
     }
 
+    impl BranchesT {
+        /// A `product_version` branch for every version of `package` in `index` that matches `req`, in index order
+        ///
+        /// Products are built by [product_version_branch] with IDs counting up from 1. A package missing from the
+        /// index has no versions, so gives no branches.
+        pub fn from_crate_version_req(
+            package: &str,
+            req: &semver::VersionReq,
+            index: &crates_index::Index,
+        ) -> BranchesT {
+            Self::from_crate_versions(package, index, |version| req.matches(version))
+        }
+
+        /// Like [BranchesT::from_crate_version_req], keeping the versions accepted by `filter`, which is called once
+        /// for each version in index order
+        fn from_crate_versions(
+            package: &str,
+            index: &crates_index::Index,
+            filter: impl FnMut(&semver::Version) -> bool,
+        ) -> BranchesT {
+            BranchesT(
+                crate_versions(index, package)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(filter)
+                    .enumerate()
+                    .map(|(index, version)| product_version_branch(version, package, index + 1))
+                    .collect(),
+            )
+        }
+    }
+
+    /// Every published version of the crate in the index, or `None` if it is not in the index
+    fn crate_versions(index: &crates_index::Index, package: &str) -> Option<Vec<semver::Version>> {
+        let registry_crate = index.crate_(package)?;
+        Some(
+            registry_crate
+                .versions()
+                .iter()
+                .map(|version| IndexPackage::from(version).version)
+                .collect(),
+        )
+    }
+
+    /// Builds the `product_version` branch for one version of a crate
+    ///
+    /// The product is named `"{package} {version}"` and given the ID `"{PACKAGE}-{id_counter}"`, with CPE and purl
+    /// identification helpers for the crate version. `id_counter` must be unique among the products of the document,
+    /// so documents that combine these branches with products of their own, such as hand-written VEX documents, can
+    /// pick IDs that do not collide.
     pub fn product_version_branch(
         version: impl ToString,
         package: &str,
@@ -310,20 +405,12 @@ pub mod rustsec {
                             .version(&version.to_string())
                             .validate()
                             .expect("Maps to valid CPE uri")
-                            .to_owned(),
-                    ),
-                    hashes: None,
-                    model_numbers: None,
-                    purl: Some(
-                        PackageUrl::new("cargo", package.to_string())
-                            .unwrap()
-                            .with_version(version.to_string())
-                            .to_owned(),
+                            .to_owned()
+                            .try_into()
+                            .expect("Maps to valid CPE"),
                     ),
-                    sbom_urls: None,
-                    serial_numbers: None,
-                    skus: None,
-                    x_generic_uris: None,
+                    ..ProductIdentificationHelper::for_cargo_crate(package, &version.to_string())
+                        .unwrap_or_default()
                 }),
             }),
             branches: None,
@@ -342,7 +429,7 @@ pub mod rustsec {
             // TODO: Reuse
             let example = include_str!("../tests/RUSTSEC-2021-0093.md");
             let advisory = Advisory::from_str(example).unwrap();
-            println!("{:#?}", advisory);
+            tracing::info!("{:#?}", advisory);
             let _document = crate::Csaf::from(advisory);
         }
 
@@ -351,7 +438,7 @@ pub mod rustsec {
             let example = include_str!("../tests/RUSTSEC-2021-0093.md");
             let advisory = Advisory::from_str(example).unwrap();
             let document = crate::Csaf::from(advisory);
-            println!("{}", serde_json::to_string_pretty(&document).unwrap());
+            tracing::info!("{}", serde_json::to_string_pretty(&document).unwrap());
         }
 
         #[test]
@@ -361,9 +448,11 @@ pub mod rustsec {
                 .expect("Need access to RustSec git repository");
 
             for advisory in db.into_iter() {
-                println!(
+                tracing::info!(
                     "{} {} {:?}",
-                    advisory.metadata.id, advisory.metadata.package, advisory.metadata.collection
+                    advisory.metadata.id,
+                    advisory.metadata.package,
+                    advisory.metadata.collection
                 );
                 let _document = crate::Csaf::from(advisory);
             }
@@ -0,0 +1,1556 @@
+//! Conversion to and from [CVRF 1.2](https://docs.oasis-open.org/csaf/csaf-cvrf/v1.2/csaf-cvrf-v1.2.html) XML, for
+//! exchanging documents with tools that have not yet moved to CSAF.
+//!
+//! CSAF 2.0 is a superset of CVRF 1.2, so the conversion is lossy: fields without a CVRF equivalent, such as flags,
+//! CVSS v4 scores and the `under_investigation` product status, are dropped.
+
+use std::{
+    fmt::Display,
+    io::{BufRead, BufReader, Read},
+    str::FromStr,
+};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use quick_xml::{
+    events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event},
+    Reader, Writer,
+};
+use url::Url;
+
+use super::non_empty;
+use crate::{
+    definitions::{
+        Acknowledgment, AcknowledgmentsT, Branch, BranchCategory, BranchesT, FullProductName, Note,
+        NoteCategory, NotesT, ProductIdT, ProductIdentificationHelper, Reference,
+        ReferenceCategory, ReferencesT,
+    },
+    document::{
+        AggregateSeverity, Category, CsafVersion, Distribution, Document, Engine, Generator,
+        Publisher, PublisherCategory, Revision, Status, Tracking,
+    },
+    product_tree::{ProductGroup, ProductTree, Relationship, RelationshipCategory},
+    vulnerability::{
+        Cwe, Involvement, InvolvementParty, InvolvementStatus, ProductStatus, ProductStatusKind,
+        Remediation, RemediationCategory, Remediations, Score, Threat, ThreatCategory,
+        Vulnerability, VulnerabilityId,
+    },
+    Csaf,
+};
+
+const CVRF_NAMESPACE: &str = "http://docs.oasis-open.org/csaf/ns/csaf-cvrf/v1.2/cvrf";
+const PROD_NAMESPACE: &str = "http://docs.oasis-open.org/csaf/ns/csaf-cvrf/v1.2/prod";
+const VULN_NAMESPACE: &str = "http://docs.oasis-open.org/csaf/ns/csaf-cvrf/v1.2/vuln";
+
+const PUBLISHER_TYPES: &[(PublisherCategory, &str)] = &[
+    (PublisherCategory::Coordinator, "Coordinator"),
+    (PublisherCategory::Discoverer, "Discoverer"),
+    (PublisherCategory::Other, "Other"),
+    (PublisherCategory::Translator, "Other"),
+    (PublisherCategory::User, "User"),
+    (PublisherCategory::Vendor, "Vendor"),
+];
+
+const DOCUMENT_STATUSES: &[(Status, &str)] = &[
+    (Status::Draft, "Draft"),
+    (Status::Final, "Final"),
+    (Status::Interim, "Interim"),
+];
+
+const NOTE_TYPES: &[(NoteCategory, &str)] = &[
+    (NoteCategory::Description, "Description"),
+    (NoteCategory::Details, "Details"),
+    (NoteCategory::Faq, "FAQ"),
+    (NoteCategory::General, "General"),
+    (NoteCategory::LegalDisclaimer, "Legal Disclaimer"),
+    (NoteCategory::Other, "Other"),
+    (NoteCategory::Summary, "Summary"),
+];
+
+const REFERENCE_TYPES: &[(ReferenceCategory, &str)] = &[
+    (ReferenceCategory::External, "External"),
+    (ReferenceCategory::RefSelf, "Self"),
+];
+
+/// CVRF has no notion of version ranges, so they are exported as versions
+const BRANCH_TYPES: &[(BranchCategory, &str)] = &[
+    (BranchCategory::Architecture, "Architecture"),
+    (BranchCategory::HostName, "Host Name"),
+    (BranchCategory::Language, "Language"),
+    (BranchCategory::Legacy, "Legacy"),
+    (BranchCategory::PatchLevel, "Patch Level"),
+    (BranchCategory::ProductFamily, "Product Family"),
+    (BranchCategory::ProductName, "Product Name"),
+    (BranchCategory::ProductVersion, "Product Version"),
+    (BranchCategory::ProductVersionRange, "Product Version"),
+    (BranchCategory::ServicePack, "Service Pack"),
+    (BranchCategory::Specification, "Specification"),
+    (BranchCategory::Vendor, "Vendor"),
+];
+
+const RELATIONSHIP_TYPES: &[(RelationshipCategory, &str)] = &[
+    (
+        RelationshipCategory::DefaultComponentOf,
+        "Default Component Of",
+    ),
+    (
+        RelationshipCategory::ExternalComponentOf,
+        "External Component Of",
+    ),
+    (RelationshipCategory::InstalledOn, "Installed On"),
+    (RelationshipCategory::InstalledWith, "Installed With"),
+    (
+        RelationshipCategory::OptionalComponentOf,
+        "Optional Component Of",
+    ),
+];
+
+const INVOLVEMENT_PARTIES: &[(InvolvementParty, &str)] = &[
+    (InvolvementParty::Coordinator, "Coordinator"),
+    (InvolvementParty::Discoverer, "Discoverer"),
+    (InvolvementParty::Other, "Other"),
+    (InvolvementParty::User, "User"),
+    (InvolvementParty::Vendor, "Vendor"),
+];
+
+const INVOLVEMENT_STATUSES: &[(InvolvementStatus, &str)] = &[
+    (InvolvementStatus::Completed, "Completed"),
+    (InvolvementStatus::ContactAttempted, "Contact Attempted"),
+    (InvolvementStatus::Disputed, "Disputed"),
+    (InvolvementStatus::InProgress, "In Progress"),
+    (InvolvementStatus::NotContacted, "Not Contacted"),
+    (InvolvementStatus::Open, "Open"),
+];
+
+/// CVRF has no equivalent of `under_investigation`
+const PRODUCT_STATUSES: &[(ProductStatusKind, &str)] = &[
+    (ProductStatusKind::FirstAffected, "First Affected"),
+    (ProductStatusKind::KnownAffected, "Known Affected"),
+    (ProductStatusKind::KnownNotAffected, "Known Not Affected"),
+    (ProductStatusKind::FirstFixed, "First Fixed"),
+    (ProductStatusKind::Fixed, "Fixed"),
+    (ProductStatusKind::Recommended, "Recommended"),
+    (ProductStatusKind::LastAffected, "Last Affected"),
+];
+
+const THREAT_TYPES: &[(ThreatCategory, &str)] = &[
+    (ThreatCategory::ExploitStatus, "Exploit Status"),
+    (ThreatCategory::Impact, "Impact"),
+    (ThreatCategory::TargetSet, "Target Set"),
+];
+
+const REMEDIATION_TYPES: &[(RemediationCategory, &str)] = &[
+    (RemediationCategory::Mitigation, "Mitigation"),
+    (RemediationCategory::NoFixPlanned, "Will Not Fix"),
+    (RemediationCategory::NoneAvailable, "None Available"),
+    (RemediationCategory::VendorFix, "Vendor Fix"),
+    (RemediationCategory::Workaround, "Workaround"),
+];
+
+/// The CVRF name of a CSAF enum value, or [CvrfError::Unsupported] for values that CVRF has no name for, such as
+/// those added in CSAF 2.1
+fn cvrf_name<T: PartialEq + std::fmt::Debug>(
+    table: &[(T, &'static str)],
+    field: &'static str,
+    value: &T,
+) -> Result<&'static str, CvrfError> {
+    table
+        .iter()
+        .find(|(v, _)| v == value)
+        .map(|(_, name)| *name)
+        .ok_or_else(|| CvrfError::Unsupported {
+            field,
+            value: format!("{:?}", value),
+        })
+}
+
+/// Failure to read or write a CVRF document
+#[derive(Debug)]
+pub enum CvrfError {
+    Xml(quick_xml::Error),
+    /// A required element or attribute was not present
+    Missing(&'static str),
+    /// An element or attribute had a value with no CSAF equivalent
+    Invalid {
+        field: &'static str,
+        value: String,
+    },
+    /// A CSAF value has no CVRF equivalent, so the document cannot be written as CVRF
+    Unsupported {
+        field: &'static str,
+        value: String,
+    },
+}
+
+impl Display for CvrfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Xml(e) => write!(f, "malformed CVRF XML: {}", e),
+            Self::Missing(name) => write!(f, "CVRF document is missing {}", name),
+            Self::Invalid { field, value } => write!(f, "invalid CVRF {}: {:?}", field, value),
+            Self::Unsupported { field, value } => {
+                write!(f, "{} has no CVRF {}", value, field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CvrfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Xml(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<quick_xml::Error> for CvrfError {
+    fn from(e: quick_xml::Error) -> Self {
+        Self::Xml(e)
+    }
+}
+
+impl Csaf {
+    /// Serializes the document as CVRF 1.2 XML, see [to_cvrf_xml]
+    pub fn to_cvrf_xml(&self) -> Result<String, CvrfError> {
+        to_cvrf_xml(self)
+    }
+
+    /// Parses a CVRF 1.2 XML document, see [from_cvrf_xml]
+    pub fn from_cvrf_xml(reader: impl Read) -> Result<Csaf, CvrfError> {
+        from_cvrf_xml(reader)
+    }
+}
+
+/// Serializes a document as CVRF 1.2 XML
+///
+/// ```
+/// # use csaf::Csaf;
+/// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../../tests/CVE-2018-0171-modified.json")).unwrap();
+/// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+/// let csaf: Csaf = serde_json::from_value(json).unwrap();
+/// let xml = csaf::interop::cvrf::to_cvrf_xml(&csaf).unwrap();
+/// assert!(xml.contains("<vuln:CVE>CVE-2018-0171</vuln:CVE>"));
+/// ```
+pub fn to_cvrf_xml(csaf: &Csaf) -> Result<String, CvrfError> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    write_document(&mut writer, csaf)?;
+    Ok(String::from_utf8(writer.into_inner()).expect("XML is written from strings"))
+}
+
+type XmlResult = Result<(), CvrfError>;
+
+fn start(writer: &mut Writer<Vec<u8>>, name: &str, attributes: &[(&str, &str)]) -> XmlResult {
+    writer.write_event(Event::Start(
+        BytesStart::new(name).with_attributes(attributes.iter().copied()),
+    ))?;
+    Ok(())
+}
+
+fn end(writer: &mut Writer<Vec<u8>>, name: &str) -> XmlResult {
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
+
+fn element(
+    writer: &mut Writer<Vec<u8>>,
+    name: &str,
+    attributes: &[(&str, &str)],
+    text: &str,
+) -> XmlResult {
+    start(writer, name, attributes)?;
+    writer.write_event(Event::Text(BytesText::new(text)))?;
+    end(writer, name)
+}
+
+fn date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339()
+}
+
+fn write_document(writer: &mut Writer<Vec<u8>>, csaf: &Csaf) -> XmlResult {
+    let document = &csaf.document;
+    let lang = document.lang.as_ref().map(|lang| lang.to_string());
+    let mut attributes = vec![
+        ("xmlns", CVRF_NAMESPACE),
+        ("xmlns:prod", PROD_NAMESPACE),
+        ("xmlns:vuln", VULN_NAMESPACE),
+    ];
+    if let Some(lang) = &lang {
+        attributes.push(("xml:lang", lang));
+    }
+    start(writer, "cvrfdoc", &attributes)?;
+    element(writer, "DocumentTitle", &[], &document.title)?;
+    element(writer, "DocumentType", &[], &document.category.to_string())?;
+
+    let publisher_type = cvrf_name(
+        PUBLISHER_TYPES,
+        "DocumentPublisher Type",
+        &document.publisher.category,
+    )?;
+    start(writer, "DocumentPublisher", &[("Type", publisher_type)])?;
+    if let Some(contact_details) = &document.publisher.contact_details {
+        element(writer, "ContactDetails", &[], contact_details)?;
+    }
+    if let Some(issuing_authority) = &document.publisher.issuing_authority {
+        element(writer, "IssuingAuthority", &[], issuing_authority)?;
+    }
+    end(writer, "DocumentPublisher")?;
+
+    let tracking = &document.tracking;
+    start(writer, "DocumentTracking", &[])?;
+    start(writer, "Identification", &[])?;
+    element(writer, "ID", &[], &tracking.id)?;
+    for alias in tracking.aliases.iter().flatten() {
+        element(writer, "Alias", &[], alias)?;
+    }
+    end(writer, "Identification")?;
+    let status = cvrf_name(DOCUMENT_STATUSES, "Status", &tracking.status)?;
+    element(writer, "Status", &[], status)?;
+    element(writer, "Version", &[], &tracking.version)?;
+    start(writer, "RevisionHistory", &[])?;
+    for revision in &tracking.revision_history {
+        start(writer, "Revision", &[])?;
+        element(writer, "Number", &[], &revision.number)?;
+        element(writer, "Date", &[], &date(&revision.date))?;
+        element(writer, "Description", &[], &revision.summary)?;
+        end(writer, "Revision")?;
+    }
+    end(writer, "RevisionHistory")?;
+    element(
+        writer,
+        "InitialReleaseDate",
+        &[],
+        &date(&tracking.initial_release_date),
+    )?;
+    element(
+        writer,
+        "CurrentReleaseDate",
+        &[],
+        &date(&tracking.current_release_date),
+    )?;
+    if let Some(generator) = &tracking.generator {
+        start(writer, "Generator", &[])?;
+        let engine = match &generator.engine.version {
+            Some(version) => format!("{} {}", generator.engine.name, version),
+            None => generator.engine.name.clone(),
+        };
+        element(writer, "Engine", &[], &engine)?;
+        if let Some(generated) = &generator.date {
+            element(writer, "Date", &[], &date(generated))?;
+        }
+        end(writer, "Generator")?;
+    }
+    end(writer, "DocumentTracking")?;
+
+    if let Some(notes) = &document.notes {
+        write_notes(writer, "DocumentNotes", "Note", notes)?;
+    }
+    if let Some(text) = document.distribution.as_ref().and_then(|d| d.text.as_ref()) {
+        element(writer, "DocumentDistribution", &[], text)?;
+    }
+    if let Some(severity) = &document.aggregate_severity {
+        match &severity.namespace {
+            Some(namespace) => element(
+                writer,
+                "AggregateSeverity",
+                &[("Namespace", namespace.as_str())],
+                &severity.text,
+            )?,
+            None => element(writer, "AggregateSeverity", &[], &severity.text)?,
+        }
+    }
+    if let Some(references) = &document.references {
+        write_references(writer, "DocumentReferences", "", references)?;
+    }
+    if let Some(acknowledgments) = &document.acknowledgments {
+        write_acknowledgments(writer, "", acknowledgments)?;
+    }
+
+    if let Some(product_tree) = &csaf.product_tree {
+        write_product_tree(writer, product_tree)?;
+    }
+    for (index, vulnerability) in csaf.vulnerabilities.iter().flatten().enumerate() {
+        write_vulnerability(writer, index + 1, vulnerability)?;
+    }
+
+    end(writer, "cvrfdoc")
+}
+
+fn write_notes(writer: &mut Writer<Vec<u8>>, list: &str, item: &str, notes: &NotesT) -> XmlResult {
+    start(writer, list, &[])?;
+    for (index, note) in notes.iter().enumerate() {
+        let ordinal = (index + 1).to_string();
+        let note_type = cvrf_name(NOTE_TYPES, "Note Type", &note.category)?;
+        let mut attributes = vec![("Type", note_type), ("Ordinal", ordinal.as_str())];
+        if let Some(title) = &note.title {
+            attributes.push(("Title", title));
+        }
+        if let Some(audience) = &note.audience {
+            attributes.push(("Audience", audience));
+        }
+        element(writer, item, &attributes, &note.text)?;
+    }
+    end(writer, list)
+}
+
+/// `prefix` is the namespace prefix of the enclosing section, including the colon, or empty for the document
+fn write_references(
+    writer: &mut Writer<Vec<u8>>,
+    list: &str,
+    prefix: &str,
+    references: &ReferencesT,
+) -> XmlResult {
+    start(writer, list, &[])?;
+    for reference in references {
+        // CVRF also defaults to external references when no type is given
+        let attributes = match &reference.category {
+            Some(category) => vec![(
+                "Type",
+                cvrf_name(REFERENCE_TYPES, "Reference Type", category)?,
+            )],
+            None => vec![],
+        };
+        start(writer, &format!("{}{}", prefix, "Reference"), &attributes)?;
+        element(
+            writer,
+            &format!("{}{}", prefix, "URL"),
+            &[],
+            reference.url.as_str(),
+        )?;
+        element(
+            writer,
+            &format!("{}{}", prefix, "Description"),
+            &[],
+            &reference.summary,
+        )?;
+        end(writer, &format!("{}{}", prefix, "Reference"))?;
+    }
+    end(writer, list)
+}
+
+/// `prefix` is the namespace prefix of the enclosing section, including the colon, or empty for the document
+fn write_acknowledgments(
+    writer: &mut Writer<Vec<u8>>,
+    prefix: &str,
+    acknowledgments: &AcknowledgmentsT,
+) -> XmlResult {
+    start(writer, &format!("{}{}", prefix, "Acknowledgments"), &[])?;
+    for acknowledgment in acknowledgments {
+        start(writer, &format!("{}{}", prefix, "Acknowledgment"), &[])?;
+        for name in acknowledgment.names.iter().flatten() {
+            element(writer, &format!("{}{}", prefix, "Name"), &[], name)?;
+        }
+        if let Some(organization) = &acknowledgment.organization {
+            element(
+                writer,
+                &format!("{}{}", prefix, "Organization"),
+                &[],
+                organization,
+            )?;
+        }
+        if let Some(summary) = &acknowledgment.summary {
+            element(
+                writer,
+                &format!("{}{}", prefix, "Description"),
+                &[],
+                summary,
+            )?;
+        }
+        for url in acknowledgment.urls.iter().flatten() {
+            element(writer, &format!("{}{}", prefix, "URL"), &[], url.as_str())?;
+        }
+        end(writer, &format!("{}{}", prefix, "Acknowledgment"))?;
+    }
+    end(writer, &format!("{}{}", prefix, "Acknowledgments"))
+}
+
+fn write_product_tree(writer: &mut Writer<Vec<u8>>, product_tree: &ProductTree) -> XmlResult {
+    start(writer, "prod:ProductTree", &[])?;
+    for branch in product_tree.branches.iter().flat_map(|b| &b.0) {
+        write_branch(writer, branch)?;
+    }
+    for product in product_tree.full_product_names.iter().flatten() {
+        write_full_product_name(writer, product)?;
+    }
+    for relationship in product_tree.relationships.iter().flatten() {
+        let relation_type = cvrf_name(
+            RELATIONSHIP_TYPES,
+            "Relationship RelationType",
+            &relationship.category,
+        )?;
+        start(
+            writer,
+            "prod:Relationship",
+            &[
+                ("ProductReference", &relationship.product_reference.0),
+                ("RelationType", relation_type),
+                (
+                    "RelatesToProductReference",
+                    &relationship.relates_to_product_reference.0,
+                ),
+            ],
+        )?;
+        write_full_product_name(writer, &relationship.full_product_name)?;
+        end(writer, "prod:Relationship")?;
+    }
+    if let Some(groups) = &product_tree.product_groups {
+        start(writer, "prod:ProductGroups", &[])?;
+        for group in groups {
+            start(writer, "prod:Group", &[("GroupID", &group.group_id)])?;
+            if let Some(summary) = &group.summary {
+                element(writer, "prod:Description", &[], summary)?;
+            }
+            for product_id in &group.product_ids {
+                element(writer, "prod:ProductID", &[], &product_id.0)?;
+            }
+            end(writer, "prod:Group")?;
+        }
+        end(writer, "prod:ProductGroups")?;
+    }
+    end(writer, "prod:ProductTree")
+}
+
+fn write_branch(writer: &mut Writer<Vec<u8>>, branch: &Branch) -> XmlResult {
+    let branch_type = cvrf_name(BRANCH_TYPES, "Branch Type", &branch.category)?;
+    start(
+        writer,
+        "prod:Branch",
+        &[("Type", branch_type), ("Name", &branch.name)],
+    )?;
+    if let Some(product) = &branch.product {
+        write_full_product_name(writer, product)?;
+    }
+    for child in branch.branches.iter().flat_map(|b| &b.0) {
+        write_branch(writer, child)?;
+    }
+    end(writer, "prod:Branch")
+}
+
+fn write_full_product_name(writer: &mut Writer<Vec<u8>>, product: &FullProductName) -> XmlResult {
+    let cpe = product
+        .product_identification_helper
+        .as_ref()
+        .and_then(|helper| helper.cpe.as_ref())
+        .map(|cpe| cpe.to_string());
+    let mut attributes = vec![("ProductID", product.product_id.0.as_str())];
+    if let Some(cpe) = &cpe {
+        attributes.push(("CPE", cpe));
+    }
+    element(writer, "prod:FullProductName", &attributes, &product.name)
+}
+
+fn write_vulnerability(
+    writer: &mut Writer<Vec<u8>>,
+    ordinal: usize,
+    vulnerability: &Vulnerability,
+) -> XmlResult {
+    start(
+        writer,
+        "vuln:Vulnerability",
+        &[("Ordinal", &ordinal.to_string())],
+    )?;
+    if let Some(title) = &vulnerability.title {
+        element(writer, "vuln:Title", &[], title)?;
+    }
+    // CVRF allows a single ID
+    if let Some(id) = vulnerability.ids.iter().flatten().next() {
+        element(
+            writer,
+            "vuln:ID",
+            &[("SystemName", &id.system_name)],
+            &id.text,
+        )?;
+    }
+    if let Some(notes) = &vulnerability.notes {
+        write_notes(writer, "vuln:Notes", "vuln:Note", notes)?;
+    }
+    if let Some(discovery_date) = &vulnerability.discovery_date {
+        element(writer, "vuln:DiscoveryDate", &[], &date(discovery_date))?;
+    }
+    if let Some(release_date) = &vulnerability.release_date {
+        element(writer, "vuln:ReleaseDate", &[], &date(release_date))?;
+    }
+    if let Some(involvements) = &vulnerability.involvements {
+        start(writer, "vuln:Involvements", &[])?;
+        for involvement in involvements {
+            let party = cvrf_name(INVOLVEMENT_PARTIES, "Involvement Party", &involvement.party)?;
+            let status = cvrf_name(
+                INVOLVEMENT_STATUSES,
+                "Involvement Status",
+                &involvement.status,
+            )?;
+            let attributes = [("Party", party), ("Status", status)];
+            match &involvement.summary {
+                Some(summary) => {
+                    start(writer, "vuln:Involvement", &attributes)?;
+                    element(writer, "vuln:Description", &[], summary)?;
+                    end(writer, "vuln:Involvement")?;
+                }
+                None => {
+                    writer.write_event(Event::Empty(
+                        BytesStart::new("vuln:Involvement").with_attributes(attributes),
+                    ))?;
+                }
+            }
+        }
+        end(writer, "vuln:Involvements")?;
+    }
+    if let Some(cve) = &vulnerability.cve {
+        element(writer, "vuln:CVE", &[], cve)?;
+    }
+    if let Some(cwe) = &vulnerability.cwe {
+        element(writer, "vuln:CWE", &[("ID", cwe.id.as_str())], &cwe.name)?;
+    }
+    if let Some(product_status) = &vulnerability.product_status {
+        start(writer, "vuln:ProductStatuses", &[])?;
+        let by_kind = |kind: ProductStatusKind| {
+            product_status
+                .entries()
+                .filter(move |(k, _)| *k == kind)
+                .map(|(_, id)| id)
+        };
+        for &(kind, status_type) in PRODUCT_STATUSES {
+            let mut ids = by_kind(kind).peekable();
+            if ids.peek().is_none() {
+                continue;
+            }
+            start(writer, "vuln:Status", &[("Type", status_type)])?;
+            for id in ids {
+                element(writer, "vuln:ProductID", &[], &id.0)?;
+            }
+            end(writer, "vuln:Status")?;
+        }
+        end(writer, "vuln:ProductStatuses")?;
+    }
+    if let Some(threats) = &vulnerability.threats {
+        start(writer, "vuln:Threats", &[])?;
+        for threat in threats {
+            let threat_type = cvrf_name(THREAT_TYPES, "Threat Type", &threat.category)?;
+            let threat_date = threat.date.as_ref().map(date);
+            let mut attributes = vec![("Type", threat_type)];
+            if let Some(threat_date) = &threat_date {
+                attributes.push(("Date", threat_date));
+            }
+            start(writer, "vuln:Threat", &attributes)?;
+            element(writer, "vuln:Description", &[], &threat.details)?;
+            for product_id in threat.product_ids.iter().flatten() {
+                element(writer, "vuln:ProductID", &[], &product_id.0)?;
+            }
+            for group_id in threat.group_ids.iter().flatten() {
+                element(writer, "vuln:GroupID", &[], group_id)?;
+            }
+            end(writer, "vuln:Threat")?;
+        }
+        end(writer, "vuln:Threats")?;
+    }
+    if let Some(scores) = &vulnerability.scores {
+        let mut score_sets = scores
+            .iter()
+            .filter(|score| score.cvss_v2.is_some() || score.cvss_v3.is_some())
+            .peekable();
+        if score_sets.peek().is_some() {
+            start(writer, "vuln:CVSSScoreSets", &[])?;
+            for score in score_sets {
+                if let Some(cvss_v3) = &score.cvss_v3 {
+                    start(writer, "vuln:ScoreSetV3", &[])?;
+                    element(
+                        writer,
+                        "vuln:BaseScoreV3",
+                        &[],
+                        &cvss_v3.score().value().to_string(),
+                    )?;
+                    element(writer, "vuln:VectorV3", &[], &cvss_v3.to_string())?;
+                    for product_id in &score.products {
+                        element(writer, "vuln:ProductID", &[], &product_id.0)?;
+                    }
+                    end(writer, "vuln:ScoreSetV3")?;
+                }
+                if let Some(cvss_v2) = &score.cvss_v2 {
+                    start(writer, "vuln:ScoreSetV2", &[])?;
+                    element(
+                        writer,
+                        "vuln:BaseScoreV2",
+                        &[],
+                        &cvss_v2.base_score().to_string(),
+                    )?;
+                    if let Some(temporal_score) = cvss_v2.temporal_score() {
+                        element(
+                            writer,
+                            "vuln:TemporalScoreV2",
+                            &[],
+                            &temporal_score.to_string(),
+                        )?;
+                    }
+                    if let Some(environmental_score) = cvss_v2.environmental_score() {
+                        element(
+                            writer,
+                            "vuln:EnvironmentalScoreV2",
+                            &[],
+                            &environmental_score.to_string(),
+                        )?;
+                    }
+                    element(writer, "vuln:VectorV2", &[], &cvss_v2.to_string())?;
+                    for product_id in &score.products {
+                        element(writer, "vuln:ProductID", &[], &product_id.0)?;
+                    }
+                    end(writer, "vuln:ScoreSetV2")?;
+                }
+            }
+            end(writer, "vuln:CVSSScoreSets")?;
+        }
+    }
+    if let Some(remediations) = &vulnerability.remediations {
+        start(writer, "vuln:Remediations", &[])?;
+        for remediation in remediations {
+            let remediation_type =
+                cvrf_name(REMEDIATION_TYPES, "Remediation Type", &remediation.category)?;
+            let remediation_date = remediation.date.as_ref().map(date);
+            let mut attributes = vec![("Type", remediation_type)];
+            if let Some(remediation_date) = &remediation_date {
+                attributes.push(("Date", remediation_date));
+            }
+            start(writer, "vuln:Remediation", &attributes)?;
+            element(writer, "vuln:Description", &[], &remediation.details)?;
+            for entitlement in remediation.entitlements.iter().flatten() {
+                element(writer, "vuln:Entitlement", &[], entitlement)?;
+            }
+            if let Some(url) = &remediation.url {
+                element(writer, "vuln:URL", &[], url.as_str())?;
+            }
+            for product_id in remediation.product_ids.iter().flatten() {
+                element(writer, "vuln:ProductID", &[], &product_id.0)?;
+            }
+            for group_id in remediation.group_ids.iter().flatten() {
+                element(writer, "vuln:GroupID", &[], group_id)?;
+            }
+            end(writer, "vuln:Remediation")?;
+        }
+        end(writer, "vuln:Remediations")?;
+    }
+    if let Some(references) = &vulnerability.references {
+        write_references(writer, "vuln:References", "vuln:", references)?;
+    }
+    if let Some(acknowledgments) = &vulnerability.acknowledgments {
+        write_acknowledgments(writer, "vuln:", acknowledgments)?;
+    }
+    end(writer, "vuln:Vulnerability")
+}
+
+/// Parses a CVRF 1.2 XML document
+///
+/// Elements are matched by their local name, so documents are accepted whether the CVRF namespace is the default or
+/// bound to a prefix such as `cvrf:`. CVRF has no equivalent of the publisher's name and namespace, so the namespace
+/// is taken from the origin of the document's `Self` reference, or its first reference if there is none, and the
+/// name from the host of that namespace.
+pub fn from_cvrf_xml(reader: impl Read) -> Result<Csaf, CvrfError> {
+    let root = parse_tree(BufReader::new(reader))?;
+    if root.name != "cvrfdoc" {
+        return Err(CvrfError::Invalid {
+            field: "root element",
+            value: root.name,
+        });
+    }
+
+    let references = root
+        .child("DocumentReferences")
+        .map(read_references)
+        .transpose()?;
+    let product_tree = root
+        .child("ProductTree")
+        .map(read_product_tree)
+        .transpose()?;
+    let vulnerabilities = root
+        .children("Vulnerability")
+        .map(read_vulnerability)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Csaf {
+        document: Document {
+            category: Category::from_str(root.required_text("DocumentType")?).expect("infallible"),
+            publisher: read_publisher(&root, references.as_deref())?,
+            title: root.required_text("DocumentTitle")?.to_string(),
+            tracking: read_tracking(root.required("DocumentTracking")?)?,
+            csaf_version: CsafVersion::TwoDotZero,
+            acknowledgments: root
+                .child("Acknowledgments")
+                .map(read_acknowledgments)
+                .transpose()?,
+            aggregate_severity: root
+                .child("AggregateSeverity")
+                .map(|severity| -> Result<_, CvrfError> {
+                    Ok(AggregateSeverity {
+                        text: severity.text.clone(),
+                        namespace: severity
+                            .attribute("Namespace")
+                            .map(|namespace| parse_url(namespace, "AggregateSeverity Namespace"))
+                            .transpose()?,
+                    })
+                })
+                .transpose()?,
+            distribution: root
+                .text("DocumentDistribution")
+                .map(Distribution::new_with_text),
+            lang: root.attribute("lang").and_then(|lang| lang.parse().ok()),
+            notes: root.child("DocumentNotes").map(read_notes).transpose()?,
+            references,
+            source_lang: None,
+            license_expression: None,
+        },
+        product_tree,
+        vulnerabilities: non_empty(vulnerabilities),
+    })
+}
+
+/// An XML element, with namespace prefixes stripped from its name and the names of its attributes
+#[derive(Debug)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn new(start: &BytesStart) -> Result<Self, quick_xml::Error> {
+        let mut attributes = Vec::new();
+        for attribute in start.attributes() {
+            let attribute = attribute?;
+            attributes.push((
+                String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned(),
+                attribute.unescape_value()?.into_owned(),
+            ));
+        }
+        Ok(Self {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+            attributes,
+            text: String::new(),
+            children: Vec::new(),
+        })
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn required_attribute(&self, name: &'static str) -> Result<&str, CvrfError> {
+        self.attribute(name).ok_or(CvrfError::Missing(name))
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn required(&self, name: &'static str) -> Result<&Element, CvrfError> {
+        self.child(name).ok_or(CvrfError::Missing(name))
+    }
+
+    fn text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|child| child.text.as_str())
+    }
+
+    fn required_text(&self, name: &'static str) -> Result<&str, CvrfError> {
+        self.required(name).map(|child| child.text.as_str())
+    }
+
+    fn texts<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.children(name).map(|child| child.text.as_str())
+    }
+}
+
+fn parse_tree(reader: impl BufRead) -> Result<Element, CvrfError> {
+    let mut reader = Reader::from_reader(reader);
+    reader.trim_text(true);
+    let mut buffer = Vec::new();
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+
+    loop {
+        let completed = match reader.read_event_into(&mut buffer)? {
+            Event::Start(start) => {
+                stack.push(Element::new(&start)?);
+                None
+            }
+            Event::Empty(start) => Some(Element::new(&start)?),
+            Event::End(_) => stack.pop(),
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text.unescape()?);
+                }
+                None
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element
+                        .text
+                        .push_str(&String::from_utf8_lossy(&data.into_inner()));
+                }
+                None
+            }
+            Event::Eof => break,
+            _ => None,
+        };
+        if let Some(element) = completed {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None => root = Some(element),
+            }
+        }
+        buffer.clear();
+    }
+
+    root.ok_or(CvrfError::Missing("cvrfdoc"))
+}
+
+/// The CSAF enum value of a CVRF name
+fn from_cvrf_name<T: Clone>(
+    table: &[(T, &'static str)],
+    field: &'static str,
+    name: &str,
+) -> Result<T, CvrfError> {
+    table
+        .iter()
+        .find(|(_, n)| *n == name)
+        .map(|(value, _)| value.clone())
+        .ok_or_else(|| CvrfError::Invalid {
+            field,
+            value: name.to_string(),
+        })
+}
+
+/// Parses an `xs:dateTime`, which may omit the timezone. Dates without one are taken to be UTC.
+fn parse_date(value: &str, field: &'static str) -> Result<DateTime<Utc>, CvrfError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .map(|date| DateTime::from_naive_utc_and_offset(date, Utc))
+        })
+        .map_err(|_| CvrfError::Invalid {
+            field,
+            value: value.to_string(),
+        })
+}
+
+fn parse_url(value: &str, field: &'static str) -> Result<Url, CvrfError> {
+    value.parse().map_err(|_| CvrfError::Invalid {
+        field,
+        value: value.to_string(),
+    })
+}
+
+fn read_publisher(
+    root: &Element,
+    references: Option<&[Reference]>,
+) -> Result<Publisher, CvrfError> {
+    let element = root.required("DocumentPublisher")?;
+    let references = references.unwrap_or_default();
+    let reference = references
+        .iter()
+        .find(|reference| reference.category == Some(ReferenceCategory::RefSelf))
+        .or_else(|| references.first())
+        .ok_or(CvrfError::Missing("DocumentReferences"))?;
+    let namespace = parse_url(
+        &reference.url.origin().ascii_serialization(),
+        "DocumentReferences URL",
+    )?;
+    Ok(Publisher {
+        category: from_cvrf_name(
+            PUBLISHER_TYPES,
+            "DocumentPublisher Type",
+            element.required_attribute("Type")?,
+        )?,
+        name: namespace.host_str().unwrap_or_default().to_string(),
+        namespace,
+        contact_details: element.text("ContactDetails").map(str::to_string),
+        issuing_authority: element.text("IssuingAuthority").map(str::to_string),
+    })
+}
+
+fn read_tracking(element: &Element) -> Result<Tracking, CvrfError> {
+    let identification = element.required("Identification")?;
+    let revision_history = element
+        .required("RevisionHistory")?
+        .children("Revision")
+        .map(|revision| -> Result<_, CvrfError> {
+            Ok(Revision {
+                date: parse_date(revision.required_text("Date")?, "Revision Date")?,
+                legacy_version: None,
+                number: revision.required_text("Number")?.to_string(),
+                summary: revision.required_text("Description")?.to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let generator = element
+        .child("Generator")
+        .map(|generator| -> Result<_, CvrfError> {
+            Ok(Generator {
+                engine: Engine {
+                    name: generator.required_text("Engine")?.to_string(),
+                    version: None,
+                },
+                date: generator
+                    .text("Date")
+                    .map(|date| parse_date(date, "Generator Date"))
+                    .transpose()?,
+            })
+        })
+        .transpose()?;
+    Ok(Tracking {
+        current_release_date: parse_date(
+            element.required_text("CurrentReleaseDate")?,
+            "CurrentReleaseDate",
+        )?,
+        id: identification.required_text("ID")?.to_string(),
+        initial_release_date: parse_date(
+            element.required_text("InitialReleaseDate")?,
+            "InitialReleaseDate",
+        )?,
+        revision_history,
+        status: from_cvrf_name(
+            DOCUMENT_STATUSES,
+            "Status",
+            element.required_text("Status")?,
+        )?,
+        version: element.required_text("Version")?.to_string(),
+        aliases: non_empty(identification.texts("Alias").map(str::to_string).collect()),
+        generator,
+    })
+}
+
+fn read_notes(element: &Element) -> Result<NotesT, CvrfError> {
+    element
+        .children("Note")
+        .map(|note| {
+            Ok(Note {
+                category: from_cvrf_name(
+                    NOTE_TYPES,
+                    "Note Type",
+                    note.required_attribute("Type")?,
+                )?,
+                text: note.text.clone(),
+                audience: note.attribute("Audience").map(str::to_string),
+                title: note.attribute("Title").map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+fn read_references(element: &Element) -> Result<ReferencesT, CvrfError> {
+    element
+        .children("Reference")
+        .map(|reference| {
+            Ok(Reference {
+                url: parse_url(reference.required_text("URL")?, "Reference URL")?,
+                summary: reference.required_text("Description")?.to_string(),
+                category: reference
+                    .attribute("Type")
+                    .map(|name| from_cvrf_name(REFERENCE_TYPES, "Reference Type", name))
+                    .transpose()?,
+            })
+        })
+        .collect()
+}
+
+fn read_acknowledgments(element: &Element) -> Result<AcknowledgmentsT, CvrfError> {
+    element
+        .children("Acknowledgment")
+        .map(|acknowledgment| {
+            Ok(Acknowledgment {
+                names: non_empty(acknowledgment.texts("Name").map(str::to_string).collect()),
+                organization: acknowledgment.text("Organization").map(str::to_string),
+                summary: acknowledgment.text("Description").map(str::to_string),
+                urls: non_empty(
+                    acknowledgment
+                        .texts("URL")
+                        .map(|url| parse_url(url, "Acknowledgment URL"))
+                        .collect::<Result<_, _>>()?,
+                ),
+            })
+        })
+        .collect()
+}
+
+fn read_product_tree(element: &Element) -> Result<ProductTree, CvrfError> {
+    let branches = element
+        .children("Branch")
+        .map(read_branch)
+        .collect::<Result<Vec<_>, _>>()?;
+    let full_product_names = element
+        .children("FullProductName")
+        .map(read_full_product_name)
+        .collect::<Result<Vec<_>, _>>()?;
+    let relationships = element
+        .children("Relationship")
+        .map(|relationship| -> Result<_, CvrfError> {
+            Ok(Relationship {
+                category: from_cvrf_name(
+                    RELATIONSHIP_TYPES,
+                    "Relationship RelationType",
+                    relationship.required_attribute("RelationType")?,
+                )?,
+                full_product_name: read_full_product_name(
+                    relationship.required("FullProductName")?,
+                )?,
+                product_reference: ProductIdT(
+                    relationship
+                        .required_attribute("ProductReference")?
+                        .to_string(),
+                ),
+                relates_to_product_reference: ProductIdT(
+                    relationship
+                        .required_attribute("RelatesToProductReference")?
+                        .to_string(),
+                ),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let product_groups = element
+        .child("ProductGroups")
+        .map(|groups| {
+            groups
+                .children("Group")
+                .map(|group| -> Result<_, CvrfError> {
+                    Ok(ProductGroup {
+                        group_id: group.required_attribute("GroupID")?.to_string(),
+                        product_ids: group.texts("ProductID").map(product_id).collect(),
+                        summary: group.text("Description").map(str::to_string),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+    Ok(ProductTree {
+        branches: non_empty(branches).map(BranchesT),
+        full_product_names: non_empty(full_product_names),
+        product_groups,
+        relationships: non_empty(relationships),
+    })
+}
+
+fn read_branch(element: &Element) -> Result<Branch, CvrfError> {
+    let branches = element
+        .children("Branch")
+        .map(read_branch)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Branch {
+        name: element.required_attribute("Name")?.to_string(),
+        category: from_cvrf_name(
+            BRANCH_TYPES,
+            "Branch Type",
+            element.required_attribute("Type")?,
+        )?,
+        product: element
+            .child("FullProductName")
+            .map(read_full_product_name)
+            .transpose()?,
+        branches: non_empty(branches).map(BranchesT),
+    })
+}
+
+fn read_full_product_name(element: &Element) -> Result<FullProductName, CvrfError> {
+    let cpe = element
+        .attribute("CPE")
+        .map(|cpe| {
+            cpe.parse().map_err(|_| CvrfError::Invalid {
+                field: "FullProductName CPE",
+                value: cpe.to_string(),
+            })
+        })
+        .transpose()?;
+    Ok(FullProductName {
+        name: element.text.clone(),
+        product_id: product_id(element.required_attribute("ProductID")?),
+        product_identification_helper: cpe.map(|cpe| ProductIdentificationHelper {
+            cpe: Some(cpe),
+            ..Default::default()
+        }),
+    })
+}
+
+fn product_id(id: &str) -> ProductIdT {
+    ProductIdT(id.to_string())
+}
+
+fn read_vulnerability(element: &Element) -> Result<Vulnerability, CvrfError> {
+    let product_status = element
+        .child("ProductStatuses")
+        .map(|statuses| -> Result<_, CvrfError> {
+            let mut product_status = ProductStatus::default();
+            for status in statuses.children("Status") {
+                let kind = from_cvrf_name(
+                    PRODUCT_STATUSES,
+                    "Status Type",
+                    status.required_attribute("Type")?,
+                )?;
+                product_status
+                    .list_mut(kind)
+                    .get_or_insert_with(Vec::new)
+                    .extend(status.texts("ProductID").map(product_id));
+            }
+            Ok(product_status)
+        })
+        .transpose()?;
+
+    let mut scores = Vec::new();
+    for score_sets in element.children("CVSSScoreSets") {
+        for score_set in score_sets.children("ScoreSetV3") {
+            if let Some(vector) = score_set.text("VectorV3") {
+                scores.push(Score {
+                    products: score_set.texts("ProductID").map(product_id).collect(),
+                    cvss_v2: None,
+                    cvss_v3: Some(vector.parse().map_err(|_| CvrfError::Invalid {
+                        field: "VectorV3",
+                        value: vector.to_string(),
+                    })?),
+                    cvss_v4: None,
+                });
+            }
+        }
+        for score_set in score_sets.children("ScoreSetV2") {
+            if let Some(vector) = score_set.text("VectorV2") {
+                scores.push(Score {
+                    products: score_set.texts("ProductID").map(product_id).collect(),
+                    cvss_v2: Some(vector.parse().map_err(|_| CvrfError::Invalid {
+                        field: "VectorV2",
+                        value: vector.to_string(),
+                    })?),
+                    cvss_v3: None,
+                    cvss_v4: None,
+                });
+            }
+        }
+    }
+
+    let involvements = element
+        .children("Involvements")
+        .flat_map(|involvements| involvements.children("Involvement"))
+        .map(|involvement| {
+            Ok(Involvement {
+                party: from_cvrf_name(
+                    INVOLVEMENT_PARTIES,
+                    "Involvement Party",
+                    involvement.required_attribute("Party")?,
+                )?,
+                status: from_cvrf_name(
+                    INVOLVEMENT_STATUSES,
+                    "Involvement Status",
+                    involvement.required_attribute("Status")?,
+                )?,
+                date: None,
+                summary: involvement.text("Description").map(str::to_string),
+            })
+        })
+        .collect::<Result<Vec<_>, CvrfError>>()?;
+
+    let threats = element
+        .children("Threats")
+        .flat_map(|threats| threats.children("Threat"))
+        .map(|threat| {
+            Ok(Threat {
+                category: from_cvrf_name(
+                    THREAT_TYPES,
+                    "Threat Type",
+                    threat.required_attribute("Type")?,
+                )?,
+                details: threat.required_text("Description")?.to_string(),
+                date: threat
+                    .attribute("Date")
+                    .map(|date| parse_date(date, "Threat Date"))
+                    .transpose()?,
+                group_ids: non_empty(threat.texts("GroupID").map(str::to_string).collect()),
+                product_ids: non_empty(threat.texts("ProductID").map(product_id).collect()),
+            })
+        })
+        .collect::<Result<Vec<_>, CvrfError>>()?;
+
+    let remediations = element
+        .children("Remediations")
+        .flat_map(|remediations| remediations.children("Remediation"))
+        .map(|remediation| {
+            Ok(Remediation {
+                category: from_cvrf_name(
+                    REMEDIATION_TYPES,
+                    "Remediation Type",
+                    remediation.required_attribute("Type")?,
+                )?,
+                details: remediation.required_text("Description")?.to_string(),
+                date: remediation
+                    .attribute("Date")
+                    .map(|date| parse_date(date, "Remediation Date"))
+                    .transpose()?,
+                entitlements: non_empty(
+                    remediation
+                        .texts("Entitlement")
+                        .map(str::to_string)
+                        .collect(),
+                ),
+                group_ids: non_empty(remediation.texts("GroupID").map(str::to_string).collect()),
+                product_ids: non_empty(remediation.texts("ProductID").map(product_id).collect()),
+                restart_required: None,
+                url: remediation
+                    .text("URL")
+                    .map(|url| parse_url(url, "Remediation URL"))
+                    .transpose()?,
+            })
+        })
+        .collect::<Result<Vec<_>, CvrfError>>()?;
+
+    Ok(Vulnerability {
+        acknowledgments: element
+            .child("Acknowledgments")
+            .map(read_acknowledgments)
+            .transpose()?,
+        cve: element.text("CVE").map(str::to_string),
+        cwe: element
+            .child("CWE")
+            .map(|cwe| -> Result<_, CvrfError> {
+                let id = cwe.required_attribute("ID")?;
+                Cwe::try_new(id, &cwe.text).map_err(|_| CvrfError::Invalid {
+                    field: "CWE ID",
+                    value: id.to_string(),
+                })
+            })
+            .transpose()?,
+        discovery_date: element
+            .text("DiscoveryDate")
+            .map(|date| parse_date(date, "DiscoveryDate"))
+            .transpose()?,
+        flags: None,
+        ids: element
+            .child("ID")
+            .map(|id| -> Result<_, CvrfError> {
+                Ok(vec![VulnerabilityId {
+                    system_name: id.required_attribute("SystemName")?.to_string(),
+                    text: id.text.clone(),
+                }])
+            })
+            .transpose()?,
+        involvements: non_empty(involvements),
+        notes: element.child("Notes").map(read_notes).transpose()?,
+        product_status,
+        references: element
+            .child("References")
+            .map(read_references)
+            .transpose()?,
+        release_date: element
+            .text("ReleaseDate")
+            .map(|date| parse_date(date, "ReleaseDate"))
+            .transpose()?,
+        remediations: non_empty(remediations).map(Remediations),
+        scores: non_empty(scores),
+        threats: non_empty(threats),
+        title: element.text("Title").map(str::to_string),
+        disclosure_date: None,
+        metrics: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use quick_xml::Reader;
+
+    use super::*;
+
+    /// Reads the XML back, returning the text of every element with the given name
+    fn texts(xml: &str, name: &str) -> Vec<String> {
+        let mut reader = Reader::from_str(xml);
+        let mut output = Vec::new();
+        let mut inside = false;
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Start(e) if e.name().as_ref() == name.as_bytes() => inside = true,
+                Event::End(e) if e.name().as_ref() == name.as_bytes() => inside = false,
+                Event::Text(e) if inside => output.push(e.unescape().unwrap().into_owned()),
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        output
+    }
+
+    #[test]
+    fn exports_vulnerability() {
+        let csaf = crate::tests::fixtures::example();
+        let xml = csaf.to_cvrf_xml().unwrap();
+
+        assert_eq!(
+            texts(&xml, "DocumentTitle"),
+            vec![csaf.document.title.clone()]
+        );
+        assert_eq!(texts(&xml, "ID"), vec![csaf.document.tracking.id.clone()]);
+        assert_eq!(texts(&xml, "vuln:CVE"), vec!["CVE-2018-0171"]);
+        let affected = csaf.vulnerabilities.as_ref().unwrap()[0]
+            .product_status
+            .as_ref()
+            .unwrap()
+            .known_affected
+            .as_ref()
+            .unwrap()
+            .len();
+        assert!(texts(&xml, "vuln:ProductID").len() >= affected);
+        assert!(xml.contains(r#"<vuln:Status Type="Known Affected">"#));
+        assert_eq!(
+            texts(&xml, "vuln:VectorV3"),
+            vec![csaf.vulnerabilities.as_ref().unwrap()[0]
+                .scores
+                .as_ref()
+                .unwrap()[0]
+                .cvss_v3
+                .as_ref()
+                .unwrap()
+                .to_string()]
+        );
+    }
+
+    #[test]
+    fn exports_document_metadata() {
+        let csaf: Csaf = serde_json::from_str(include_str!(
+            "../../tests/cvrf-rhba-2018-0489-modified.json"
+        ))
+        .unwrap();
+        let xml = csaf.to_cvrf_xml().unwrap();
+
+        assert_eq!(
+            texts(&xml, "Note").len(),
+            csaf.document.notes.as_ref().unwrap().len()
+        );
+        assert!(xml.contains(r#"Type="Legal Disclaimer""#));
+        assert_eq!(
+            texts(&xml, "DocumentDistribution"),
+            vec![csaf.document.distribution.unwrap().text.unwrap()]
+        );
+        assert!(!texts(&xml, "prod:FullProductName").is_empty());
+    }
+
+    #[test]
+    fn roundtrips_through_cvrf() {
+        let csaf = crate::tests::fixtures::example();
+        let parsed = Csaf::from_cvrf_xml(csaf.to_cvrf_xml().unwrap().as_bytes()).unwrap();
+
+        assert_eq!(parsed.product_tree, csaf.product_tree);
+        assert_eq!(parsed.vulnerabilities, csaf.vulnerabilities);
+        assert_eq!(parsed.document.title, csaf.document.title);
+        assert_eq!(parsed.document.tracking.id, csaf.document.tracking.id);
+        assert_eq!(
+            parsed.document.tracking.revision_history,
+            csaf.document.tracking.revision_history
+        );
+        assert_eq!(parsed.document.notes, csaf.document.notes);
+        assert_eq!(parsed.document.references, csaf.document.references);
+    }
+
+    #[test]
+    fn parses_prefixed_namespace() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<cvrf:cvrfdoc xmlns:cvrf="http://docs.oasis-open.org/csaf/ns/csaf-cvrf/v1.2/cvrf"
+    xmlns:prod="http://docs.oasis-open.org/csaf/ns/csaf-cvrf/v1.2/prod"
+    xmlns:vuln="http://docs.oasis-open.org/csaf/ns/csaf-cvrf/v1.2/vuln">
+  <cvrf:DocumentTitle>Example &amp; advisory</cvrf:DocumentTitle>
+  <cvrf:DocumentType>Security Advisory</cvrf:DocumentType>
+  <cvrf:DocumentPublisher Type="Vendor"/>
+  <cvrf:DocumentTracking>
+    <cvrf:Identification><cvrf:ID>EX-2018-0001</cvrf:ID></cvrf:Identification>
+    <cvrf:Status>Final</cvrf:Status>
+    <cvrf:Version>1</cvrf:Version>
+    <cvrf:RevisionHistory>
+      <cvrf:Revision>
+        <cvrf:Number>1</cvrf:Number>
+        <cvrf:Date>2018-03-28T16:00:00</cvrf:Date>
+        <cvrf:Description>Initial release</cvrf:Description>
+      </cvrf:Revision>
+    </cvrf:RevisionHistory>
+    <cvrf:InitialReleaseDate>2018-03-28T16:00:00</cvrf:InitialReleaseDate>
+    <cvrf:CurrentReleaseDate>2018-03-28T16:00:00Z</cvrf:CurrentReleaseDate>
+  </cvrf:DocumentTracking>
+  <cvrf:DocumentReferences>
+    <cvrf:Reference Type="Self">
+      <cvrf:URL>https://example.com/advisories/EX-2018-0001</cvrf:URL>
+      <cvrf:Description>Advisory</cvrf:Description>
+    </cvrf:Reference>
+  </cvrf:DocumentReferences>
+  <prod:ProductTree>
+    <prod:Branch Type="Vendor" Name="Example">
+      <prod:Branch Type="Product Version" Name="1.0">
+        <prod:FullProductName ProductID="EX-1">Example 1.0</prod:FullProductName>
+      </prod:Branch>
+    </prod:Branch>
+  </prod:ProductTree>
+  <vuln:Vulnerability Ordinal="1">
+    <vuln:CVE>CVE-2018-0001</vuln:CVE>
+    <vuln:ProductStatuses>
+      <vuln:Status Type="Known Affected"><vuln:ProductID>EX-1</vuln:ProductID></vuln:Status>
+    </vuln:ProductStatuses>
+  </vuln:Vulnerability>
+</cvrf:cvrfdoc>"#;
+        let csaf = Csaf::from_cvrf_xml(xml.as_bytes()).unwrap();
+
+        assert_eq!(csaf.document.title, "Example & advisory");
+        assert_eq!(csaf.document.publisher.name, "example.com");
+        assert_eq!(
+            csaf.document.publisher.namespace.as_str(),
+            "https://example.com/"
+        );
+        assert_eq!(
+            csaf.document.tracking.initial_release_date,
+            csaf.document.tracking.current_release_date
+        );
+        let branches = &csaf
+            .product_tree
+            .as_ref()
+            .unwrap()
+            .branches
+            .as_ref()
+            .unwrap()
+            .0;
+        let version = &branches[0].branches.as_ref().unwrap().0[0];
+        assert_eq!(version.category, BranchCategory::ProductVersion);
+        assert_eq!(
+            version.product.as_ref().unwrap().product_id,
+            ProductIdT("EX-1".to_string())
+        );
+        assert_eq!(
+            csaf.vulnerabilities_for_product(&ProductIdT("EX-1".to_string()))
+                .map(|(_, status)| status)
+                .collect::<Vec<_>>(),
+            vec![ProductStatusKind::KnownAffected]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_types() {
+        let csaf = crate::tests::fixtures::example();
+        let xml = csaf
+            .to_cvrf_xml()
+            .unwrap()
+            .replace("Known Affected", "Possibly Affected");
+        assert!(matches!(
+            Csaf::from_cvrf_xml(xml.as_bytes()),
+            Err(CvrfError::Invalid {
+                field: "Status Type",
+                ..
+            })
+        ));
+    }
+
+    #[cfg(feature = "csaf_2_1")]
+    #[test]
+    fn csaf_2_1_values_are_unsupported() {
+        let mut csaf = crate::tests::fixtures::example();
+        let vulnerability = &mut csaf.vulnerabilities.as_mut().unwrap()[0];
+        vulnerability.remediations.as_mut().unwrap()[0].category = RemediationCategory::FixPlanned;
+        assert!(matches!(
+            csaf.to_cvrf_xml(),
+            Err(CvrfError::Unsupported {
+                field: "Remediation Type",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,485 @@
+//! Conversion from [GitHub Security Advisories](https://docs.github.com/en/rest/security-advisories/global-advisories),
+//! as returned by the GitHub REST API.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{child_branches, non_empty};
+use crate::{
+    definitions::{
+        Acknowledgment, Branch, BranchCategory, BranchesT, FullProductName, Note, NoteCategory,
+        ProductIdT, Reference, ReferenceCategory,
+    },
+    document::{
+        AggregateSeverity, Category, CsafVersion, Document, Generator, Publisher,
+        PublisherCategory, Revision, Status, Tracking,
+    },
+    product_tree::ProductTree,
+    vulnerability::{
+        Cwe, ProductStatus, Remediation, RemediationCategory, Remediations, Score, Vulnerability,
+        VulnerabilityId,
+    },
+    Csaf,
+};
+
+/// [Global security advisory](https://docs.github.com/en/rest/security-advisories/global-advisories#get-a-global-security-advisory)
+///
+/// Only the fields used by the conversion to `Csaf` are modelled.
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GhsaAdvisory {
+    pub ghsa_id: String,
+    pub cve_id: Option<String>,
+    pub html_url: Option<Url>,
+    pub summary: String,
+    pub description: Option<String>,
+    pub severity: Option<GhsaSeverity>,
+    pub identifiers: Option<Vec<GhsaIdentifier>>,
+    pub references: Option<Vec<String>>,
+    pub published_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub withdrawn_at: Option<DateTime<Utc>>,
+    pub vulnerabilities: Option<Vec<GhsaVulnerability>>,
+    pub cvss: Option<GhsaCvss>,
+    pub cwes: Option<Vec<GhsaCwe>>,
+    pub credits: Option<Vec<GhsaCredit>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GhsaSeverity {
+    Low,
+    Medium,
+    High,
+    Critical,
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GhsaIdentifier {
+    #[serde(rename = "type")]
+    pub identifier_type: String,
+    pub value: String,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GhsaVulnerability {
+    pub package: Option<GhsaPackage>,
+    pub vulnerable_version_range: Option<String>,
+    pub first_patched_version: Option<String>,
+    pub vulnerable_functions: Option<Vec<String>>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GhsaPackage {
+    pub ecosystem: String,
+    pub name: Option<String>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GhsaCvss {
+    pub vector_string: Option<String>,
+    pub score: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GhsaCwe {
+    pub cwe_id: String,
+    pub name: String,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GhsaCredit {
+    pub user: GhsaUser,
+    #[serde(rename = "type")]
+    pub credit_type: Option<String>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GhsaUser {
+    pub login: String,
+    pub html_url: Option<Url>,
+}
+
+// ASSUMPTIONS:
+// GHSA records only the latest update, so at most two revisions (published and updated) are recorded.
+//
+// Each vulnerable package becomes a product_version_range branch for its vulnerable range, plus a product_version
+// branch for its first patched version, nested under ecosystem (vendor) and package name branches.
+
+/// Provides a conversion from a [GhsaAdvisory] to a `Csaf` with the
+/// [security advisory profile](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#44-profile-4-security-advisory)
+impl From<GhsaAdvisory> for Csaf {
+    fn from(input: GhsaAdvisory) -> Self {
+        let mut revision_history = vec![Revision {
+            date: input.published_at,
+            number: "1".to_string(),
+            summary: "Initial publication".to_string(),
+            legacy_version: None,
+        }];
+        if input.updated_at > input.published_at {
+            revision_history.push(Revision {
+                date: input.updated_at,
+                number: "2".to_string(),
+                summary: "Last update in GitHub Advisory Database".to_string(),
+                legacy_version: None,
+            });
+        }
+        let version = revision_history.len().to_string();
+
+        let ids = input
+            .identifiers
+            .iter()
+            .flatten()
+            .filter(|id| id.identifier_type != "CVE")
+            .map(|id| VulnerabilityId {
+                system_name: id.identifier_type.clone(),
+                text: id.value.clone(),
+            })
+            .collect::<Vec<_>>();
+        let aliases = input
+            .identifiers
+            .iter()
+            .flatten()
+            .map(|id| id.value.clone())
+            .filter(|id| *id != input.ghsa_id)
+            .collect::<Vec<_>>();
+
+        let mut branches = Vec::new();
+        let mut known_affected = Vec::new();
+        let mut fixed = Vec::new();
+        let mut id_counter: usize = 1;
+
+        for vulnerability in input.vulnerabilities.iter().flatten() {
+            let (ecosystem, name) = match &vulnerability.package {
+                Some(GhsaPackage {
+                    ecosystem,
+                    name: Some(name),
+                }) => (ecosystem, name),
+                _ => continue,
+            };
+            let vendor = child_branches(&mut branches, ecosystem, BranchCategory::Vendor);
+            let leaves = child_branches(vendor, name, BranchCategory::ProductName);
+
+            let mut leaf = |version: String, category: BranchCategory| -> ProductIdT {
+                if let Some(product) = leaves
+                    .iter()
+                    .filter(|leaf| leaf.name == version && leaf.category == category)
+                    .find_map(|leaf| leaf.product.as_ref())
+                {
+                    return product.product_id.clone();
+                }
+                let product_id = ProductIdT(format!("{}-{}", name.to_uppercase(), id_counter));
+                id_counter += 1;
+                leaves.push(Branch {
+                    name: version.clone(),
+                    category,
+                    product: Some(FullProductName {
+                        name: format!("{} {}", name, version),
+                        product_id: product_id.clone(),
+                        product_identification_helper: None,
+                    }),
+                    branches: None,
+                });
+                product_id
+            };
+
+            if let Some(range) = &vulnerability.vulnerable_version_range {
+                known_affected.push(leaf(
+                    vers_range(ecosystem, range),
+                    BranchCategory::ProductVersionRange,
+                ));
+            }
+            if let Some(patched) = &vulnerability.first_patched_version {
+                let id = leaf(patched.clone(), BranchCategory::ProductVersion);
+                if !fixed.contains(&id) {
+                    fixed.push(id);
+                }
+            }
+        }
+
+        let score = input
+            .cvss
+            .as_ref()
+            .and_then(|cvss| cvss.vector_string.as_deref())
+            .filter(|_| !known_affected.is_empty())
+            .and_then(|vector| {
+                let mut score = Score {
+                    products: known_affected.clone(),
+                    cvss_v2: None,
+                    cvss_v3: None,
+                    cvss_v4: None,
+                };
+                if vector.starts_with("CVSS:4") {
+                    score.cvss_v4 = Some(vector.parse().ok()?);
+                } else {
+                    score.cvss_v3 = Some(vector.parse().ok()?);
+                }
+                Some(score)
+            });
+
+        let mut notes = vec![Note {
+            category: NoteCategory::Summary,
+            text: input.summary.clone(),
+            audience: None,
+            title: None,
+        }];
+        if let Some(description) = input.description {
+            notes.push(Note {
+                category: NoteCategory::Description,
+                text: description,
+                audience: None,
+                title: None,
+            });
+        }
+        if let Some(withdrawn) = input.withdrawn_at {
+            notes.push(Note {
+                category: NoteCategory::General,
+                text: format!(
+                    "Withdrawn from the GitHub Advisory Database on {}",
+                    withdrawn
+                ),
+                audience: None,
+                title: Some("Withdrawn".to_string()),
+            });
+        }
+
+        let references = input
+            .html_url
+            .iter()
+            .map(|url| Reference {
+                url: url.clone(),
+                summary: input.ghsa_id.clone(),
+                category: Some(ReferenceCategory::RefSelf),
+            })
+            .chain(
+                input
+                    .references
+                    .iter()
+                    .flatten()
+                    .filter_map(|url| Url::parse(url).ok())
+                    .filter(|url| Some(url) != input.html_url.as_ref())
+                    .map(|url| Reference {
+                        summary: url.to_string(),
+                        url,
+                        category: Some(ReferenceCategory::External),
+                    }),
+            )
+            .collect::<Vec<_>>();
+
+        let acknowledgments = input
+            .credits
+            .into_iter()
+            .flatten()
+            .map(|credit| Acknowledgment {
+                names: Some(vec![credit.user.login]),
+                summary: credit.credit_type,
+                urls: credit.user.html_url.map(|url| vec![url]),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        Csaf {
+            document: Document {
+                category: Category::SecurityAdvisory,
+                publisher: Publisher {
+                    category: PublisherCategory::Coordinator,
+                    name: "GitHub".to_string(),
+                    namespace: Url::parse("https://github.com/advisories").unwrap(),
+                    contact_details: None,
+                    issuing_authority: None,
+                },
+                title: input.summary.clone(),
+                tracking: Tracking {
+                    current_release_date: input.updated_at.max(input.published_at),
+                    id: input.ghsa_id.clone(),
+                    initial_release_date: input.published_at,
+                    revision_history,
+                    status: Status::Final,
+                    version,
+                    aliases: non_empty(aliases),
+                    generator: Some(Generator::default()),
+                },
+                csaf_version: CsafVersion::TwoDotZero,
+                acknowledgments: None,
+                aggregate_severity: input.severity.and_then(|severity| {
+                    Some(AggregateSeverity {
+                        text: match severity {
+                            GhsaSeverity::Low => "Low",
+                            GhsaSeverity::Medium => "Medium",
+                            GhsaSeverity::High => "High",
+                            GhsaSeverity::Critical => "Critical",
+                            GhsaSeverity::Unknown => return None,
+                        }
+                        .to_string(),
+                        namespace: None,
+                    })
+                }),
+                distribution: None,
+                lang: None,
+                notes: None,
+                references: non_empty(references),
+                source_lang: None,
+                license_expression: None,
+            },
+            product_tree: non_empty(branches).map(|branches| ProductTree {
+                branches: Some(BranchesT(branches)),
+                ..Default::default()
+            }),
+            vulnerabilities: Some(vec![Vulnerability {
+                acknowledgments: non_empty(acknowledgments),
+                cve: input.cve_id,
+                // CSAF 2.0 allows a single CWE per vulnerability, so take the first valid one
+                cwe: input
+                    .cwes
+                    .into_iter()
+                    .flatten()
+                    .find_map(|cwe| Cwe::try_new(&cwe.cwe_id, &cwe.name).ok()),
+                discovery_date: None,
+                flags: None,
+                ids: non_empty(ids),
+                involvements: None,
+                notes: Some(notes),
+                product_status: if fixed.is_empty() && known_affected.is_empty() {
+                    None
+                } else {
+                    Some(ProductStatus {
+                        fixed: non_empty(fixed.clone()),
+                        known_affected: non_empty(known_affected.clone()),
+                        ..Default::default()
+                    })
+                },
+                references: None,
+                release_date: Some(input.published_at),
+                remediations: if fixed.is_empty() || known_affected.is_empty() {
+                    None
+                } else {
+                    Some(Remediations(vec![Remediation {
+                        category: RemediationCategory::VendorFix,
+                        details: "Upgrade to a patched version".to_string(),
+                        date: None,
+                        entitlements: None,
+                        group_ids: None,
+                        product_ids: Some(known_affected),
+                        restart_required: None,
+                        url: None,
+                    }]))
+                },
+                scores: score.map(|score| vec![score]),
+                threats: None,
+                title: Some(input.summary),
+                disclosure_date: None,
+                metrics: None,
+            }]),
+        }
+    }
+}
+
+/// Expresses a GHSA version range like `>= 0.8.0, < 0.8.1` as a [vers](https://github.com/package-url/purl-spec/blob/version-range-spec/VERSION-RANGE-SPEC.rst)
+/// string, as recommended for `product_version_range` branches
+fn vers_range(ecosystem: &str, range: &str) -> String {
+    let scheme = match ecosystem {
+        "rust" => "cargo",
+        "pip" => "pypi",
+        "go" => "golang",
+        "rubygems" => "gem",
+        "erlang" => "hex",
+        _ => ecosystem,
+    };
+    let constraints = range
+        .split(',')
+        .map(|constraint| {
+            let constraint: String = constraint.split_whitespace().collect();
+            // vers expresses equality with the bare version
+            constraint
+                .strip_prefix('=')
+                .map(str::to_string)
+                .unwrap_or(constraint)
+        })
+        .collect::<Vec<_>>();
+    format!("vers:{}/{}", scheme, constraints.join("|"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> GhsaAdvisory {
+        serde_json::from_str(include_str!("../../tests/GHSA-pqqp-xmhj-wgcw.json")).unwrap()
+    }
+
+    #[test]
+    fn example_advisory_converts() {
+        let document = Csaf::from(example());
+        assert_eq!(document.validate(), Ok(()));
+
+        let tracking = &document.document.tracking;
+        assert_eq!(tracking.id, "GHSA-pqqp-xmhj-wgcw");
+        assert_eq!(tracking.aliases, Some(vec!["CVE-2021-32810".to_string()]));
+        assert_eq!(
+            document.document.aggregate_severity.as_ref().unwrap().text,
+            "Critical"
+        );
+
+        let branches = document.product_tree.unwrap().branches.unwrap();
+        assert_eq!(branches.0.len(), 1);
+        let package = &branches.0[0].branches.as_ref().unwrap().0[0];
+        let names: Vec<_> = package
+            .branches
+            .as_ref()
+            .unwrap()
+            .0
+            .iter()
+            .map(|b| b.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "vers:cargo/<0.7.4",
+                "0.7.4",
+                "vers:cargo/>=0.8.0|<0.8.1",
+                "0.8.1"
+            ]
+        );
+
+        let vulnerability = &document.vulnerabilities.unwrap()[0];
+        assert_eq!(vulnerability.cve.as_deref(), Some("CVE-2021-32810"));
+        assert_eq!(vulnerability.cwe.as_ref().unwrap().id.as_str(), "CWE-362");
+        let scores = vulnerability.scores.as_ref().unwrap();
+        assert_eq!(scores[0].products.len(), 2);
+        assert!(scores[0].cvss_v3.is_some());
+    }
+
+    #[test]
+    fn missing_optional_fields() {
+        let json = r#"{
+            "ghsa_id": "GHSA-xxxx-xxxx-xxxx",
+            "summary": "Example",
+            "severity": "unknown",
+            "published_at": "2023-01-01T00:00:00Z",
+            "updated_at": "2023-01-01T00:00:00Z",
+            "cvss": { "vector_string": null, "score": null },
+            "vulnerabilities": [
+                { "package": { "ecosystem": "npm", "name": null }, "vulnerable_version_range": "= 1.0.0" }
+            ]
+        }"#;
+        let document = Csaf::from(serde_json::from_str::<GhsaAdvisory>(json).unwrap());
+        assert_eq!(document.validate(), Ok(()));
+        assert!(document.document.aggregate_severity.is_none());
+        assert!(document.product_tree.is_none());
+        let vulnerability = &document.vulnerabilities.unwrap()[0];
+        assert!(vulnerability.product_status.is_none());
+        assert!(vulnerability.scores.is_none());
+    }
+
+    #[test]
+    fn equality_ranges() {
+        assert_eq!(vers_range("npm", "= 1.0.0"), "vers:npm/1.0.0");
+    }
+}
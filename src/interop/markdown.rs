@@ -0,0 +1,300 @@
+//! Conversion from advisories written as Markdown with YAML frontmatter, a format many projects without a formal
+//! advisory database use
+//!
+//! ```text
+//! ---
+//! id: EXAMPLE-2023-0001
+//! title: Heap overflow in parser
+//! date: 2023-03-01
+//! publisher:
+//!   category: vendor
+//!   name: Example Project
+//!   namespace: https://example.com
+//! package: example-parser
+//! cve: CVE-2023-12345
+//! cvss: CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H
+//! patched:
+//!   - ">= 1.2.3"
+//! ---
+//!
+//! The parser writes past the end of its buffer when ...
+//! ```
+
+use std::fmt::Display;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use serde::Deserialize;
+
+use super::non_empty;
+use crate::{
+    definitions::{
+        Branch, BranchCategory, BranchesT, FullProductName, Note, NoteCategory, ProductIdT,
+    },
+    document::{Category, CsafVersion, Document, Generator, Publisher, Revision, Status, Tracking},
+    product_tree::ProductTree,
+    vulnerability::{
+        ProductStatus, Remediation, RemediationCategory, Remediations, Score, Vulnerability,
+        VulnerabilityId,
+    },
+    Csaf,
+};
+
+/// The YAML frontmatter of a Markdown advisory
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct AdvisoryFrontmatter {
+    pub id: String,
+    pub title: String,
+    pub date: NaiveDate,
+    /// CSAF requires a publisher, which informal advisories rarely name, so it must be given in full
+    pub publisher: Publisher,
+    /// The affected package, or else the title is used as the product name
+    pub package: Option<String>,
+    pub cve: Option<String>,
+    pub cvss: Option<cvss::v3::Base>,
+    /// Version requirements such as `>= 1.2.3` that are not affected because they contain the fix
+    #[serde(default)]
+    pub patched: Vec<String>,
+}
+
+/// Failure to read a Markdown advisory
+#[derive(Debug)]
+pub enum MarkdownError {
+    /// The text does not start with frontmatter between `---` lines
+    MissingFrontmatter,
+    /// The frontmatter is not valid YAML or is missing required fields
+    Frontmatter(serde_yaml::Error),
+}
+
+impl Display for MarkdownError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingFrontmatter => write!(f, "advisory has no frontmatter between --- lines"),
+            Self::Frontmatter(source) => write!(f, "invalid advisory frontmatter: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for MarkdownError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Frontmatter(source) => Some(source),
+            Self::MissingFrontmatter => None,
+        }
+    }
+}
+
+// ASSUMPTIONS:
+// Informal advisories have no history, so the date is both the initial and current release date.
+//
+// Each patched requirement becomes a fixed product_version_range branch. The versions outside of all of them are the
+// single known affected product, as the frontmatter gives no separate affected ranges.
+
+impl Csaf {
+    /// Converts a Markdown advisory with YAML frontmatter, see [AdvisoryFrontmatter], to a `Csaf` with the
+    /// [security advisory profile](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#44-profile-4-security-advisory).
+    /// The Markdown body becomes the description of the vulnerability.
+    pub fn from_advisory_markdown(text: &str) -> Result<Csaf, MarkdownError> {
+        let (frontmatter, body) =
+            split_frontmatter(text).ok_or(MarkdownError::MissingFrontmatter)?;
+        let frontmatter: AdvisoryFrontmatter =
+            serde_yaml::from_str(frontmatter).map_err(MarkdownError::Frontmatter)?;
+        Ok(convert(frontmatter, body.trim()))
+    }
+}
+
+/// Splits the text into the frontmatter and the body that follows it
+fn split_frontmatter(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_start_matches('\u{feff}');
+    let rest = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn convert(input: AdvisoryFrontmatter, body: &str) -> Csaf {
+    let date = Utc.from_utc_datetime(&input.date.and_hms_opt(0, 0, 0).unwrap());
+    let product_name = input.package.as_deref().unwrap_or(&input.title);
+
+    let mut leaves = Vec::new();
+    let mut leaf = |name: String| -> ProductIdT {
+        let product_id = ProductIdT(format!("{}-{}", input.id, leaves.len() + 1));
+        leaves.push(Branch {
+            name: name.clone(),
+            category: BranchCategory::ProductVersionRange,
+            product: Some(FullProductName {
+                name: format!("{} {}", product_name, name),
+                product_id: product_id.clone(),
+                product_identification_helper: None,
+            }),
+            branches: None,
+        });
+        product_id
+    };
+    let known_affected = vec![leaf(if input.patched.is_empty() {
+        "all versions".to_string()
+    } else {
+        format!("versions not matching {}", input.patched.join(" or "))
+    })];
+    let fixed = input
+        .patched
+        .iter()
+        .map(|requirement| leaf(requirement.clone()))
+        .collect::<Vec<_>>();
+
+    let notes = if body.is_empty() {
+        None
+    } else {
+        Some(vec![Note {
+            category: NoteCategory::Description,
+            text: body.to_string(),
+            audience: None,
+            title: None,
+        }])
+    };
+
+    Csaf {
+        document: Document {
+            category: Category::SecurityAdvisory,
+            publisher: input.publisher,
+            title: input.title.clone(),
+            tracking: Tracking {
+                current_release_date: date,
+                id: input.id.clone(),
+                initial_release_date: date,
+                revision_history: vec![Revision {
+                    date,
+                    number: "1".to_string(),
+                    summary: "Initial publication".to_string(),
+                    legacy_version: None,
+                }],
+                status: Status::Final,
+                version: "1".to_string(),
+                aliases: input
+                    .cve
+                    .clone()
+                    .filter(|cve| *cve != input.id)
+                    .map(|cve| vec![cve]),
+                generator: Some(Generator::default()),
+            },
+            csaf_version: CsafVersion::TwoDotZero,
+            acknowledgments: None,
+            aggregate_severity: None,
+            distribution: None,
+            lang: None,
+            notes: None,
+            references: None,
+            source_lang: None,
+            license_expression: None,
+        },
+        product_tree: Some(ProductTree {
+            branches: Some(BranchesT(vec![Branch {
+                name: product_name.to_string(),
+                category: BranchCategory::ProductName,
+                product: None,
+                branches: Some(BranchesT(leaves)),
+            }])),
+            ..Default::default()
+        }),
+        vulnerabilities: Some(vec![Vulnerability {
+            cve: input.cve,
+            ids: Some(vec![VulnerabilityId {
+                system_name: input.id.split('-').next().unwrap_or(&input.id).to_string(),
+                text: input.id,
+            }]),
+            notes,
+            product_status: Some(ProductStatus {
+                fixed: non_empty(fixed.clone()),
+                known_affected: Some(known_affected.clone()),
+                ..Default::default()
+            }),
+            release_date: Some(date),
+            remediations: if fixed.is_empty() {
+                None
+            } else {
+                Some(Remediations(vec![Remediation {
+                    category: RemediationCategory::VendorFix,
+                    details: format!(
+                        "Upgrade to a version matching {}",
+                        input.patched.join(" or ")
+                    ),
+                    date: None,
+                    entitlements: None,
+                    group_ids: None,
+                    product_ids: Some(known_affected.clone()),
+                    restart_required: None,
+                    url: None,
+                }]))
+            },
+            scores: input.cvss.map(|cvss| {
+                vec![Score {
+                    products: known_affected,
+                    cvss_v2: None,
+                    cvss_v3: Some(cvss),
+                    cvss_v4: None,
+                }]
+            }),
+            title: Some(input.title),
+            ..Default::default()
+        }]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "---
+id: EXAMPLE-2023-0001
+title: Heap overflow in parser
+date: 2023-03-01
+publisher:
+  category: vendor
+  name: Example Project
+  namespace: https://example.com
+package: example-parser
+cve: CVE-2023-12345
+cvss: CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H
+patched:
+  - \">= 1.2.3\"
+---
+
+The parser writes past the end of its buffer.
+";
+
+    #[test]
+    fn markdown_advisory_converts() {
+        let document = Csaf::from_advisory_markdown(EXAMPLE).unwrap();
+        assert_eq!(document.validate(), Ok(()));
+        assert_eq!(document.document.category, Category::SecurityAdvisory);
+        assert_eq!(document.document.tracking.id, "EXAMPLE-2023-0001");
+
+        let vulnerability = &document.vulnerabilities.unwrap()[0];
+        assert_eq!(vulnerability.cve.as_deref(), Some("CVE-2023-12345"));
+        assert_eq!(
+            vulnerability.notes.as_ref().unwrap()[0].text,
+            "The parser writes past the end of its buffer."
+        );
+        let status = vulnerability.product_status.as_ref().unwrap();
+        assert_eq!(status.fixed.as_ref().unwrap().len(), 1);
+        assert!(vulnerability.scores.as_ref().unwrap()[0].cvss_v3.is_some());
+    }
+
+    #[test]
+    fn frontmatter_is_required() {
+        assert!(matches!(
+            Csaf::from_advisory_markdown("# Heap overflow in parser"),
+            Err(MarkdownError::MissingFrontmatter)
+        ));
+        assert!(matches!(
+            Csaf::from_advisory_markdown("---\nid: EXAMPLE\n---\n"),
+            Err(MarkdownError::Frontmatter(_))
+        ));
+    }
+}
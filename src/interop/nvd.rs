@@ -0,0 +1,495 @@
+//! Conversion from CVE records returned by the [NVD CVE API 2.0](https://nvd.nist.gov/developers/vulnerabilities)
+//!
+//! Only the subset of the API used by the conversion is modelled in [schema].
+
+use std::fmt::Display;
+
+use url::Url;
+
+use super::{child_branches, non_empty};
+use crate::{
+    definitions::{
+        Branch, BranchCategory, BranchesT, Cpe, CpeParseError, FullProductName, Note, NoteCategory,
+        ProductIdT, ProductIdentificationHelper, Reference, ReferenceCategory,
+    },
+    document::{
+        Category, CsafVersion, Document, Generator, Publisher, PublisherCategory, Revision, Status,
+        Tracking,
+    },
+    product_tree::ProductTree,
+    vulnerability::{ProductStatus, Score, Vulnerability},
+    Csaf,
+};
+
+use schema::{CpeMatch, CveItem, CvssMetric};
+
+/// [NVD CVE API 2.0 schema](https://csrc.nist.gov/schema/nvd/api/2.0/cve_api_json_2.0.schema) types
+pub mod schema {
+    use chrono::{DateTime, NaiveDateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serialize};
+
+    /// An entry of the `vulnerabilities` list in an API response
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct CveEntry {
+        pub cve: CveItem,
+    }
+
+    /// The `cve` object of a vulnerability
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CveItem {
+        pub id: String,
+        pub source_identifier: Option<String>,
+        #[serde(deserialize_with = "nvd_date")]
+        pub published: DateTime<Utc>,
+        #[serde(deserialize_with = "nvd_date")]
+        pub last_modified: DateTime<Utc>,
+        pub vuln_status: Option<String>,
+        pub descriptions: Vec<LangString>,
+        pub metrics: Option<Metrics>,
+        pub configurations: Option<Vec<Configuration>>,
+        pub references: Option<Vec<Reference>>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct LangString {
+        pub lang: String,
+        pub value: String,
+    }
+
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Metrics {
+        pub cvss_metric_v31: Option<Vec<CvssMetric>>,
+        pub cvss_metric_v30: Option<Vec<CvssMetric>>,
+        pub cvss_metric_v2: Option<Vec<CvssMetric>>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CvssMetric {
+        pub source: String,
+        /// `Primary` for the NVD's own score, `Secondary` for scores from other sources
+        #[serde(rename = "type")]
+        pub metric_type: String,
+        pub cvss_data: CvssData,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CvssData {
+        pub version: String,
+        pub vector_string: String,
+        pub base_score: f64,
+    }
+
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Configuration {
+        pub operator: Option<String>,
+        pub nodes: Vec<Node>,
+    }
+
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Node {
+        pub operator: String,
+        pub negate: Option<bool>,
+        pub cpe_match: Vec<CpeMatch>,
+    }
+
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CpeMatch {
+        pub vulnerable: bool,
+        pub criteria: String,
+        pub match_criteria_id: Option<String>,
+        pub version_start_including: Option<String>,
+        pub version_start_excluding: Option<String>,
+        pub version_end_including: Option<String>,
+        pub version_end_excluding: Option<String>,
+    }
+
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Reference {
+        pub url: String,
+        pub source: Option<String>,
+        pub tags: Option<Vec<String>>,
+    }
+
+    /// The API gives timestamps without an offset, which are in UTC
+    fn nvd_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        match DateTime::parse_from_rfc3339(&value) {
+            Ok(date) => Ok(date.with_timezone(&Utc)),
+            Err(_) => NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S%.f")
+                .map(|date| DateTime::from_naive_utc_and_offset(date, Utc))
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Reasons an NVD CVE record could not be converted
+#[derive(Debug)]
+pub enum NvdConvertError {
+    /// The JSON is not an NVD CVE record
+    Json(serde_json::Error),
+    /// A configuration lists a CPE match criteria that is not a valid [Cpe]
+    InvalidCpe {
+        criteria: String,
+        source: CpeParseError,
+    },
+}
+
+impl Display for NvdConvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json(source) => write!(f, "invalid NVD CVE record: {}", source),
+            Self::InvalidCpe { criteria, source } => {
+                write!(f, "invalid CPE match criteria {:?}: {}", criteria, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NvdConvertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(source) => Some(source),
+            Self::InvalidCpe { source, .. } => Some(source),
+        }
+    }
+}
+
+// ASSUMPTIONS:
+// Every CPE match marked vulnerable is a known affected product, regardless of the configuration it appears in. Matches
+// that are not vulnerable only describe the platform a product runs on, so are left out.
+//
+// Products are nested under vendor and product name branches taken from the CPE. A match with a version range becomes a
+// product_version_range branch with a vers range using the generic scheme.
+
+/// Converts an NVD CVE record, either an entry of the API's `vulnerabilities` list or the `cve` object within it, to a
+/// `Csaf` with the [security advisory profile](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#44-profile-4-security-advisory)
+pub fn from_nvd_cve(nvd_json: &serde_json::Value) -> Result<Csaf, NvdConvertError> {
+    let cve = nvd_json.get("cve").unwrap_or(nvd_json);
+    let input: CveItem = serde_json::from_value(cve.clone()).map_err(NvdConvertError::Json)?;
+
+    let mut branches = Vec::new();
+    let mut known_affected: Vec<ProductIdT> = Vec::new();
+    let matches = input
+        .configurations
+        .iter()
+        .flatten()
+        .flat_map(|configuration| &configuration.nodes)
+        .flat_map(|node| &node.cpe_match)
+        .filter(|cpe_match| cpe_match.vulnerable);
+    for cpe_match in matches {
+        let id = leaf_product(&mut branches, cpe_match, known_affected.len() + 1)?;
+        if !known_affected.contains(&id) {
+            known_affected.push(id);
+        }
+    }
+
+    let metrics = input.metrics.as_ref();
+    let cvss_v3 = metrics
+        .and_then(|m| m.cvss_metric_v31.as_ref().or(m.cvss_metric_v30.as_ref()))
+        .and_then(|metrics| primary_vector(metrics))
+        .and_then(|vector| vector.parse().ok());
+    let cvss_v2 = metrics
+        .and_then(|m| m.cvss_metric_v2.as_ref())
+        .and_then(|metrics| primary_vector(metrics))
+        .and_then(|vector| vector.parse().ok());
+    let scores = if known_affected.is_empty() || (cvss_v3.is_none() && cvss_v2.is_none()) {
+        None
+    } else {
+        Some(vec![Score {
+            products: known_affected.clone(),
+            cvss_v2,
+            cvss_v3,
+            cvss_v4: None,
+        }])
+    };
+
+    let notes = input
+        .descriptions
+        .iter()
+        .map(|description| Note {
+            category: NoteCategory::Description,
+            text: description.value.clone(),
+            audience: None,
+            title: if description.lang == "en" {
+                None
+            } else {
+                Some(format!("Description ({})", description.lang))
+            },
+        })
+        .collect::<Vec<_>>();
+
+    let references = input
+        .references
+        .iter()
+        .flatten()
+        .filter_map(|reference| {
+            let url = Url::parse(&reference.url).ok()?;
+            Some(Reference {
+                summary: match &reference.tags {
+                    Some(tags) if !tags.is_empty() => tags.join(", "),
+                    _ => reference.url.clone(),
+                },
+                url,
+                category: Some(ReferenceCategory::External),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut revision_history = vec![Revision {
+        date: input.published,
+        number: "1".to_string(),
+        summary: "Initial publication".to_string(),
+        legacy_version: None,
+    }];
+    if input.last_modified > input.published {
+        revision_history.push(Revision {
+            date: input.last_modified,
+            number: "2".to_string(),
+            summary: "Last modification in the National Vulnerability Database".to_string(),
+            legacy_version: None,
+        });
+    }
+    let version = revision_history.len().to_string();
+    let detail_url = Url::parse("https://nvd.nist.gov/vuln/detail/")
+        .and_then(|url| url.join(&input.id))
+        .ok();
+
+    Ok(Csaf {
+        document: Document {
+            category: Category::SecurityAdvisory,
+            publisher: Publisher {
+                category: PublisherCategory::Coordinator,
+                name: "National Vulnerability Database".to_string(),
+                namespace: Url::parse("https://nvd.nist.gov").unwrap(),
+                contact_details: None,
+                issuing_authority: None,
+            },
+            title: input.id.clone(),
+            tracking: Tracking {
+                current_release_date: input.last_modified.max(input.published),
+                id: input.id.clone(),
+                initial_release_date: input.published,
+                revision_history,
+                status: Status::Final,
+                version,
+                aliases: None,
+                generator: Some(Generator::default()),
+            },
+            csaf_version: CsafVersion::TwoDotZero,
+            acknowledgments: None,
+            aggregate_severity: None,
+            distribution: None,
+            lang: None,
+            notes: None,
+            references: detail_url.map(|url| {
+                vec![Reference {
+                    url,
+                    summary: input.id.clone(),
+                    category: Some(ReferenceCategory::RefSelf),
+                }]
+            }),
+            source_lang: None,
+            license_expression: None,
+        },
+        product_tree: non_empty(branches).map(|branches| ProductTree {
+            branches: Some(BranchesT(branches)),
+            ..Default::default()
+        }),
+        vulnerabilities: Some(vec![Vulnerability {
+            cve: Some(input.id),
+            notes: non_empty(notes),
+            product_status: non_empty(known_affected).map(|known_affected| ProductStatus {
+                known_affected: Some(known_affected),
+                ..Default::default()
+            }),
+            references: non_empty(references),
+            release_date: Some(input.published),
+            scores,
+            ..Default::default()
+        }]),
+    })
+}
+
+/// Finds or adds the product for a CPE match, returning its ID. New products are numbered with `next_id`.
+fn leaf_product(
+    branches: &mut Vec<Branch>,
+    cpe_match: &CpeMatch,
+    next_id: usize,
+) -> Result<ProductIdT, NvdConvertError> {
+    let cpe: Cpe = cpe_match
+        .criteria
+        .parse()
+        .map_err(|source| NvdConvertError::InvalidCpe {
+            criteria: cpe_match.criteria.clone(),
+            source,
+        })?;
+    let vendor = cpe.vendor().unwrap_or("*").to_string();
+    let product = cpe.product().unwrap_or("*").to_string();
+    let (version, category) = match (cpe.version(), vers_range(cpe_match)) {
+        (Some(version), _) => (version.to_string(), BranchCategory::ProductVersion),
+        (None, Some(range)) => (range, BranchCategory::ProductVersionRange),
+        (None, None) => (
+            "vers:all/*".to_string(),
+            BranchCategory::ProductVersionRange,
+        ),
+    };
+    let name = [
+        Some(vendor.as_str()),
+        Some(product.as_str()),
+        cpe.version(),
+        cpe.update(),
+    ]
+    .iter()
+    .flatten()
+    .copied()
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    let vendor_branches = child_branches(branches, &vendor, BranchCategory::Vendor);
+    let leaves = child_branches(vendor_branches, &product, BranchCategory::ProductName);
+    if let Some(existing) = leaves
+        .iter()
+        .filter(|leaf| leaf.name == version && leaf.category == category)
+        .filter_map(|leaf| leaf.product.as_ref())
+        .find(|existing| existing.name == name)
+    {
+        return Ok(existing.product_id.clone());
+    }
+
+    let product_id = ProductIdT(format!("CSAFPID-{:04}", next_id));
+    leaves.push(Branch {
+        name: version,
+        category,
+        product: Some(FullProductName {
+            name,
+            product_id: product_id.clone(),
+            product_identification_helper: Some(ProductIdentificationHelper {
+                cpe: Some(cpe),
+                ..Default::default()
+            }),
+        }),
+        branches: None,
+    });
+    Ok(product_id)
+}
+
+/// The NVD's own vector, falling back to the first from another source
+fn primary_vector(metrics: &[CvssMetric]) -> Option<&str> {
+    metrics
+        .iter()
+        .find(|metric| metric.metric_type == "Primary")
+        .or_else(|| metrics.first())
+        .map(|metric| metric.cvss_data.vector_string.as_str())
+}
+
+/// Expresses the version bounds of a CPE match as a [vers](https://github.com/package-url/purl-spec/blob/version-range-spec/VERSION-RANGE-SPEC.rst)
+/// string, if it has any
+fn vers_range(cpe_match: &CpeMatch) -> Option<String> {
+    let constraints = [
+        (">=", &cpe_match.version_start_including),
+        (">", &cpe_match.version_start_excluding),
+        ("<=", &cpe_match.version_end_including),
+        ("<", &cpe_match.version_end_excluding),
+    ]
+    .iter()
+    .filter_map(|(comparator, version)| {
+        version
+            .as_ref()
+            .map(|version| format!("{}{}", comparator, version))
+    })
+    .collect::<Vec<_>>();
+    if constraints.is_empty() {
+        None
+    } else {
+        Some(format!("vers:generic/{}", constraints.join("|")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> serde_json::Value {
+        serde_json::from_str(include_str!("../../tests/CVE-2021-44228.nvd.json")).unwrap()
+    }
+
+    #[test]
+    fn example_cve_converts() {
+        let document = from_nvd_cve(&example()).unwrap();
+        assert_eq!(document.validate(), Ok(()));
+        assert_eq!(document.document.tracking.id, "CVE-2021-44228");
+        assert_eq!(document.document.tracking.revision_history.len(), 2);
+
+        let vulnerability = &document.vulnerabilities.as_ref().unwrap()[0];
+        assert_eq!(vulnerability.cve.as_deref(), Some("CVE-2021-44228"));
+        assert_eq!(vulnerability.notes.as_ref().unwrap().len(), 2);
+        assert_eq!(vulnerability.references.as_ref().unwrap().len(), 2);
+        assert_eq!(vulnerability.known_affected_count(), 4);
+        assert_eq!(vulnerability.max_cvss_score(), Some(10.0));
+
+        let vendors = &document
+            .product_tree
+            .as_ref()
+            .unwrap()
+            .branches
+            .as_ref()
+            .unwrap()
+            .0;
+        let names: Vec<_> = vendors[0].branches.as_ref().unwrap().0[0]
+            .branches
+            .as_ref()
+            .unwrap()
+            .0
+            .iter()
+            .map(|branch| branch.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "vers:generic/>=2.0.1|<2.3.1",
+                "vers:generic/>=2.4.0|<2.12.2",
+                "2.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_bare_cve_object() {
+        let entry = example();
+        let bare = from_nvd_cve(&entry["cve"]).unwrap();
+        let wrapped = from_nvd_cve(&entry).unwrap();
+        assert_eq!(bare.product_tree, wrapped.product_tree);
+        assert_eq!(bare.vulnerabilities, wrapped.vulnerabilities);
+        assert!(matches!(
+            from_nvd_cve(&serde_json::json!({"id": "CVE-2021-44228"})),
+            Err(NvdConvertError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn cve_without_configurations_has_no_products() {
+        let mut entry = example();
+        entry["cve"]
+            .as_object_mut()
+            .unwrap()
+            .remove("configurations");
+        let document = from_nvd_cve(&entry).unwrap();
+        assert_eq!(document.product_tree, None);
+        let vulnerability = &document.vulnerabilities.as_ref().unwrap()[0];
+        assert_eq!(vulnerability.product_status, None);
+        assert_eq!(vulnerability.scores, None);
+        assert_eq!(document.validate(), Ok(()));
+    }
+}
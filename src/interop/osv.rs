@@ -0,0 +1,1076 @@
+//! Conversion between CSAF and [OSV](https://ossf.github.io/osv-schema/) vulnerabilities, the interchange format used by
+//! GitHub Advisory, PyPI, crates.io and others.
+//!
+//! Only the subset of OSV used by the conversions is modelled in [schema], rather than depending on the `osv` crate.
+
+use packageurl::PackageUrl;
+use std::{collections::HashSet, str::FromStr};
+use url::Url;
+
+use super::{child_branches, non_empty};
+use crate::{
+    definitions::{
+        Acknowledgment, Branch, BranchCategory, BranchesT, FullProductName, Note, NoteCategory,
+        ProductIdT, ProductIdentificationHelper, ProductsT, Purl, Reference, ReferenceCategory,
+    },
+    document::{
+        AggregateSeverity, Category, CsafVersion, Document, Generator, Publisher,
+        PublisherCategory, Revision, Status, Tracking,
+    },
+    product_tree::ProductTree,
+    vulnerability::{
+        ProductStatus, Remediation, RemediationCategory, Remediations, Score, Vulnerability,
+        VulnerabilityId,
+    },
+    Csaf,
+};
+
+use schema::{Affected, Event, Package, RangeType, ReferenceType, Severity, SeverityType};
+
+/// [OSV schema](https://ossf.github.io/osv-schema/) types
+pub mod schema {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Serialize};
+
+    /// [Top level OSV structure](https://ossf.github.io/osv-schema/#id-modified-fields)
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Vulnerability {
+        pub schema_version: Option<String>,
+        pub id: String,
+        pub modified: DateTime<Utc>,
+        pub published: Option<DateTime<Utc>>,
+        pub withdrawn: Option<DateTime<Utc>>,
+        pub aliases: Option<Vec<String>>,
+        pub related: Option<Vec<String>>,
+        pub summary: Option<String>,
+        pub details: Option<String>,
+        pub severity: Option<Vec<Severity>>,
+        pub affected: Option<Vec<Affected>>,
+        pub references: Option<Vec<Reference>>,
+        pub credits: Option<Vec<Credit>>,
+        pub database_specific: Option<serde_json::Value>,
+    }
+
+    /// [Severity](https://ossf.github.io/osv-schema/#severity-field)
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Severity {
+        #[serde(rename = "type")]
+        pub severity_type: SeverityType,
+        pub score: String,
+    }
+
+    /// [Severity type](https://ossf.github.io/osv-schema/#severitytype-field)
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    pub enum SeverityType {
+        CvssV2,
+        CvssV3,
+        CvssV4,
+        #[serde(rename = "Ubuntu")]
+        Ubuntu,
+    }
+
+    /// [Affected](https://ossf.github.io/osv-schema/#affected-fields)
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Affected {
+        pub package: Option<Package>,
+        pub severity: Option<Vec<Severity>>,
+        pub ranges: Option<Vec<Range>>,
+        pub versions: Option<Vec<String>>,
+        pub ecosystem_specific: Option<serde_json::Value>,
+        pub database_specific: Option<serde_json::Value>,
+    }
+
+    /// [Package](https://ossf.github.io/osv-schema/#affectedpackage-field)
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Package {
+        pub ecosystem: String,
+        pub name: String,
+        pub purl: Option<String>,
+    }
+
+    /// [Range](https://ossf.github.io/osv-schema/#affectedranges-field)
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Range {
+        #[serde(rename = "type")]
+        pub range_type: RangeType,
+        pub repo: Option<String>,
+        pub events: Vec<Event>,
+        pub database_specific: Option<serde_json::Value>,
+    }
+
+    /// [Range type](https://ossf.github.io/osv-schema/#affectedrangestype-field)
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    pub enum RangeType {
+        Git,
+        Semver,
+        Ecosystem,
+    }
+
+    /// [Event](https://ossf.github.io/osv-schema/#affectedrangesevents-fields)
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Event {
+        Introduced(String),
+        Fixed(String),
+        LastAffected(String),
+        Limit(String),
+    }
+
+    /// [Reference](https://ossf.github.io/osv-schema/#references-field)
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Reference {
+        #[serde(rename = "type")]
+        pub reference_type: ReferenceType,
+        pub url: String,
+    }
+
+    /// [Reference type](https://ossf.github.io/osv-schema/#references-field)
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    pub enum ReferenceType {
+        Advisory,
+        Article,
+        Detection,
+        Discussion,
+        Report,
+        Fix,
+        Introduced,
+        Git,
+        Package,
+        Evidence,
+        Web,
+    }
+
+    /// [Credit](https://ossf.github.io/osv-schema/#credits-fields)
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Credit {
+        pub name: String,
+        pub contact: Option<Vec<String>>,
+        #[serde(rename = "type")]
+        pub credit_type: Option<String>,
+    }
+}
+
+// ASSUMPTIONS:
+// OSV records only the latest modification, so at most two revisions (published and modified) are recorded.
+//
+// Each affected package becomes a product_version_range branch per range, plus a product_version branch per fixed
+// version, nested under ecosystem (vendor) and package name branches.
+
+/// Provides a conversion from an OSV [Vulnerability](schema::Vulnerability) to a `Csaf` with the
+/// [security advisory profile](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#44-profile-4-security-advisory)
+///
+/// `GIT` ranges are skipped, as commit hashes do not identify a product version.
+impl From<schema::Vulnerability> for Csaf {
+    fn from(input: schema::Vulnerability) -> Self {
+        let published = input.published.unwrap_or(input.modified);
+        let mut revision_history = vec![Revision {
+            date: published,
+            number: "1".to_string(),
+            summary: "Initial publication".to_string(),
+            legacy_version: None,
+        }];
+        if input.modified > published {
+            revision_history.push(Revision {
+                date: input.modified,
+                number: "2".to_string(),
+                summary: "Last modification in OSV".to_string(),
+                legacy_version: None,
+            });
+        }
+        let version = revision_history.len().to_string();
+
+        let aliases = input.aliases.unwrap_or_default();
+        let cve = std::iter::once(&input.id)
+            .chain(&aliases)
+            .find(|id| id.starts_with("CVE-"))
+            .cloned();
+        let ids = std::iter::once(&input.id)
+            .chain(&aliases)
+            .filter(|id| Some(*id) != cve.as_ref())
+            .map(|id| VulnerabilityId {
+                system_name: id.split('-').next().unwrap_or_default().to_string(),
+                text: id.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut products = AffectedProducts::extract(input.affected.iter().flatten());
+        if let Some(score) = severity_score(
+            input.severity.iter().flatten(),
+            products.known_affected.clone(),
+        ) {
+            products.scores.insert(0, score);
+        }
+
+        let mut notes = Vec::new();
+        if let Some(summary) = &input.summary {
+            notes.push(Note {
+                category: NoteCategory::Summary,
+                text: summary.clone(),
+                audience: None,
+                title: None,
+            });
+        }
+        if let Some(details) = input.details {
+            notes.push(Note {
+                category: NoteCategory::Description,
+                text: details,
+                audience: None,
+                title: None,
+            });
+        }
+        if let Some(withdrawn) = input.withdrawn {
+            notes.push(Note {
+                category: NoteCategory::General,
+                text: format!("Withdrawn from OSV on {}", withdrawn),
+                audience: None,
+                title: Some("Withdrawn".to_string()),
+            });
+        }
+
+        let references = input
+            .references
+            .iter()
+            .flatten()
+            .filter_map(|reference| {
+                Some(Reference {
+                    url: Url::parse(&reference.url).ok()?,
+                    summary: reference_summary(reference.reference_type).to_string(),
+                    category: Some(ReferenceCategory::External),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let acknowledgments = input
+            .credits
+            .into_iter()
+            .flatten()
+            .map(|credit| Acknowledgment {
+                names: Some(vec![credit.name]),
+                urls: credit
+                    .contact
+                    .map(|contact| contact.iter().filter_map(|c| Url::parse(c).ok()).collect())
+                    .filter(|urls: &Vec<Url>| !urls.is_empty()),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        let aggregate_severity = input
+            .database_specific
+            .as_ref()
+            .and_then(|specific| specific.get("severity"))
+            .and_then(|severity| severity.as_str())
+            .map(|severity| AggregateSeverity {
+                text: severity.to_string(),
+                namespace: None,
+            });
+
+        let title = input.summary.unwrap_or_else(|| input.id.clone());
+
+        Csaf {
+            document: Document {
+                category: Category::SecurityAdvisory,
+                publisher: Publisher {
+                    category: PublisherCategory::Coordinator,
+                    name: "OSV".to_string(),
+                    namespace: Url::parse("https://osv.dev/").unwrap(),
+                    contact_details: None,
+                    issuing_authority: None,
+                },
+                title: title.clone(),
+                tracking: Tracking {
+                    current_release_date: input.modified.max(published),
+                    id: input.id.clone(),
+                    initial_release_date: published,
+                    revision_history,
+                    status: Status::Final,
+                    version,
+                    aliases: if aliases.is_empty() {
+                        None
+                    } else {
+                        Some(aliases.clone())
+                    },
+                    generator: Some(Generator::default()),
+                },
+                csaf_version: CsafVersion::TwoDotZero,
+                acknowledgments: None,
+                aggregate_severity,
+                distribution: None,
+                lang: None,
+                notes: None,
+                references: non_empty(references),
+                source_lang: None,
+                license_expression: None,
+            },
+            product_tree: non_empty(products.branches).map(|branches| ProductTree {
+                branches: Some(BranchesT(branches)),
+                ..Default::default()
+            }),
+            vulnerabilities: Some(vec![Vulnerability {
+                acknowledgments: non_empty(acknowledgments),
+                cve,
+                cwe: None,
+                discovery_date: None,
+                flags: None,
+                ids: non_empty(ids),
+                involvements: None,
+                notes: non_empty(notes),
+                product_status: if products.fixed.is_empty() && products.known_affected.is_empty() {
+                    None
+                } else {
+                    Some(ProductStatus {
+                        fixed: non_empty(products.fixed.clone()),
+                        known_affected: non_empty(products.known_affected.clone()),
+                        ..Default::default()
+                    })
+                },
+                references: None,
+                release_date: Some(published),
+                remediations: if products.fixed.is_empty() || products.known_affected.is_empty() {
+                    None
+                } else {
+                    Some(Remediations(vec![Remediation {
+                        category: RemediationCategory::VendorFix,
+                        details: "Upgrade to a fixed version".to_string(),
+                        date: None,
+                        entitlements: None,
+                        group_ids: None,
+                        product_ids: Some(products.known_affected),
+                        restart_required: None,
+                        url: None,
+                    }]))
+                },
+                scores: non_empty(products.scores),
+                threats: None,
+                title: Some(title),
+                disclosure_date: None,
+                metrics: None,
+            }]),
+        }
+    }
+}
+
+struct AffectedProducts {
+    branches: Vec<Branch>,
+    known_affected: ProductsT,
+    fixed: ProductsT,
+    scores: Vec<Score>,
+}
+
+impl AffectedProducts {
+    fn extract<'a>(affected: impl Iterator<Item = &'a Affected>) -> Self {
+        let mut output = Self {
+            branches: Vec::new(),
+            known_affected: Vec::new(),
+            fixed: Vec::new(),
+            scores: Vec::new(),
+        };
+
+        let mut id_counter: usize = 1;
+
+        for entry in affected {
+            // Without a package there is nothing to name the product by
+            let package = match &entry.package {
+                Some(package) => package,
+                None => continue,
+            };
+
+            let vendor = child_branches(
+                &mut output.branches,
+                &package.ecosystem,
+                BranchCategory::Vendor,
+            );
+            let leaves = child_branches(vendor, &package.name, BranchCategory::ProductName);
+
+            let mut affected_ids = Vec::new();
+            for range in entry.ranges.iter().flatten() {
+                if range.range_type == RangeType::Git {
+                    continue;
+                }
+                if let Some(vers) = vers_range(&package.ecosystem, &range.events) {
+                    affected_ids.push(leaf_product(
+                        leaves,
+                        package,
+                        vers,
+                        BranchCategory::ProductVersionRange,
+                        &mut id_counter,
+                    ));
+                }
+                for event in &range.events {
+                    if let Event::Fixed(version) = event {
+                        let id = leaf_product(
+                            leaves,
+                            package,
+                            version.clone(),
+                            BranchCategory::ProductVersion,
+                            &mut id_counter,
+                        );
+                        if !output.fixed.contains(&id) {
+                            output.fixed.push(id);
+                        }
+                    }
+                }
+            }
+
+            // Explicitly enumerated versions are only used when no range described the affected versions
+            if affected_ids.is_empty() {
+                for version in entry.versions.iter().flatten() {
+                    affected_ids.push(leaf_product(
+                        leaves,
+                        package,
+                        version.clone(),
+                        BranchCategory::ProductVersion,
+                        &mut id_counter,
+                    ));
+                }
+            }
+
+            if !affected_ids.is_empty() {
+                if let Some(score) =
+                    severity_score(entry.severity.iter().flatten(), affected_ids.clone())
+                {
+                    output.scores.push(score);
+                }
+            }
+            // The same version can be listed by more than one entry, such as one per ecosystem-specific range
+            for id in affected_ids {
+                if !output.known_affected.contains(&id) {
+                    output.known_affected.push(id);
+                }
+            }
+        }
+
+        output
+    }
+}
+
+/// Finds or creates a leaf branch for a version (range) of `package`, returning its product ID
+fn leaf_product(
+    leaves: &mut Vec<Branch>,
+    package: &Package,
+    name: String,
+    category: BranchCategory,
+    id_counter: &mut usize,
+) -> ProductIdT {
+    if let Some(product) = leaves
+        .iter()
+        .filter(|leaf| leaf.name == name && leaf.category == category)
+        .find_map(|leaf| leaf.product.as_ref())
+    {
+        return product.product_id.clone();
+    }
+
+    let product_id = ProductIdT(format!("{}-{}", package.name.to_uppercase(), id_counter));
+    *id_counter += 1;
+
+    let purl = match category {
+        BranchCategory::ProductVersion => package
+            .purl
+            .as_deref()
+            .and_then(|purl| PackageUrl::from_str(purl).ok())
+            .map(|mut purl| {
+                purl.with_version(name.clone());
+                purl.into()
+            }),
+        _ => None,
+    };
+
+    leaves.push(Branch {
+        name: name.clone(),
+        category,
+        product: Some(FullProductName {
+            name: format!("{} {}", package.name, name),
+            product_id: product_id.clone(),
+            product_identification_helper: purl.map(|purl| ProductIdentificationHelper {
+                purl: Some(purl),
+                ..Default::default()
+            }),
+        }),
+        branches: None,
+    });
+
+    product_id
+}
+
+/// Expresses the affected versions of an OSV range as a [vers](https://github.com/package-url/purl-spec/blob/version-range-spec/VERSION-RANGE-SPEC.rst)
+/// string, as recommended for `product_version_range` branches
+fn vers_range(ecosystem: &str, events: &[Event]) -> Option<String> {
+    if events.is_empty() {
+        return None;
+    }
+
+    let constraints = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Introduced(version) if version == "0" => None,
+            Event::Introduced(version) => Some(format!(">={}", version)),
+            Event::Fixed(version) | Event::Limit(version) => Some(format!("<{}", version)),
+            Event::LastAffected(version) => Some(format!("<={}", version)),
+        })
+        .collect::<Vec<_>>();
+
+    Some(format!(
+        "vers:{}/{}",
+        vers_scheme(ecosystem),
+        if constraints.is_empty() {
+            "*".to_string()
+        } else {
+            constraints.join("|")
+        }
+    ))
+}
+
+/// Maps an OSV ecosystem to the matching purl type where one is known, ignoring any release suffix like `Debian:11`
+fn vers_scheme(ecosystem: &str) -> String {
+    let ecosystem = ecosystem.split(':').next().unwrap_or(ecosystem);
+    match ecosystem {
+        "crates.io" => "cargo",
+        "Go" => "golang",
+        "RubyGems" => "gem",
+        "Packagist" => "composer",
+        _ => ecosystem,
+    }
+    .to_lowercase()
+}
+
+fn severity_score<'a>(
+    severities: impl Iterator<Item = &'a Severity>,
+    products: ProductsT,
+) -> Option<Score> {
+    let mut score = Score {
+        products,
+        cvss_v2: None,
+        cvss_v3: None,
+        cvss_v4: None,
+    };
+    for severity in severities {
+        match severity.severity_type {
+            SeverityType::CvssV2 => score.cvss_v2 = severity.score.parse().ok(),
+            SeverityType::CvssV3 => score.cvss_v3 = severity.score.parse().ok(),
+            SeverityType::CvssV4 => score.cvss_v4 = severity.score.parse().ok(),
+            SeverityType::Ubuntu => {}
+        }
+    }
+
+    if score.products.is_empty()
+        || (score.cvss_v2.is_none() && score.cvss_v3.is_none() && score.cvss_v4.is_none())
+    {
+        None
+    } else {
+        Some(score)
+    }
+}
+
+fn reference_summary(reference_type: ReferenceType) -> &'static str {
+    match reference_type {
+        ReferenceType::Advisory => "Advisory",
+        ReferenceType::Article => "Article",
+        ReferenceType::Detection => "Detection",
+        ReferenceType::Discussion => "Discussion",
+        ReferenceType::Report => "Report",
+        ReferenceType::Fix => "Fix",
+        ReferenceType::Introduced => "Introducing change",
+        ReferenceType::Git => "Git repository",
+        ReferenceType::Package => "Package",
+        ReferenceType::Evidence => "Evidence",
+        ReferenceType::Web => "Web page",
+    }
+}
+
+/// Provides a conversion from a `Csaf` to an OSV [Vulnerability](schema::Vulnerability)
+///
+/// OSV describes a single vulnerability, so the product status, scores and identifiers of all vulnerabilities in the
+/// document are merged. Products are attributed to an OSV package by their enclosing `vendor` (ecosystem) and
+/// `product_name` (package) branches, as produced by the conversion from OSV, falling back to their purl. Products
+/// that cannot be attributed to a package are left out.
+impl From<Csaf> for schema::Vulnerability {
+    fn from(input: Csaf) -> Self {
+        let vulnerabilities = input.vulnerabilities.unwrap_or_default();
+
+        let mut aliases = input.document.tracking.aliases.unwrap_or_default();
+        for vulnerability in &vulnerabilities {
+            let ids = vulnerability
+                .cve
+                .iter()
+                .chain(vulnerability.ids.iter().flatten().map(|id| &id.text));
+            for id in ids {
+                if *id != input.document.tracking.id && !aliases.contains(id) {
+                    aliases.push(id.clone());
+                }
+            }
+        }
+
+        let mut affected_ids = HashSet::new();
+        let mut fixed_ids = HashSet::new();
+        for status in vulnerabilities
+            .iter()
+            .filter_map(|v| v.product_status.as_ref())
+        {
+            for list in [
+                &status.first_affected,
+                &status.known_affected,
+                &status.last_affected,
+            ] {
+                affected_ids.extend(list.iter().flatten().map(|id| id.0.as_str()));
+            }
+            for list in [&status.first_fixed, &status.fixed] {
+                fixed_ids.extend(list.iter().flatten().map(|id| id.0.as_str()));
+            }
+        }
+
+        let mut packages: Vec<(Package, Vec<&Branch>)> = Vec::new();
+        if let Some(branches) = input
+            .product_tree
+            .as_ref()
+            .and_then(|t| t.branches.as_ref())
+        {
+            collect_package_leaves(branches, None, None, &mut packages);
+        }
+        let affected = packages
+            .into_iter()
+            .filter_map(|(package, leaves)| {
+                affected_package(package, &leaves, &affected_ids, &fixed_ids)
+            })
+            .collect::<Vec<_>>();
+
+        let mut severity: Vec<Severity> = Vec::new();
+        for score in vulnerabilities
+            .iter()
+            .flat_map(|v| v.scores.iter().flatten())
+        {
+            for (severity_type, vector) in [
+                (SeverityType::CvssV2, score.cvss_v2.map(|v| v.to_string())),
+                (
+                    SeverityType::CvssV3,
+                    score.cvss_v3.as_ref().map(|v| v.to_string()),
+                ),
+                (
+                    SeverityType::CvssV4,
+                    score.cvss_v4.as_ref().map(|v| v.to_string()),
+                ),
+            ] {
+                if let Some(vector) = vector {
+                    if !severity.iter().any(|s| s.severity_type == severity_type) {
+                        severity.push(Severity {
+                            severity_type,
+                            score: vector,
+                        });
+                    }
+                }
+            }
+        }
+
+        let details = vulnerabilities
+            .iter()
+            .flat_map(|v| v.notes.iter().flatten())
+            .chain(input.document.notes.iter().flatten())
+            .filter(|note| {
+                matches!(
+                    note.category,
+                    NoteCategory::Description | NoteCategory::Details
+                )
+            })
+            .map(|note| note.text.as_str())
+            .collect::<Vec<_>>();
+
+        let references = input
+            .document
+            .references
+            .iter()
+            .flatten()
+            .chain(
+                vulnerabilities
+                    .iter()
+                    .flat_map(|v| v.references.iter().flatten()),
+            )
+            .map(|reference| schema::Reference {
+                reference_type: match reference.category {
+                    Some(ReferenceCategory::RefSelf) => ReferenceType::Advisory,
+                    _ => ReferenceType::Web,
+                },
+                url: reference.url.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        let credits = input
+            .document
+            .acknowledgments
+            .iter()
+            .flatten()
+            .chain(
+                vulnerabilities
+                    .iter()
+                    .flat_map(|v| v.acknowledgments.iter().flatten()),
+            )
+            .flat_map(|acknowledgment| {
+                let contact = acknowledgment
+                    .urls
+                    .as_ref()
+                    .map(|urls| urls.iter().map(|url| url.to_string()).collect());
+                acknowledgment
+                    .names
+                    .iter()
+                    .flatten()
+                    .chain(acknowledgment.organization.iter())
+                    .map(move |name| schema::Credit {
+                        name: name.clone(),
+                        contact: contact.clone(),
+                        credit_type: None,
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        schema::Vulnerability {
+            schema_version: Some(OSV_SCHEMA_VERSION.to_string()),
+            id: input.document.tracking.id,
+            modified: input.document.tracking.current_release_date,
+            published: Some(input.document.tracking.initial_release_date),
+            withdrawn: None,
+            aliases: non_empty(aliases),
+            related: None,
+            summary: Some(input.document.title),
+            details: if details.is_empty() {
+                None
+            } else {
+                Some(details.join("\n\n"))
+            },
+            severity: non_empty(severity),
+            affected: non_empty(affected),
+            references: non_empty(references),
+            credits: non_empty(credits),
+            database_specific: input
+                .document
+                .aggregate_severity
+                .map(|severity| serde_json::json!({ "severity": severity.text })),
+        }
+    }
+}
+
+/// The OSV schema version produced by the conversion from `Csaf`
+const OSV_SCHEMA_VERSION: &str = "1.4.0";
+
+/// Groups the product leaves of `branches` by the OSV package they belong to
+fn collect_package_leaves<'a>(
+    branches: &'a BranchesT,
+    ecosystem: Option<&'a str>,
+    name: Option<&'a str>,
+    output: &mut Vec<(Package, Vec<&'a Branch>)>,
+) {
+    for branch in branches {
+        let ecosystem = match branch.category {
+            BranchCategory::Vendor => Some(branch.name.as_str()),
+            _ => ecosystem,
+        };
+        let name = match branch.category {
+            BranchCategory::ProductName => Some(branch.name.as_str()),
+            _ => name,
+        };
+
+        if let Some(product) = &branch.product {
+            let purl = product
+                .product_identification_helper
+                .as_ref()
+                .and_then(|helper| helper.purl.as_ref());
+            let package = match (ecosystem, name, purl) {
+                (Some(ecosystem), Some(name), purl) => Package {
+                    ecosystem: ecosystem.to_string(),
+                    name: name.to_string(),
+                    purl: purl.and_then(package_purl),
+                },
+                (_, _, Some(purl)) => Package {
+                    ecosystem: purl_ecosystem(purl.ty()),
+                    name: match purl.namespace() {
+                        Some(namespace) => format!("{}/{}", namespace, purl.name()),
+                        None => purl.name().to_string(),
+                    },
+                    purl: package_purl(purl),
+                },
+                _ => continue,
+            };
+            // Only some leaves of a package, like fixed versions, may have a purl
+            match output
+                .iter_mut()
+                .find(|(p, _)| p.ecosystem == package.ecosystem && p.name == package.name)
+            {
+                Some((existing, leaves)) => {
+                    if existing.purl.is_none() {
+                        existing.purl = package.purl;
+                    }
+                    leaves.push(branch);
+                }
+                None => output.push((package, vec![branch])),
+            }
+        }
+
+        if let Some(children) = &branch.branches {
+            collect_package_leaves(children, ecosystem, name, output);
+        }
+    }
+}
+
+fn affected_package(
+    package: Package,
+    leaves: &[&Branch],
+    affected_ids: &HashSet<&str>,
+    fixed_ids: &HashSet<&str>,
+) -> Option<Affected> {
+    let mut events = Vec::new();
+    let mut versions = Vec::new();
+
+    for leaf in leaves {
+        let id = match &leaf.product {
+            Some(product) => product.product_id.0.as_str(),
+            None => continue,
+        };
+        if affected_ids.contains(id) {
+            match (&leaf.category, vers_events(&leaf.name)) {
+                (BranchCategory::ProductVersionRange, Some(mut range)) => events.append(&mut range),
+                _ => versions.push(leaf.name.clone()),
+            }
+        }
+    }
+    for leaf in leaves {
+        let is_fixed = leaf
+            .product
+            .as_ref()
+            .map_or(false, |p| fixed_ids.contains(p.product_id.0.as_str()));
+        if is_fixed && leaf.category == BranchCategory::ProductVersion {
+            let fixed = Event::Fixed(leaf.name.clone());
+            if !events.contains(&fixed) {
+                events.push(fixed);
+            }
+        }
+    }
+
+    if events.is_empty() && versions.is_empty() {
+        return None;
+    }
+    if !events.is_empty() && !matches!(events.first(), Some(Event::Introduced(_))) {
+        events.insert(0, Event::Introduced("0".to_string()));
+    }
+
+    Some(Affected {
+        package: Some(package),
+        severity: None,
+        ranges: if events.is_empty() {
+            None
+        } else {
+            Some(vec![schema::Range {
+                range_type: RangeType::Ecosystem,
+                repo: None,
+                events,
+                database_specific: None,
+            }])
+        },
+        versions: non_empty(versions),
+        ecosystem_specific: None,
+        database_specific: None,
+    })
+}
+
+/// Inverse of [vers_range], for the comparators it produces
+fn vers_events(vers: &str) -> Option<Vec<Event>> {
+    let (_scheme, constraints) = vers.strip_prefix("vers:")?.split_once('/')?;
+    if constraints == "*" {
+        return Some(vec![Event::Introduced("0".to_string())]);
+    }
+    constraints
+        .split('|')
+        .map(|constraint| {
+            if let Some(version) = constraint.strip_prefix(">=") {
+                Some(Event::Introduced(version.to_string()))
+            } else if let Some(version) = constraint.strip_prefix("<=") {
+                Some(Event::LastAffected(version.to_string()))
+            } else {
+                constraint
+                    .strip_prefix('<')
+                    .map(|version| Event::Fixed(version.to_string()))
+            }
+        })
+        .collect()
+}
+
+/// Inverse of [vers_scheme], for the purl types that do not match their ecosystem name when lowercased
+fn purl_ecosystem(ty: &str) -> String {
+    match ty {
+        "cargo" => "crates.io",
+        "golang" => "Go",
+        "gem" => "RubyGems",
+        "composer" => "Packagist",
+        "pypi" => "PyPI",
+        "maven" => "Maven",
+        "nuget" => "NuGet",
+        _ => ty,
+    }
+    .to_string()
+}
+
+/// The purl of a package is that of its products, without a version
+fn package_purl(purl: &Purl) -> Option<String> {
+    let mut package = PackageUrl::new(purl.ty().to_string(), purl.name().to_string()).ok()?;
+    if let Some(namespace) = purl.namespace() {
+        package.with_namespace(namespace.to_string());
+    }
+    Some(package.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> schema::Vulnerability {
+        serde_json::from_str(include_str!("../../tests/RUSTSEC-2021-0093.osv.json")).unwrap()
+    }
+
+    #[test]
+    fn example_vulnerability_converts() {
+        let document = Csaf::from(example());
+        assert_eq!(document.validate(), Ok(()));
+        assert_eq!(document.document.tracking.id, "RUSTSEC-2021-0093");
+        assert_eq!(document.document.tracking.version, "2");
+        assert!(document.document.aggregate_severity.is_none());
+
+        let vulnerability = &document.vulnerabilities.as_ref().unwrap()[0];
+        assert_eq!(vulnerability.cve.as_deref(), Some("CVE-2021-32810"));
+
+        let status = vulnerability.product_status.as_ref().unwrap();
+        assert_eq!(status.known_affected.as_ref().unwrap().len(), 1);
+        assert_eq!(status.fixed.as_ref().unwrap().len(), 2);
+
+        let branches = document.product_tree.unwrap().branches.unwrap();
+        let vendor = &branches.0[0];
+        assert_eq!(vendor.name, "crates.io");
+        let package = &vendor.branches.as_ref().unwrap().0[0];
+        let range = &package.branches.as_ref().unwrap().0[0];
+        assert_eq!(range.name, "vers:cargo/>=0.0.0-0|<0.7.4|>=0.8.0|<0.8.1");
+
+        let scores = vulnerability.scores.as_ref().unwrap();
+        assert_eq!(scores[0].products, status.known_affected.clone().unwrap());
+        assert!(scores[0].cvss_v3.is_some());
+    }
+
+    #[test]
+    fn multiple_ecosystems() {
+        let json = r#"{
+            "id": "GHSA-xxxx-xxxx-xxxx",
+            "modified": "2023-01-01T00:00:00Z",
+            "database_specific": { "severity": "HIGH" },
+            "affected": [
+                {
+                    "package": { "ecosystem": "PyPI", "name": "example" },
+                    "severity": [{ "type": "CVSS_V2", "score": "AV:N/AC:L/Au:N/C:P/I:P/A:P" }],
+                    "ranges": [{ "type": "ECOSYSTEM", "events": [{ "introduced": "0" }, { "fixed": "1.2" }] }]
+                },
+                {
+                    "package": { "ecosystem": "npm", "name": "example" },
+                    "versions": ["1.0.0", "1.0.1"]
+                },
+                {
+                    "package": { "ecosystem": "Go", "name": "example.com/example" },
+                    "ranges": [{ "type": "GIT", "repo": "https://example.com", "events": [{ "introduced": "abc" }] }]
+                }
+            ]
+        }"#;
+        let document = Csaf::from(serde_json::from_str::<schema::Vulnerability>(json).unwrap());
+        assert_eq!(document.validate(), Ok(()));
+        assert_eq!(document.document.tracking.version, "1");
+        assert_eq!(
+            document.document.aggregate_severity.unwrap().text,
+            "HIGH".to_string()
+        );
+
+        let branches = document.product_tree.unwrap().branches.unwrap();
+        let names: Vec<_> = branches.0.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["PyPI", "npm", "Go"]);
+
+        let vulnerability = &document.vulnerabilities.unwrap()[0];
+        assert!(vulnerability.cve.is_none());
+        let status = vulnerability.product_status.as_ref().unwrap();
+        assert_eq!(status.known_affected.as_ref().unwrap().len(), 3);
+        assert_eq!(status.fixed.as_ref().unwrap().len(), 1);
+
+        let scores = vulnerability.scores.as_ref().unwrap();
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[0].products.len(), 1);
+        assert!(scores[0].cvss_v2.is_some());
+    }
+
+    #[test]
+    fn affected_products_are_deduplicated() {
+        let json = r#"{
+            "id": "GHSA-xxxx-xxxx-xxxx",
+            "modified": "2023-01-01T00:00:00Z",
+            "affected": [
+                { "package": { "ecosystem": "npm", "name": "example" }, "versions": ["1.0.0"] },
+                { "package": { "ecosystem": "npm", "name": "example" }, "versions": ["1.0.0", "1.0.1"] }
+            ]
+        }"#;
+        let document = Csaf::from(serde_json::from_str::<schema::Vulnerability>(json).unwrap());
+        assert_eq!(document.validate(), Ok(()));
+        let vulnerability = &document.vulnerabilities.unwrap()[0];
+        assert_eq!(vulnerability.known_affected_count(), 2);
+
+        let json = r#"{ "id": "GHSA-xxxx-xxxx-xxxx", "modified": "2023-01-01T00:00:00Z" }"#;
+        let document = Csaf::from(serde_json::from_str::<schema::Vulnerability>(json).unwrap());
+        assert_eq!(document.product_tree, None);
+        assert_eq!(document.vulnerabilities.unwrap()[0].product_status, None);
+    }
+
+    #[test]
+    fn roundtrips_through_csaf() {
+        let original = example();
+        let exported = schema::Vulnerability::from(Csaf::from(original.clone()));
+
+        assert_eq!(exported.id, original.id);
+        assert_eq!(exported.modified, original.modified);
+        assert_eq!(exported.published, original.published);
+        assert_eq!(exported.summary, original.summary);
+        assert_eq!(exported.details, original.details);
+        assert_eq!(exported.severity, original.severity);
+
+        let mut aliases = exported.aliases.unwrap();
+        aliases.sort();
+        assert_eq!(aliases, original.aliases.unwrap());
+
+        let affected = exported.affected.unwrap();
+        assert_eq!(affected.len(), 1);
+        let original_affected = &original.affected.as_ref().unwrap()[0];
+        assert_eq!(affected[0].package, original_affected.package);
+        assert_eq!(
+            affected[0].ranges.as_ref().unwrap()[0].events,
+            original_affected.ranges.as_ref().unwrap()[0].events
+        );
+    }
+
+    #[test]
+    fn exports_csaf_document() {
+        let document = crate::tests::fixtures::example();
+        let exported = schema::Vulnerability::from(document);
+
+        assert_eq!(exported.id, "cisco-sa-20180328-smi2");
+        assert_eq!(exported.schema_version.as_deref(), Some(OSV_SCHEMA_VERSION));
+        assert!(exported
+            .aliases
+            .unwrap()
+            .contains(&"CVE-2018-0171".to_string()));
+        assert_eq!(
+            exported.severity.unwrap()[0].severity_type,
+            SeverityType::CvssV3
+        );
+
+        let json =
+            serde_json::to_value(schema::Vulnerability::from(Csaf::from(example()))).unwrap();
+        assert_eq!(json["affected"][0]["package"]["ecosystem"], "crates.io");
+        assert_eq!(
+            json["affected"][0]["ranges"][0]["events"][1]["fixed"],
+            "0.7.4"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Conversion to [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html), so advisories can
+//! be reported by CI tools such as GitHub code scanning
+//!
+//! Each vulnerability becomes a rule and a result. Products are not files, so the affected products are given as the
+//! result's logical locations.
+
+use std::fmt::Display;
+
+use serde_json::{json, Value};
+
+use crate::{
+    definitions::{NoteCategory, NotesT},
+    vulnerability::{CvssSeverity, Vulnerability},
+    Csaf,
+};
+
+/// Failure to convert a document to SARIF
+#[derive(Debug, Clone, PartialEq)]
+pub enum SarifError {
+    /// The vulnerability at this index has no CVE, ID or title to use as its rule ID
+    UnidentifiedVulnerability { index: usize },
+}
+
+impl Display for SarifError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnidentifiedVulnerability { index } => write!(
+                f,
+                "vulnerability {} has no CVE, ID or title to use as a SARIF rule ID",
+                index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SarifError {}
+
+impl Csaf {
+    /// Converts the document to a SARIF log with a single run
+    ///
+    /// The rule ID is the CVE, or else the first ID or the title. The level follows the highest CVSS score: `error`
+    /// for high and critical, `warning` for medium or no score and `note` below that.
+    ///
+    /// ```
+    /// # use csaf::Csaf;
+    /// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../../tests/CVE-2018-0171-modified.json")).unwrap();
+    /// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+    /// let csaf: Csaf = serde_json::from_value(json).unwrap();
+    /// let sarif = csaf.to_sarif().unwrap();
+    /// assert_eq!(sarif["runs"][0]["results"][0]["ruleId"], "CVE-2018-0171");
+    /// assert_eq!(sarif["runs"][0]["results"][0]["level"], "error");
+    /// ```
+    pub fn to_sarif(&self) -> Result<Value, SarifError> {
+        let affected: Vec<_> = self.affected_products().collect();
+        let mut rules = Vec::new();
+        let mut results = Vec::new();
+
+        for (index, vulnerability) in self.vulnerabilities.iter().flatten().enumerate() {
+            let rule_id = vulnerability
+                .key()
+                .ok_or(SarifError::UnidentifiedVulnerability { index })?;
+
+            let mut rule = json!({ "id": rule_id });
+            if let Some(title) = &vulnerability.title {
+                rule["shortDescription"] = json!({ "text": title });
+            }
+            if let Some(url) = vulnerability.nvd_url() {
+                rule["helpUri"] = json!(url.as_str());
+            }
+            rules.push(rule);
+
+            let locations: Vec<Value> = affected
+                .iter()
+                .filter(|(affected, _)| std::ptr::eq(*affected, vulnerability))
+                .map(|(_, product)| {
+                    json!({
+                        "name": product.name,
+                        "fullyQualifiedName": product.product_id.0,
+                        "kind": "module",
+                    })
+                })
+                .collect();
+            let mut result = json!({
+                "ruleId": rule_id,
+                "ruleIndex": index,
+                "level": level(vulnerability),
+                "message": { "text": message(vulnerability).unwrap_or(&rule_id) },
+            });
+            if !locations.is_empty() {
+                result["locations"] = json!([{ "logicalLocations": locations }]);
+            }
+            results.push(result);
+        }
+
+        Ok(json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": self.document.publisher.name,
+                        "informationUri": self.document.publisher.namespace.as_str(),
+                        "version": self.document.tracking.version,
+                        "rules": rules,
+                    },
+                },
+                "automationDetails": { "id": self.document.tracking.id },
+                "results": results,
+            }],
+        }))
+    }
+}
+
+fn level(vulnerability: &Vulnerability) -> &'static str {
+    match vulnerability.max_cvss_score().map(CvssSeverity::from_score) {
+        Some(CvssSeverity::Critical | CvssSeverity::High) => "error",
+        Some(CvssSeverity::Medium) | None => "warning",
+        Some(CvssSeverity::Low | CvssSeverity::None) => "note",
+    }
+}
+
+/// The summary, description or details note, in that order of preference, or else the title
+fn message(vulnerability: &Vulnerability) -> Option<&str> {
+    fn note(notes: &NotesT, category: NoteCategory) -> Option<&str> {
+        notes
+            .iter()
+            .find(|note| note.category == category)
+            .map(|note| note.text.as_str())
+    }
+
+    vulnerability
+        .notes
+        .as_ref()
+        .and_then(|notes| {
+            note(notes, NoteCategory::Summary)
+                .or_else(|| note(notes, NoteCategory::Description))
+                .or_else(|| note(notes, NoteCategory::Details))
+        })
+        .or(vulnerability.title.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sarif_results_per_vulnerability() {
+        let mut csaf = crate::tests::fixtures::example();
+        let sarif = csaf.to_sarif().unwrap();
+        let run = &sarif["runs"][0];
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "CVE-2018-0171");
+        assert!(run["results"][0]["locations"][0]["logicalLocations"]
+            .as_array()
+            .map_or(false, |locations| !locations.is_empty()));
+        assert!(!run["results"][0]["message"]["text"]
+            .as_str()
+            .unwrap()
+            .is_empty());
+
+        csaf.vulnerabilities = Some(vec![Vulnerability::default()]);
+        assert_eq!(
+            csaf.to_sarif(),
+            Err(SarifError::UnidentifiedVulnerability { index: 0 })
+        );
+    }
+}
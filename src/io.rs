@@ -0,0 +1,428 @@
+//! Reading and writing CSAF documents
+//!
+//! Single documents are loaded and saved with [Csaf::from_json_file] and [Csaf::to_json_file]. Aggregator feeds can
+//! contain thousands of documents, so [CsafReader] deserializes one document at a time instead of loading the whole
+//! collection into memory. When only part of a document is needed, [CsafEnvelope] borrows each top-level section as
+//! raw JSON and deserializes it on request.
+
+use std::{
+    fmt::Display,
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{de::Error, Deserialize};
+use serde_json::value::RawValue;
+
+use crate::{document::Document, product_tree::ProductTree, vulnerability::Vulnerability, Csaf};
+
+/// Failure to load or save a document, with the path of the file
+#[derive(Debug)]
+pub enum CsafLoadError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Json {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+impl Display for CsafLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Json { path, source } => {
+                write!(f, "{}: invalid CSAF JSON: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsafLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Json { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Failure to deserialize a document, with the path to the field that failed
+#[derive(Debug)]
+pub struct CsafDeserializeError {
+    /// Dotted path to the field, such as `vulnerabilities[2].product_status.fixed[0]`, or `.` for the document itself
+    pub path: String,
+    pub source: serde_json::Error,
+}
+
+impl Display for CsafDeserializeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.source)
+    }
+}
+
+impl std::error::Error for CsafDeserializeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl Csaf {
+    /// Deserializes a document from JSON, reporting the path to the field that failed on error
+    ///
+    /// ```
+    /// # use csaf::Csaf;
+    /// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../tests/CVE-2018-0171-modified.json")).unwrap();
+    /// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+    /// let json = serde_json::to_string_pretty(&json)
+    ///     .unwrap()
+    ///     .replacen(r#""known_affected": ["#, r#""known_affected": [1, "#, 1);
+    /// let error = Csaf::from_json_with_context(&json).unwrap_err();
+    /// assert_eq!(error.path, "vulnerabilities[0].product_status.known_affected[0]");
+    /// ```
+    pub fn from_json_with_context(s: &str) -> Result<Csaf, CsafDeserializeError> {
+        let deserializer = &mut serde_json::Deserializer::from_str(s);
+        serde_path_to_error::deserialize(deserializer).map_err(|error| CsafDeserializeError {
+            path: error.path().to_string(),
+            source: error.into_inner(),
+        })
+    }
+
+    /// Reads a document from a JSON file
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Csaf, CsafLoadError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|source| CsafLoadError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        serde_json::from_str(&json).map_err(|source| CsafLoadError::Json {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Serializes the document as JSON indented for reading
+    pub fn to_pretty_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Serializes the document as JSON without whitespace
+    pub fn to_compact_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Writes the document as JSON indented for reading, without building it in memory first
+    pub fn write_pretty_json(&self, writer: impl Write) -> Result<(), serde_json::Error> {
+        serde_json::to_writer_pretty(writer, self)
+    }
+
+    /// Writes the document as JSON without whitespace, without building it in memory first
+    pub fn write_compact_json(&self, writer: impl Write) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Writes the document to a file as pretty-printed JSON, replacing the file if it exists
+    pub fn to_json_file(&self, path: impl AsRef<Path>) -> Result<(), CsafLoadError> {
+        let path = path.as_ref();
+        let json = self
+            .to_pretty_json()
+            .map_err(|source| CsafLoadError::Json {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        std::fs::write(path, json).map_err(|source| CsafLoadError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Converts the document directly to a JSON tree, for merging with other metadata before writing
+///
+/// ```
+/// # use csaf::Csaf;
+/// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../tests/CVE-2018-0171-modified.json")).unwrap();
+/// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+/// let csaf: Csaf = serde_json::from_value(json).unwrap();
+/// let mut value = serde_json::Value::from(&csaf);
+/// value["x_generated_by"] = "example".into();
+/// assert_eq!(value["document"]["tracking"]["id"], "cisco-sa-20180328-smi2");
+/// ```
+impl From<&Csaf> for serde_json::Value {
+    fn from(csaf: &Csaf) -> Self {
+        // Every map in the document has string keys and every field serializes infallibly
+        serde_json::to_value(csaf).expect("Csaf always serializes to a JSON value")
+    }
+}
+
+impl From<Csaf> for serde_json::Value {
+    fn from(csaf: Csaf) -> Self {
+        Self::from(&csaf)
+    }
+}
+
+/// A document whose top-level sections are kept as raw JSON borrowed from the input, so only the sections that are
+/// needed get deserialized
+///
+/// The sections are checked to be well-formed JSON, but not to be valid CSAF until they are accessed.
+///
+/// ```
+/// # use csaf::io::CsafEnvelope;
+/// let json = include_str!("../tests/CVE-2018-0171-modified.json");
+/// let envelope: CsafEnvelope = serde_json::from_str(json).unwrap();
+/// assert_eq!(envelope.document().unwrap().tracking.id, "cisco-sa-20180328-smi2");
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct CsafEnvelope<'a> {
+    #[serde(borrow)]
+    pub document: &'a RawValue,
+    #[serde(borrow)]
+    pub product_tree: Option<&'a RawValue>,
+    #[serde(borrow)]
+    pub vulnerabilities: Option<&'a RawValue>,
+}
+
+impl<'a> CsafEnvelope<'a> {
+    /// Parses the envelope from JSON without deserializing any of the sections
+    pub fn from_json(s: &'a str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    pub fn document(&self) -> Result<Document, serde_json::Error> {
+        serde_json::from_str(self.document.get())
+    }
+
+    pub fn product_tree(&self) -> Result<Option<ProductTree>, serde_json::Error> {
+        self.product_tree
+            .map(|raw| serde_json::from_str(raw.get()))
+            .transpose()
+    }
+
+    pub fn vulnerabilities(&self) -> Result<Option<Vec<Vulnerability>>, serde_json::Error> {
+        self.vulnerabilities
+            .map(|raw| serde_json::from_str(raw.get()))
+            .transpose()
+    }
+
+    /// Deserializes every section into a full document
+    pub fn to_csaf(&self) -> Result<Csaf, serde_json::Error> {
+        Ok(Csaf {
+            document: self.document()?,
+            product_tree: self.product_tree()?,
+            vulnerabilities: self.vulnerabilities()?,
+        })
+    }
+}
+
+/// Iterator over the [Csaf] documents in a reader
+///
+/// The input may either be a single JSON array of documents, or a sequence of documents separated by whitespace (such
+/// as newline-delimited JSON). Iteration stops after the first error, as the position in the input can no longer be
+/// trusted.
+///
+/// ```
+/// # use csaf::io::CsafReader;
+/// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../tests/CVE-2018-0171-modified.json")).unwrap();
+/// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+/// let feed = format!("[{0},{0}]", json);
+/// let documents = CsafReader::new(feed.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(documents.len(), 2);
+/// ```
+pub struct CsafReader<R> {
+    reader: BufReader<R>,
+    state: State,
+}
+
+enum State {
+    Start,
+    Array { first: bool },
+    Stream,
+    Done,
+}
+
+impl<R: Read> CsafReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            state: State::Start,
+        }
+    }
+
+    /// Skips whitespace and returns the next byte, without consuming it
+    fn peek(&mut self) -> Result<Option<u8>, serde_json::Error> {
+        loop {
+            let buffer = self.reader.fill_buf().map_err(serde_json::Error::io)?;
+            match buffer.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(index) => {
+                    let byte = buffer[index];
+                    self.reader.consume(index);
+                    return Ok(Some(byte));
+                }
+                None if buffer.is_empty() => return Ok(None),
+                None => {
+                    let length = buffer.len();
+                    self.reader.consume(length);
+                }
+            }
+        }
+    }
+
+    fn next_document(&mut self) -> Result<Option<Csaf>, serde_json::Error> {
+        loop {
+            let next = self.peek()?;
+            match (&mut self.state, next) {
+                (State::Done, _) => return Ok(None),
+                (State::Start, Some(b'[')) => {
+                    self.reader.consume(1);
+                    self.state = State::Array { first: true };
+                }
+                (State::Start, _) => self.state = State::Stream,
+                (State::Array { .. }, Some(b']')) => {
+                    self.reader.consume(1);
+                    self.state = State::Done;
+                }
+                (State::Array { first: false }, Some(b',')) => {
+                    self.reader.consume(1);
+                    self.state = State::Array { first: true };
+                }
+                (State::Array { first: true }, Some(_)) => {
+                    self.state = State::Array { first: false };
+                    return self.deserialize().map(Some);
+                }
+                (State::Array { .. }, next) => {
+                    return Err(serde_json::Error::custom(match next {
+                        Some(byte) => format!(
+                            "expected `,` or `]` in array of documents, found `{}`",
+                            byte as char
+                        ),
+                        None => "unterminated array of documents".to_string(),
+                    }))
+                }
+                (State::Stream, None) => return Ok(None),
+                (State::Stream, Some(_)) => return self.deserialize().map(Some),
+            }
+        }
+    }
+
+    fn deserialize(&mut self) -> Result<Csaf, serde_json::Error> {
+        // Documents are objects, so the deserializer stops at the closing brace without reading past it
+        let mut deserializer = serde_json::Deserializer::from_reader(&mut self.reader);
+        Csaf::deserialize(&mut deserializer)
+    }
+}
+
+impl<R: Read> Iterator for CsafReader<R> {
+    type Item = Result<Csaf, serde_json::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_document() {
+            Ok(document) => document.map(Ok),
+            Err(error) => {
+                self.state = State::Done;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::example_json;
+
+    #[test]
+    fn reads_whitespace_separated_documents() {
+        let example = example_json();
+        let input = format!(
+            "{}\n{}\n",
+            serde_json::from_str::<Csaf>(&example)
+                .unwrap()
+                .to_compact_json()
+                .unwrap(),
+            example
+        );
+        let documents = CsafReader::new(input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0], documents[1]);
+    }
+
+    #[test]
+    fn reads_empty_input() {
+        assert_eq!(CsafReader::new(&b""[..]).count(), 0);
+        assert_eq!(CsafReader::new(&b" [ ] "[..]).count(), 0);
+    }
+
+    #[test]
+    fn stops_after_error() {
+        let example = example_json();
+        let input = format!("[{}, {{}}, {}]", example, example);
+        let results = CsafReader::new(input.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+
+        let input = format!("[{} {}]", example, example);
+        let results = CsafReader::new(input.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn json_file_roundtrip() {
+        let example = example_json();
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("advisory.json");
+        let csaf: Csaf = serde_json::from_str(&example).unwrap();
+
+        csaf.to_json_file(&path).unwrap();
+        assert_eq!(Csaf::from_json_file(&path).unwrap(), csaf);
+
+        let missing = directory.path().join("missing.json");
+        let error = Csaf::from_json_file(&missing).unwrap_err();
+        assert!(matches!(error, CsafLoadError::Io { .. }));
+        assert!(error.to_string().contains("missing.json"));
+
+        std::fs::write(&path, "{}").unwrap();
+        assert!(matches!(
+            Csaf::from_json_file(&path),
+            Err(CsafLoadError::Json { .. })
+        ));
+    }
+
+    #[test]
+    fn json_writers_match_strings() {
+        let example = example_json();
+        let csaf: Csaf = serde_json::from_str(&example).unwrap();
+
+        let mut pretty = Vec::new();
+        csaf.write_pretty_json(&mut pretty).unwrap();
+        assert_eq!(pretty, csaf.to_pretty_json().unwrap().into_bytes());
+
+        let mut compact = Vec::new();
+        csaf.write_compact_json(&mut compact).unwrap();
+        let compact = String::from_utf8(compact).unwrap();
+        assert_eq!(compact, csaf.to_compact_json().unwrap());
+        assert!(!compact.contains('\n'));
+        assert_eq!(serde_json::from_str::<Csaf>(&compact).unwrap(), csaf);
+    }
+
+    #[test]
+    fn envelope_defers_sections() {
+        let example = example_json();
+        let envelope = CsafEnvelope::from_json(&example).unwrap();
+        assert_eq!(
+            envelope.to_csaf().unwrap(),
+            serde_json::from_str::<Csaf>(&example).unwrap()
+        );
+
+        let json = r#"{"document": {}, "vulnerabilities": [{"cve": 1}]}"#;
+        let envelope = CsafEnvelope::from_json(json).unwrap();
+        assert!(envelope.document().is_err());
+        assert_eq!(envelope.product_tree().unwrap(), None);
+        assert!(envelope.vulnerabilities().is_err());
+    }
+}
@@ -9,36 +9,396 @@
 //! allows for inclusion of the documentation in this repository directly. Inclusion of details from upstream
 //!  would be more usable, but without guidance on license compatibility I'm only comfortable providing links for now.
 
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 
 pub mod document;
-use document::Document;
+use document::{AggregateSeverity, Category, CsafProfile, Document, Status};
 
 pub mod product_tree;
 use product_tree::ProductTree;
 
 pub mod vulnerability;
-use vulnerability::Vulnerability;
+use vulnerability::{CvssSeverity, ProductStatusKind, Vulnerability};
 
 pub mod definitions;
+use definitions::{FullProductName, NoteCategory, NotesT, ProductIdT, ReferenceCategory};
+
+pub mod cvss_v2;
 
 pub mod interop;
 
+pub mod io;
+
+pub mod validation;
+use validation::{ValidationConfig, ValidationError};
+
+pub mod diff;
+use diff::CsafDiff;
+
+pub mod merge;
+use merge::MergeError;
+
+pub mod index;
+
+pub mod feed;
+
+pub mod render;
+
+pub mod checksum;
+
+#[cfg(feature = "async")]
+pub mod fetch;
+
+#[cfg(feature = "signing")]
+pub mod signing;
+
 /// [Top level CSAF structure definition](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#32-properties)
 ///
 /// Interoperatbility with [RustSec](https://rustsec.org/) advisories is provided by a `From` implementation.
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Csaf {
     pub document: Document,
     pub product_tree: Option<ProductTree>,
     pub vulnerabilities: Option<Vec<Vulnerability>>,
 }
 
+impl Csaf {
+    /// Checks the document against the mandatory constraints of the spec that are not already enforced by the type system.
+    ///
+    /// All violations are collected rather than stopping at the first one. See [validation] for details.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        self.validate_with(&ValidationConfig::default())
+    }
+
+    /// Like [Csaf::validate], additionally applying the optional checks enabled in `config`
+    pub fn validate_with(&self, config: &ValidationConfig) -> Result<(), Vec<ValidationError>> {
+        let errors = validation::validate(self, config);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Every product ID defined in the `product_tree`, including those in nested `branches` and `relationships`
+    pub fn all_product_ids(&self) -> impl Iterator<Item = &ProductIdT> {
+        self.product_tree
+            .iter()
+            .flat_map(|tree| tree.defined_product_ids())
+    }
+
+    /// Every product ID referenced by `vulnerabilities`, in product status, scores, remediations, flags and threats.
+    /// IDs referenced more than once are yielded more than once.
+    pub fn all_referenced_product_ids(&self) -> impl Iterator<Item = &ProductIdT> {
+        self.vulnerabilities
+            .iter()
+            .flatten()
+            .flat_map(Vulnerability::referenced_product_ids)
+    }
+
+    /// Product IDs referenced by `vulnerabilities` but not defined in the `product_tree`, each listed once in the order
+    /// first referenced. See [Csaf::all_referenced_product_ids] for the references searched.
+    pub fn referenced_but_undefined_products(&self) -> Vec<ProductIdT> {
+        let defined: HashSet<_> = self.all_product_ids().collect();
+        let mut reported = HashSet::new();
+        self.all_referenced_product_ids()
+            .filter(|id| !defined.contains(id) && reported.insert(*id))
+            .cloned()
+            .collect()
+    }
+
+    /// Every vulnerability listing the product in its product status, paired with the status it is listed under. A
+    /// vulnerability listing the product under several statuses is yielded once per status.
+    pub fn vulnerabilities_for_product<'a>(
+        &'a self,
+        id: &'a ProductIdT,
+    ) -> impl Iterator<Item = (&'a Vulnerability, ProductStatusKind)> + 'a {
+        self.vulnerabilities
+            .iter()
+            .flatten()
+            .flat_map(move |vulnerability| {
+                vulnerability
+                    .product_status
+                    .iter()
+                    .flat_map(|status| status.entries())
+                    .filter(move |(_, listed)| *listed == id)
+                    .map(move |(kind, _)| (vulnerability, kind))
+            })
+    }
+
+    /// Every vulnerability paired with each product defined in the `product_tree` it lists as `first_affected`,
+    /// `known_affected` or `last_affected`. Product IDs that are not defined are skipped.
+    pub fn affected_products(&self) -> impl Iterator<Item = (&Vulnerability, &FullProductName)> {
+        self.products_with_status(ProductStatusKind::is_affected)
+    }
+
+    /// Pairs as for [Csaf::affected_products], with products listed as `first_fixed` or `fixed`
+    pub fn fixed_products(&self) -> impl Iterator<Item = (&Vulnerability, &FullProductName)> {
+        self.products_with_status(ProductStatusKind::is_fixed)
+    }
+
+    /// Pairs as for [Csaf::affected_products], with products listed as `known_not_affected`
+    pub fn not_affected_products(
+        &self,
+    ) -> impl Iterator<Item = (&Vulnerability, &FullProductName)> {
+        self.products_with_status(ProductStatusKind::is_not_affected)
+    }
+
+    /// Pairs each vulnerability with the products it lists under a status matching `predicate`, in `product_tree` order
+    fn products_with_status(
+        &self,
+        predicate: fn(ProductStatusKind) -> bool,
+    ) -> impl Iterator<Item = (&Vulnerability, &FullProductName)> {
+        let mut defined = HashSet::new();
+        let products: Vec<_> = self
+            .product_tree
+            .iter()
+            .flat_map(|tree| tree.defined_products())
+            .filter(|product| defined.insert(&product.product_id))
+            .collect();
+        self.vulnerabilities
+            .iter()
+            .flatten()
+            .flat_map(move |vulnerability| {
+                let status = vulnerability.product_status.as_ref();
+                products
+                    .clone()
+                    .into_iter()
+                    .filter(move |product| {
+                        status.map_or(false, |status| {
+                            status.has_status(&product.product_id, predicate)
+                        })
+                    })
+                    .map(move |product| (vulnerability, product))
+            })
+    }
+
+    /// The severity rating of the highest CVSS base score across every vulnerability, suitable for
+    /// `document.aggregate_severity`, or `None` if nothing is scored
+    pub fn aggregate_severity_from_scores(&self) -> Option<AggregateSeverity> {
+        let max_score = self
+            .vulnerabilities
+            .iter()
+            .flatten()
+            .filter_map(Vulnerability::max_cvss_score)
+            .reduce(f64::max)?;
+        Some(AggregateSeverity {
+            text: CvssSeverity::from_score(max_score).to_string(),
+            namespace: Some(
+                "https://www.first.org/cvss/"
+                    .parse()
+                    .expect("Is a valid URL"),
+            ),
+        })
+    }
+
+    /// Each vulnerability with the severity of its [Vulnerability::max_cvss_score], from the highest score to the
+    /// lowest. Vulnerabilities without a score come last, paired with [CvssSeverity::None], and otherwise keep their
+    /// order in the document.
+    pub fn vulnerabilities_by_severity(&self) -> Vec<(&Vulnerability, CvssSeverity)> {
+        let mut scored: Vec<_> = self
+            .vulnerabilities
+            .iter()
+            .flatten()
+            .map(|vulnerability| (vulnerability, vulnerability.max_cvss_score()))
+            .collect();
+        // Some(_) orders above None, so reversing puts unscored vulnerabilities last
+        scored.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        scored
+            .into_iter()
+            .map(|(vulnerability, score)| {
+                (
+                    vulnerability,
+                    score.map_or(CvssSeverity::None, CvssSeverity::from_score),
+                )
+            })
+            .collect()
+    }
+
+    /// The [ProductTree::max_depth] of the product tree, or 0 without one
+    pub fn product_tree_depth(&self) -> usize {
+        self.product_tree.as_ref().map_or(0, ProductTree::max_depth)
+    }
+
+    /// The most specific profile whose requirements the document meets, falling back to [CsafProfile::Base]
+    ///
+    /// The profile is selected by the document category, so a document meeting the requirements of a profile other
+    /// than the one its category names is still [CsafProfile::Base].
+    pub fn profile(&self) -> CsafProfile {
+        let document = &self.document;
+        // Both incident responses and informational advisories need context beyond the title
+        let has_context = document.notes.iter().flatten().any(|note| {
+            matches!(
+                note.category,
+                NoteCategory::Description
+                    | NoteCategory::Details
+                    | NoteCategory::General
+                    | NoteCategory::Summary
+            )
+        }) && document.references.iter().flatten().any(|reference| {
+            reference
+                .category
+                .as_ref()
+                .map_or(true, |category| *category == ReferenceCategory::External)
+        });
+        let vulnerabilities = self
+            .vulnerabilities
+            .as_ref()
+            .filter(|vulnerabilities| !vulnerabilities.is_empty());
+
+        match document.category {
+            Category::SecurityIncidentResponse if has_context => {
+                CsafProfile::SecurityIncidentResponse
+            }
+            Category::InformationalAdvisory if has_context && self.vulnerabilities.is_none() => {
+                CsafProfile::InformationalAdvisory
+            }
+            Category::SecurityAdvisory
+                if self.product_tree.is_some() && vulnerabilities.is_some() =>
+            {
+                CsafProfile::SecurityAdvisory
+            }
+            Category::Vex
+                if self.product_tree.is_some()
+                    && vulnerabilities.map_or(false, |vulnerabilities| {
+                        vulnerabilities.iter().all(|vulnerability| {
+                            vulnerability
+                                .is_vex_compliant(self.product_tree.as_ref())
+                                .is_ok()
+                        })
+                    }) =>
+            {
+                CsafProfile::Vex
+            }
+            _ => CsafProfile::Base,
+        }
+    }
+
+    /// Compares this document to a later version of it. See [diff] for details.
+    pub fn diff(&self, other: &Csaf) -> CsafDiff {
+        diff::diff(self, other)
+    }
+
+    /// A copy of the document prepared for publishing: `status` is final, the `generator` is removed, notes with an
+    /// `internal` audience are dropped and a revision dated now is recorded with [document::Tracking::bump_revision],
+    /// which also updates `version` and `current_release_date`
+    ///
+    /// Notes lists left empty are removed, as the spec requires at least one note when present.
+    pub fn for_publication(&self) -> Csaf {
+        fn strip_internal(notes: &mut Option<NotesT>) {
+            if let Some(list) = notes {
+                list.retain(|note| {
+                    !note.audience.as_deref().map_or(false, |audience| {
+                        audience.trim().eq_ignore_ascii_case("internal")
+                    })
+                });
+                if list.is_empty() {
+                    *notes = None;
+                }
+            }
+        }
+
+        let mut output = self.clone();
+        let tracking = &mut output.document.tracking;
+        tracking.generator = None;
+        tracking.status = Status::Final;
+        tracking.bump_revision("Published");
+        strip_internal(&mut output.document.notes);
+        for vulnerability in output.vulnerabilities.iter_mut().flatten() {
+            strip_internal(&mut vulnerability.notes);
+        }
+        output
+    }
+
+    /// Combines documents, such as per-advisory VEX documents, into a single document.
+    ///
+    /// Document metadata is taken from the first document, except for the release dates which span all of them.
+    /// Products are deduplicated by ID, keeping the first definition. Vulnerabilities present in several documents are
+    /// combined, which fails if the documents list a product under different statuses. See [merge] for details.
+    pub fn merge(documents: impl IntoIterator<Item = Csaf>) -> Result<Csaf, MergeError> {
+        merge::merge(documents)
+    }
+}
+
+/// Builder for [Csaf] which validates the document before returning it
+///
+/// ```
+/// # use csaf::{Csaf, CsafBuilder};
+/// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../tests/CVE-2018-0171-modified.json")).unwrap();
+/// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+/// # let example: Csaf = serde_json::from_value(json).unwrap();
+/// let mut builder = CsafBuilder::new(example.document).product_tree(example.product_tree.unwrap());
+/// for vulnerability in example.vulnerabilities.unwrap() {
+///     builder = builder.vulnerability(vulnerability);
+/// }
+/// let document = builder.build().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsafBuilder {
+    document: Document,
+    product_tree: Option<ProductTree>,
+    vulnerabilities: Vec<Vulnerability>,
+    aggregate_severity_from_scores: bool,
+}
+
+impl CsafBuilder {
+    pub fn new(document: Document) -> Self {
+        Self {
+            document,
+            product_tree: None,
+            vulnerabilities: Vec::new(),
+            aggregate_severity_from_scores: false,
+        }
+    }
+
+    pub fn product_tree(mut self, product_tree: ProductTree) -> Self {
+        self.product_tree = Some(product_tree);
+        self
+    }
+
+    pub fn vulnerability(mut self, vulnerability: Vulnerability) -> Self {
+        self.vulnerabilities.push(vulnerability);
+        self
+    }
+
+    /// Sets `document.aggregate_severity` from [Csaf::aggregate_severity_from_scores] when building, replacing any set on
+    /// the document if there are scores
+    pub fn aggregate_severity_from_scores(mut self) -> Self {
+        self.aggregate_severity_from_scores = true;
+        self
+    }
+
+    /// Assembles the document, returning every violation found by [Csaf::validate] if it is not valid
+    pub fn build(self) -> Result<Csaf, Vec<ValidationError>> {
+        let mut csaf = Csaf {
+            document: self.document,
+            product_tree: self.product_tree,
+            vulnerabilities: if self.vulnerabilities.is_empty() {
+                None
+            } else {
+                Some(self.vulnerabilities)
+            },
+        };
+        if self.aggregate_severity_from_scores {
+            if let Some(severity) = csaf.aggregate_severity_from_scores() {
+                csaf.document.aggregate_severity = Some(severity);
+            }
+        }
+        csaf.validate()?;
+        Ok(csaf)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    pub(crate) mod fixtures;
+    mod roundtrip;
+
     use super::*;
-    use serde_json;
 
     #[test]
     fn generic_template_deserializes() {
@@ -75,8 +435,7 @@ mod tests {
 
     #[test]
     fn first_example_deserializes() {
-        let example = include_str!("../tests/CVE-2018-0171-modified.json");
-        let document: Csaf = serde_json::from_str(example).unwrap();
+        let document = fixtures::example();
         println!("{:#?}", document);
     }
     #[test]
@@ -91,4 +450,361 @@ mod tests {
         let document: Csaf = serde_json::from_str(example).unwrap();
         println!("{:#?}", document);
     }
+
+    #[test]
+    fn all_product_ids() {
+        let document = fixtures::example();
+
+        let defined: std::collections::HashSet<_> = document.all_product_ids().collect();
+        assert!(!defined.is_empty());
+        assert!(document
+            .all_referenced_product_ids()
+            .all(|id| defined.contains(id)));
+
+        let status = document.vulnerabilities.as_ref().unwrap()[0]
+            .product_status
+            .as_ref()
+            .unwrap();
+        let known_affected = status.known_affected.as_ref().unwrap();
+        assert!(document
+            .all_referenced_product_ids()
+            .any(|id| *id == known_affected[0]));
+    }
+
+    #[test]
+    fn vulnerabilities_for_product() {
+        let document = fixtures::example();
+        let vulnerability = &document.vulnerabilities.as_ref().unwrap()[0];
+        let id = &vulnerability
+            .product_status
+            .as_ref()
+            .unwrap()
+            .known_affected
+            .as_ref()
+            .unwrap()[0];
+
+        let found: Vec<_> = document.vulnerabilities_for_product(id).collect();
+        assert_eq!(
+            found,
+            vec![(vulnerability, ProductStatusKind::KnownAffected)]
+        );
+
+        let missing = ProductIdT("CSAFPID-MISSING".to_string());
+        assert_eq!(document.vulnerabilities_for_product(&missing).count(), 0);
+    }
+
+    #[test]
+    fn affected_products() {
+        let document = fixtures::example();
+        let vulnerability = &document.vulnerabilities.as_ref().unwrap()[0];
+        let known_affected = vulnerability
+            .product_status
+            .as_ref()
+            .unwrap()
+            .known_affected
+            .as_ref()
+            .unwrap();
+
+        let affected: Vec<_> = document.affected_products().collect();
+        assert_eq!(affected.len(), known_affected.len());
+        assert!(affected.iter().all(
+            |(v, product)| *v == vulnerability && known_affected.contains(&product.product_id)
+        ));
+        assert_eq!(document.not_affected_products().count(), 0);
+    }
+
+    #[test]
+    fn referenced_but_undefined_products() {
+        let mut document = fixtures::example();
+        assert!(document.referenced_but_undefined_products().is_empty());
+
+        let missing = ProductIdT("CSAFPID-MISSING".to_string());
+        let vulnerability = &mut document.vulnerabilities.as_mut().unwrap()[0];
+        vulnerability.scores.as_mut().unwrap()[0]
+            .products
+            .push(missing.clone());
+        vulnerability
+            .product_status
+            .as_mut()
+            .unwrap()
+            .fixed
+            .get_or_insert_with(Vec::new)
+            .push(missing.clone());
+        assert_eq!(document.referenced_but_undefined_products(), vec![missing]);
+    }
+
+    #[test]
+    fn builder_validates() {
+        let document = fixtures::example();
+
+        let built = CsafBuilder::new(document.document.clone())
+            .product_tree(document.product_tree.clone().unwrap())
+            .vulnerability(document.vulnerabilities.clone().unwrap().remove(0))
+            .build();
+        assert_eq!(built, Ok(document.clone()));
+
+        let errors = CsafBuilder::new(document.document.clone())
+            .vulnerability(document.vulnerabilities.clone().unwrap().remove(0))
+            .build()
+            .unwrap_err();
+        assert!(errors
+            .iter()
+            .all(|e| matches!(e, ValidationError::UndefinedProductId { .. })));
+    }
+
+    #[cfg(feature = "strict")]
+    #[test]
+    fn strict_rejects_unknown_fields() {
+        let mut json = serde_json::json!({
+            "document": {
+                "category": "csaf_base",
+                "csaf_version": "2.0",
+                "publisher": {
+                    "category": "vendor",
+                    "name": "Example",
+                    "namespace": "https://example.com"
+                },
+                "title": "Example",
+                "tracking": {
+                    "current_release_date": "2023-01-01T00:00:00Z",
+                    "id": "EXAMPLE-1",
+                    "initial_release_date": "2023-01-01T00:00:00Z",
+                    "revision_history": [{
+                        "date": "2023-01-01T00:00:00Z",
+                        "number": "1",
+                        "summary": "Initial"
+                    }],
+                    "status": "final",
+                    "version": "1"
+                }
+            },
+            "vulnerabilities": [{ "cve": "CVE-2023-0001" }]
+        });
+        serde_json::from_value::<Csaf>(json.clone()).unwrap();
+
+        json["document"]["tracking"]["verison"] = "1".into();
+        let error = serde_json::from_value::<Csaf>(json.clone()).unwrap_err();
+        assert!(error.to_string().contains("unknown field `verison`"));
+
+        // The single vulnerability `id` of earlier drafts, as in CVE-2018-0171-modified.json, is `ids` in CSAF 2.0
+        let mut json = serde_json::from_str::<serde_json::Value>(include_str!(
+            "../tests/CVE-2018-0171-modified.json"
+        ))
+        .unwrap();
+        let error = serde_json::from_value::<Csaf>(json.clone()).unwrap_err();
+        assert!(error.to_string().contains("unknown field `id`"));
+        json["vulnerabilities"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("id");
+        serde_json::from_value::<Csaf>(json).unwrap();
+    }
+
+    #[test]
+    fn aggregate_severity_from_scores() {
+        let document = fixtures::example();
+        assert_eq!(
+            document
+                .aggregate_severity_from_scores()
+                .map(|severity| severity.text),
+            Some("Critical".to_string())
+        );
+
+        let built = CsafBuilder::new(document.document.clone())
+            .product_tree(document.product_tree.clone().unwrap())
+            .vulnerability(document.vulnerabilities.clone().unwrap().remove(0))
+            .aggregate_severity_from_scores()
+            .build()
+            .unwrap();
+        assert_eq!(
+            built.document.aggregate_severity,
+            document.aggregate_severity_from_scores()
+        );
+
+        let unscored = Csaf {
+            vulnerabilities: None,
+            ..document
+        };
+        assert_eq!(unscored.aggregate_severity_from_scores(), None);
+    }
+
+    #[test]
+    fn profile_requires_category_and_fields() {
+        let mut csaf: Csaf = fixtures::example();
+        assert_eq!(csaf.profile(), CsafProfile::Base);
+        csaf.document.category = Category::SecurityAdvisory;
+        assert_eq!(csaf.profile(), CsafProfile::SecurityAdvisory);
+
+        csaf.document.category = Category::InformationalAdvisory;
+        assert_eq!(csaf.profile(), CsafProfile::Base);
+        csaf.vulnerabilities = None;
+        assert_eq!(csaf.profile(), CsafProfile::InformationalAdvisory);
+
+        csaf.document.category = Category::SecurityAdvisory;
+        assert_eq!(csaf.profile(), CsafProfile::Base);
+    }
+
+    #[test]
+    fn vex_profile_resolves_product_groups() {
+        use vulnerability::{Flag, FlagLabel};
+
+        let mut csaf: Csaf = fixtures::example();
+        csaf.document.category = Category::Vex;
+        assert_eq!(csaf.profile(), CsafProfile::Vex);
+
+        let not_affected = ProductIdT("CVRFPID-100000".to_string());
+        let vulnerability = &mut csaf.vulnerabilities.as_mut().unwrap()[0];
+        vulnerability
+            .product_status
+            .as_mut()
+            .unwrap()
+            .known_not_affected = Some(vec![not_affected.clone()]);
+        vulnerability.flags = Some(vec![Flag {
+            label: FlagLabel::VulnerableCodeNotPresent,
+            date: None,
+            group_ids: Some(vec!["CSAFGID-0001".to_string()]),
+            product_ids: None,
+        }]);
+        assert_eq!(csaf.profile(), CsafProfile::Base);
+
+        csaf.product_tree.as_mut().unwrap().product_groups =
+            Some(vec![product_tree::ProductGroup {
+                group_id: "CSAFGID-0001".to_string(),
+                product_ids: vec![not_affected, ProductIdT("CVRFPID-103559".to_string())],
+                summary: None,
+            }]);
+        assert_eq!(csaf.profile(), CsafProfile::Vex);
+    }
+
+    #[test]
+    fn vex_profile_requires_notes_and_vex_statuses() {
+        let mut csaf: Csaf = fixtures::example();
+        csaf.document.category = Category::Vex;
+        let vulnerability = &mut csaf.vulnerabilities.as_mut().unwrap()[0];
+        let status = vulnerability.product_status.as_mut().unwrap();
+        status.first_affected = status.known_affected.take();
+        assert_eq!(csaf.profile(), CsafProfile::Base);
+
+        let mut csaf: Csaf = fixtures::example();
+        csaf.document.category = Category::Vex;
+        csaf.vulnerabilities.as_mut().unwrap()[0].notes = None;
+        assert_eq!(csaf.profile(), CsafProfile::Base);
+    }
+
+    #[test]
+    fn publication_strips_internal_details() {
+        let mut csaf: Csaf = fixtures::example();
+        csaf.document.tracking.status = Status::Draft;
+        let notes = csaf.document.notes.as_mut().unwrap();
+        let public = notes.len();
+        notes[0].audience = Some("Internal".to_string());
+        csaf.vulnerabilities.as_mut().unwrap()[0].notes = Some(vec![definitions::Note {
+            category: NoteCategory::Other,
+            text: "Ask the kernel team before publishing".to_string(),
+            audience: Some("internal".to_string()),
+            title: None,
+        }]);
+
+        let published = csaf.for_publication();
+        let tracking = &published.document.tracking;
+        assert_eq!(tracking.status, Status::Final);
+        assert!(tracking.generator.is_none());
+        assert!(tracking.current_release_date > csaf.document.tracking.current_release_date);
+        let revision = tracking.latest_revision().unwrap();
+        assert_eq!(revision.date, tracking.current_release_date);
+        assert_eq!(revision.number, tracking.version);
+        assert_ne!(tracking.version, csaf.document.tracking.version);
+        assert_eq!(published.validate(), Ok(()));
+        assert_eq!(published.document.notes.unwrap().len(), public - 1);
+        assert!(published.vulnerabilities.unwrap()[0].notes.is_none());
+    }
+
+    #[test]
+    fn vulnerabilities_sorted_by_severity() {
+        let mut csaf: Csaf = fixtures::example();
+        let critical = csaf.vulnerabilities.as_ref().unwrap()[0].clone();
+        let mut medium = critical.clone();
+        medium.cve = Some("CVE-2099-0002".to_string());
+        let score = &mut medium.scores.as_mut().unwrap()[0];
+        score.cvss_v3 = Some(
+            "CVSS:3.0/AV:N/AC:H/PR:L/UI:N/S:U/C:L/I:L/A:N"
+                .parse()
+                .unwrap(),
+        );
+        let unscored = Vulnerability {
+            cve: Some("CVE-2099-0001".to_string()),
+            ..Default::default()
+        };
+        csaf.vulnerabilities = Some(vec![unscored, medium, critical]);
+
+        let sorted: Vec<_> = csaf
+            .vulnerabilities_by_severity()
+            .into_iter()
+            .map(|(vulnerability, severity)| (vulnerability.cve.as_deref().unwrap(), severity))
+            .collect();
+        assert_eq!(
+            sorted,
+            [
+                ("CVE-2018-0171", CvssSeverity::Critical),
+                ("CVE-2099-0002", CvssSeverity::Medium),
+                ("CVE-2099-0001", CvssSeverity::None),
+            ]
+        );
+    }
+
+    #[cfg(feature = "csaf_2_1")]
+    #[test]
+    fn exporters_handle_csaf_2_1_documents() {
+        use crate::vulnerability::{Epss, Metric, MetricContent, RemediationCategory};
+
+        let mut csaf: Csaf = fixtures::example();
+        csaf.document.csaf_version = document::CsafVersion::TwoDotOne;
+        csaf.document.license_expression = Some("CC-BY-4.0".to_string());
+        let vulnerability = &mut csaf.vulnerabilities.as_mut().unwrap()[0];
+        vulnerability.disclosure_date = vulnerability.release_date;
+        vulnerability.metrics = Some(vec![Metric {
+            content: MetricContent {
+                epss: Some(Epss {
+                    percentile: "0.9".to_string(),
+                    probability: "0.5".to_string(),
+                    timestamp: csaf.document.tracking.current_release_date,
+                }),
+                ..Default::default()
+            },
+            products: vulnerability.scores.as_ref().unwrap()[0].products.clone(),
+            source: None,
+        }]);
+        vulnerability.remediations.as_mut().unwrap()[0].category = RemediationCategory::FixPlanned;
+
+        let json = csaf.to_pretty_json().unwrap();
+        assert_eq!(serde_json::from_str::<Csaf>(&json).unwrap(), csaf);
+        assert!(csaf.to_markdown_summary().contains("Fix planned"));
+        #[cfg(feature = "html")]
+        assert!(!csaf.to_html_report().is_empty());
+        #[cfg(feature = "sarif")]
+        csaf.to_sarif().unwrap();
+        #[cfg(feature = "cvrf-interop")]
+        assert!(matches!(
+            csaf.to_cvrf_xml(),
+            Err(interop::cvrf::CvrfError::Unsupported { .. })
+        ));
+        #[cfg(feature = "osv-interop")]
+        let _ = interop::osv::schema::Vulnerability::from(csaf.clone());
+
+        let mut feed = feed::CsafRolieFeed::new(
+            feed::CsafFeedIndex::new(
+                "https://example.com/.well-known/csaf/provider-metadata.json"
+                    .parse()
+                    .unwrap(),
+                csaf.document.publisher.clone(),
+                &[],
+            )
+            .provider_metadata,
+        );
+        feed.add_entry("https://example.com/a.json".parse().unwrap(), &csaf);
+        assert_eq!(
+            feed.to_json()["feed"]["entry"][0]["format"]["version"],
+            "2.1"
+        );
+    }
 }
@@ -0,0 +1,354 @@
+//! Combining several CSAF documents, typically per-advisory VEX documents, into one with [Csaf::merge]
+
+use std::{collections::HashSet, fmt::Display};
+
+use crate::{
+    definitions::{Branch, BranchesT, ProductIdT},
+    product_tree::ProductTree,
+    vulnerability::{ProductStatus, ProductStatusKind, Vulnerability},
+    Csaf,
+};
+
+/// Reasons documents could not be merged
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeError {
+    /// No documents were given
+    NoDocuments,
+    /// Two documents list a product under different statuses for the same vulnerability
+    ConflictingStatus {
+        vulnerability: String,
+        product_id: ProductIdT,
+        first: Vec<ProductStatusKind>,
+        second: Vec<ProductStatusKind>,
+    },
+}
+
+impl Display for MergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoDocuments => write!(f, "at least one document is required to merge"),
+            Self::ConflictingStatus {
+                vulnerability,
+                product_id,
+                first,
+                second,
+            } => write!(
+                f,
+                "documents disagree on the status of product {} for {}: {:?} and {:?}",
+                product_id.0, vulnerability, first, second
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// A product listed under different statuses by two of the statuses passed to [ProductStatus::merge]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    pub product_id: ProductIdT,
+    pub first: Vec<ProductStatusKind>,
+    pub second: Vec<ProductStatusKind>,
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "product {} is listed as {:?} and as {:?}",
+            self.product_id.0, self.first, self.second
+        )
+    }
+}
+
+impl std::error::Error for MergeConflict {}
+
+impl ProductStatus {
+    /// Combines the statuses of several sources for the same vulnerability, removing duplicate product IDs
+    ///
+    /// A product may appear in any number of the statuses, but must be listed under the same status lists in each.
+    pub fn merge(
+        statuses: impl IntoIterator<Item = ProductStatus>,
+    ) -> Result<ProductStatus, MergeConflict> {
+        let mut output = ProductStatus::default();
+        for status in statuses {
+            merge_status(&mut output, &status)?;
+        }
+        Ok(output)
+    }
+}
+
+pub(crate) fn merge(documents: impl IntoIterator<Item = Csaf>) -> Result<Csaf, MergeError> {
+    let mut documents = documents.into_iter();
+    let first = documents.next().ok_or(MergeError::NoDocuments)?;
+
+    let mut output = Csaf {
+        document: first.document.clone(),
+        product_tree: None,
+        vulnerabilities: None,
+    };
+    let mut defined = HashSet::new();
+
+    for document in std::iter::once(first).chain(documents) {
+        let tracking = &mut output.document.tracking;
+        tracking.initial_release_date = tracking
+            .initial_release_date
+            .min(document.document.tracking.initial_release_date);
+        tracking.current_release_date = tracking
+            .current_release_date
+            .max(document.document.tracking.current_release_date);
+
+        if let Some(tree) = document.product_tree {
+            merge_product_tree(
+                output.product_tree.get_or_insert_with(Default::default),
+                tree,
+                &mut defined,
+            );
+        }
+
+        for vulnerability in document.vulnerabilities.into_iter().flatten() {
+            let merged = output.vulnerabilities.get_or_insert_with(Vec::new);
+            let key = vulnerability.key();
+            match merged
+                .iter_mut()
+                .find(|existing| key.is_some() && existing.key() == key)
+            {
+                Some(existing) => merge_vulnerability(existing, vulnerability)?,
+                None => merged.push(vulnerability),
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Adds the products of `from` not already in `defined` to `into`
+fn merge_product_tree(
+    into: &mut ProductTree,
+    from: ProductTree,
+    defined: &mut HashSet<ProductIdT>,
+) {
+    if let Some(branches) = from.branches {
+        let into = into.branches.get_or_insert_with(|| BranchesT(Vec::new()));
+        merge_branches(&mut into.0, branches.0, defined);
+    }
+
+    for product in from.full_product_names.into_iter().flatten() {
+        if defined.insert(product.product_id.clone()) {
+            into.full_product_names
+                .get_or_insert_with(Vec::new)
+                .push(product);
+        }
+    }
+
+    for relationship in from.relationships.into_iter().flatten() {
+        if defined.insert(relationship.full_product_name.product_id.clone()) {
+            into.relationships
+                .get_or_insert_with(Vec::new)
+                .push(relationship);
+        }
+    }
+
+    for group in from.product_groups.into_iter().flatten() {
+        let groups = into.product_groups.get_or_insert_with(Vec::new);
+        match groups.iter_mut().find(|g| g.group_id == group.group_id) {
+            Some(existing) => extend_unique(&mut existing.product_ids, group.product_ids),
+            None => groups.push(group),
+        }
+    }
+}
+
+/// Branches without a product are matched by name and category and merged recursively
+fn merge_branches(into: &mut Vec<Branch>, from: Vec<Branch>, defined: &mut HashSet<ProductIdT>) {
+    for mut branch in from {
+        match &branch.product {
+            Some(product) => {
+                if defined.insert(product.product_id.clone()) {
+                    into.push(branch);
+                }
+            }
+            None => {
+                let children = branch.branches.take().map(|b| b.0).unwrap_or_default();
+                let index = match into.iter().position(|existing| {
+                    existing.product.is_none()
+                        && existing.name == branch.name
+                        && existing.category == branch.category
+                }) {
+                    Some(index) => index,
+                    None => {
+                        into.push(branch);
+                        into.len() - 1
+                    }
+                };
+                let into = into[index]
+                    .branches
+                    .get_or_insert_with(|| BranchesT(Vec::new()));
+                merge_branches(&mut into.0, children, defined);
+            }
+        }
+    }
+}
+
+fn merge_vulnerability(into: &mut Vulnerability, from: Vulnerability) -> Result<(), MergeError> {
+    if let Some(from) = &from.product_status {
+        let status = into.product_status.get_or_insert_with(Default::default);
+        merge_status(status, from).map_err(|conflict| MergeError::ConflictingStatus {
+            vulnerability: into.key().unwrap_or_default(),
+            product_id: conflict.product_id,
+            first: conflict.first,
+            second: conflict.second,
+        })?;
+    }
+
+    extend_unique_option(&mut into.scores, from.scores);
+    if let Some(from) = from.remediations {
+        extend_unique(
+            into.remediations.get_or_insert_with(Default::default),
+            from.0,
+        );
+    }
+    extend_unique_option(&mut into.threats, from.threats);
+    extend_unique_option(&mut into.flags, from.flags);
+    extend_unique_option(&mut into.notes, from.notes);
+    extend_unique_option(&mut into.references, from.references);
+    extend_unique_option(&mut into.acknowledgments, from.acknowledgments);
+    extend_unique_option(&mut into.involvements, from.involvements);
+    extend_unique_option(&mut into.ids, from.ids);
+    Ok(())
+}
+
+/// Adds the products of `from` that are not in `into` yet, under the same statuses
+fn merge_status(into: &mut ProductStatus, from: &ProductStatus) -> Result<(), MergeConflict> {
+    // Entries are grouped by status, so a product listed twice under one status is adjacent
+    let by_product = |status: &ProductStatus| {
+        let mut by_product = status.by_product();
+        by_product.values_mut().for_each(Vec::dedup);
+        by_product
+    };
+    let existing = by_product(into);
+    for (product_id, second) in by_product(from) {
+        match existing.get(&product_id) {
+            Some(first) if *first != second => {
+                return Err(MergeConflict {
+                    product_id,
+                    first: first.clone(),
+                    second,
+                })
+            }
+            Some(_) => {}
+            None => {
+                for kind in second {
+                    into.list_mut(kind)
+                        .get_or_insert_with(Vec::new)
+                        .push(product_id.clone());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn extend_unique<T: PartialEq>(into: &mut Vec<T>, from: Vec<T>) {
+    for item in from {
+        if !into.contains(&item) {
+            into.push(item);
+        }
+    }
+}
+
+fn extend_unique_option<T: PartialEq>(into: &mut Option<Vec<T>>, from: Option<Vec<T>>) {
+    if let Some(from) = from {
+        extend_unique(into.get_or_insert_with(Vec::new), from);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::example;
+    use chrono::Duration;
+
+    #[test]
+    fn merging_a_document_with_itself_is_a_no_op() {
+        let merged = Csaf::merge([example(), example()]).unwrap();
+        assert_eq!(merged, example());
+    }
+
+    #[test]
+    fn combines_vulnerabilities_and_release_dates() {
+        let first = example();
+        let mut second = example();
+        second.vulnerabilities.as_mut().unwrap()[0].cve = Some("CVE-2099-0001".to_string());
+        second.document.tracking.initial_release_date -= Duration::days(1);
+        second.document.tracking.current_release_date += Duration::days(1);
+
+        let merged = Csaf::merge([first.clone(), second.clone()]).unwrap();
+        assert_eq!(merged.vulnerabilities.as_ref().unwrap().len(), 2);
+        assert_eq!(
+            merged.document.tracking.initial_release_date,
+            second.document.tracking.initial_release_date
+        );
+        assert_eq!(
+            merged.document.tracking.current_release_date,
+            second.document.tracking.current_release_date
+        );
+        assert_eq!(merged.product_tree, first.product_tree);
+        assert_eq!(merged.validate(), Ok(()));
+    }
+
+    #[test]
+    fn conflicting_status_is_an_error() {
+        let mut second = example();
+        let status = second.vulnerabilities.as_mut().unwrap()[0]
+            .product_status
+            .as_mut()
+            .unwrap();
+        let moved = status.known_affected.as_mut().unwrap().remove(0);
+        status.fixed = Some(vec![moved.clone()]);
+
+        assert_eq!(
+            Csaf::merge([example(), second]),
+            Err(MergeError::ConflictingStatus {
+                vulnerability: "CVE-2018-0171".to_string(),
+                product_id: moved,
+                first: vec![ProductStatusKind::KnownAffected],
+                second: vec![ProductStatusKind::Fixed],
+            })
+        );
+    }
+
+    #[test]
+    fn no_documents() {
+        assert_eq!(Csaf::merge([]), Err(MergeError::NoDocuments));
+    }
+
+    #[test]
+    fn merges_product_statuses() {
+        let product = |id: &str| ProductIdT(id.to_string());
+        let first = ProductStatus {
+            fixed: Some(vec![product("A"), product("A")]),
+            known_affected: Some(vec![product("B")]),
+            ..Default::default()
+        };
+        let second = ProductStatus {
+            fixed: Some(vec![product("A"), product("C")]),
+            ..Default::default()
+        };
+        let merged = ProductStatus::merge([first.clone(), second]).unwrap();
+        assert_eq!(merged.fixed, Some(vec![product("A"), product("C")]));
+        assert_eq!(merged.known_affected, Some(vec![product("B")]));
+
+        let conflicting = ProductStatus {
+            known_not_affected: Some(vec![product("B")]),
+            ..Default::default()
+        };
+        assert_eq!(
+            ProductStatus::merge([first, conflicting]),
+            Err(MergeConflict {
+                product_id: product("B"),
+                first: vec![ProductStatusKind::KnownAffected],
+                second: vec![ProductStatusKind::KnownNotAffected],
+            })
+        );
+    }
+}
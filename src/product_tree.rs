@@ -1,10 +1,20 @@
+use std::collections::{HashMap, HashSet};
+
 use serde::{Deserialize, Serialize};
 
-use crate::definitions::{BranchesT, FullProductName, ProductGroupIdT, ProductIdT};
+use crate::{
+    definitions::{
+        Branch, BranchCategory, BranchesT, Cpe, CpeParseError, FullProductName, ProductGroupIdT,
+        ProductIdT, ProductIdentificationHelper,
+    },
+    interop::child_branches,
+    validation::ValidationError,
+};
 
 /// [Product Tree](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#322-product-tree-property)
 #[serde_with::skip_serializing_none]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct ProductTree {
     pub branches: Option<BranchesT>,
     pub full_product_names: Option<Vec<FullProductName>>,
@@ -12,17 +22,312 @@ pub struct ProductTree {
     pub relationships: Option<Vec<Relationship>>,
 }
 
+impl ProductTree {
+    /// Builds a tree of vendor, product name and product version branches from CPEs, such as those in the NVD CPE
+    /// dictionary, with one product per distinct CPE
+    ///
+    /// Products are given IDs `CSAFPID-0001`, `CSAFPID-0002` and so on in the order first listed. A CPE without a
+    /// version is placed under a `product_version_range` branch named `vers:all/*`, and missing vendor or product
+    /// names are given as `*`.
+    ///
+    /// ```
+    /// # use csaf::{definitions::ProductIdT, product_tree::ProductTree};
+    /// let tree = ProductTree::from_cpe_list([
+    ///     "cpe:2.3:o:cisco:ios:15.5\\(2\\)t1:*:*:*:*:*:*:*",
+    ///     "cpe:/o:cisco:ios_xe:16.2.1",
+    /// ])
+    /// .unwrap();
+    /// let product = tree.lookup_product(&ProductIdT("CSAFPID-0002".to_string())).unwrap();
+    /// assert_eq!(product.name, "cisco ios_xe 16.2.1");
+    /// ```
+    pub fn from_cpe_list<'a>(
+        cpes: impl IntoIterator<Item = &'a str>,
+    ) -> Result<ProductTree, CpeParseError> {
+        let mut branches = Vec::new();
+        let mut seen = HashSet::new();
+
+        for cpe in cpes {
+            let cpe: Cpe = cpe.parse()?;
+            if !seen.insert(cpe.as_str().to_string()) {
+                continue;
+            }
+
+            let vendor = cpe.vendor().unwrap_or("*");
+            let product = cpe.product().unwrap_or("*");
+            let (version, category) = match cpe.version() {
+                Some(version) => (version, BranchCategory::ProductVersion),
+                None => ("vers:all/*", BranchCategory::ProductVersionRange),
+            };
+            let name = [cpe.vendor(), cpe.product(), cpe.version(), cpe.update()]
+                .iter()
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let vendor_branches = child_branches(&mut branches, vendor, BranchCategory::Vendor);
+            let leaves = child_branches(vendor_branches, product, BranchCategory::ProductName);
+            leaves.push(Branch {
+                name: version.to_string(),
+                category,
+                product: Some(FullProductName {
+                    name,
+                    product_id: ProductIdT(format!("CSAFPID-{:04}", seen.len())),
+                    product_identification_helper: Some(ProductIdentificationHelper {
+                        cpe: Some(cpe),
+                        ..Default::default()
+                    }),
+                }),
+                branches: None,
+            });
+        }
+
+        Ok(ProductTree {
+            branches: Some(BranchesT(branches)),
+            ..Default::default()
+        })
+    }
+
+    /// Finds the product with the given ID, searching `branches` depth-first, then `full_product_names`, then
+    /// `relationships`
+    pub fn lookup_product(&self, id: &ProductIdT) -> Option<&FullProductName> {
+        fn search<'a>(branches: &'a BranchesT, id: &ProductIdT) -> Option<&'a FullProductName> {
+            branches.iter().find_map(|branch| {
+                branch
+                    .product
+                    .as_ref()
+                    .filter(|product| product.product_id == *id)
+                    .or_else(|| branch.branches.as_ref().and_then(|b| search(b, id)))
+            })
+        }
+
+        self.branches
+            .as_ref()
+            .and_then(|branches| search(branches, id))
+            .or_else(|| {
+                self.full_product_names
+                    .iter()
+                    .flatten()
+                    .find(|product| product.product_id == *id)
+            })
+            .or_else(|| {
+                self.relationships
+                    .iter()
+                    .flatten()
+                    .map(|relationship| &relationship.full_product_name)
+                    .find(|product| product.product_id == *id)
+            })
+    }
+
+    /// Mutable version of [ProductTree::lookup_product]
+    pub fn lookup_product_mut(&mut self, id: &ProductIdT) -> Option<&mut FullProductName> {
+        fn search<'a>(
+            branches: &'a mut BranchesT,
+            id: &ProductIdT,
+        ) -> Option<&'a mut FullProductName> {
+            branches.iter_mut().find_map(|branch| {
+                match &mut branch.product {
+                    Some(product) if product.product_id == *id => return Some(product),
+                    _ => {}
+                }
+                branch.branches.as_mut().and_then(|b| search(b, id))
+            })
+        }
+
+        if let Some(product) = self
+            .branches
+            .as_mut()
+            .and_then(|branches| search(branches, id))
+        {
+            return Some(product);
+        }
+        if let Some(product) = self
+            .full_product_names
+            .iter_mut()
+            .flatten()
+            .find(|product| product.product_id == *id)
+        {
+            return Some(product);
+        }
+        self.relationships
+            .iter_mut()
+            .flatten()
+            .map(|relationship| &mut relationship.full_product_name)
+            .find(|product| product.product_id == *id)
+    }
+
+    /// Every product defined in `branches` (recursively), `full_product_names` and `relationships`, in that order.
+    /// Duplicates are kept.
+    pub(crate) fn defined_products(&self) -> Vec<&FullProductName> {
+        fn collect<'a>(branches: &'a BranchesT, output: &mut Vec<&'a FullProductName>) {
+            for branch in branches {
+                if let Some(product) = &branch.product {
+                    output.push(product);
+                }
+                if let Some(children) = &branch.branches {
+                    collect(children, output);
+                }
+            }
+        }
+
+        let mut output = Vec::new();
+        if let Some(branches) = &self.branches {
+            collect(branches, &mut output);
+        }
+        output.extend(self.full_product_names.iter().flatten());
+        output.extend(
+            self.relationships
+                .iter()
+                .flatten()
+                .map(|relationship| &relationship.full_product_name),
+        );
+        output
+    }
+
+    /// The number of distinct product IDs defined in `branches` (recursively) and `full_product_names`. Products
+    /// defined by relationships are combinations of these, so they are not counted.
+    pub fn product_count(&self) -> usize {
+        // Relationships come last in defined_products
+        let products = self.defined_products();
+        products[..products.len() - self.relationship_count()]
+            .iter()
+            .map(|product| &product.product_id)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// The number of entries in `product_groups`
+    pub fn group_count(&self) -> usize {
+        self.product_groups.as_ref().map_or(0, Vec::len)
+    }
+
+    /// The number of entries in `relationships`
+    pub fn relationship_count(&self) -> usize {
+        self.relationships.as_ref().map_or(0, Vec::len)
+    }
+
+    /// The number of levels of `branches`: 0 without branches, 1 when the top level branches have no children, and so
+    /// on. Computed without recursion, so it is safe to call on trees too deep for recursive code.
+    pub fn max_depth(&self) -> usize {
+        let mut max_depth = 0;
+        let mut pending: Vec<(&BranchesT, usize)> =
+            self.branches.iter().map(|branches| (branches, 1)).collect();
+        while let Some((branches, depth)) = pending.pop() {
+            if branches.0.is_empty() {
+                continue;
+            }
+            max_depth = max_depth.max(depth);
+            pending.extend(
+                branches
+                    .0
+                    .iter()
+                    .filter_map(|branch| branch.branches.as_ref())
+                    .map(|children| (children, depth + 1)),
+            );
+        }
+        max_depth
+    }
+
+    /// The IDs of [ProductTree::defined_products]
+    pub(crate) fn defined_product_ids(&self) -> Vec<&ProductIdT> {
+        self.defined_products()
+            .into_iter()
+            .map(|product| &product.product_id)
+            .collect()
+    }
+}
+
 /// [Product Groups](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3223-product-tree-property---product-groups)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct ProductGroup {
     pub group_id: ProductGroupIdT,
     pub product_ids: Vec<ProductIdT>,
     pub summary: Option<String>,
 }
 
+/// Builder for [ProductGroup] which takes the two products the spec requires up front
+///
+/// ```
+/// # use csaf::{definitions::ProductIdT, product_tree::ProductGroupBuilder};
+/// let id = |id: &str| ProductIdT(id.to_string());
+/// let group = ProductGroupBuilder::new("CSAFGID-0001", id("CSAFPID-0001"), id("CSAFPID-0002"))
+///     .add_product(id("CSAFPID-0003"))
+///     .summary("All supported releases")
+///     .build();
+/// assert_eq!(group.product_ids.len(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductGroupBuilder {
+    group: ProductGroup,
+}
+
+impl ProductGroupBuilder {
+    pub fn new(group_id: impl Into<String>, first: ProductIdT, second: ProductIdT) -> Self {
+        Self {
+            group: ProductGroup {
+                group_id: group_id.into(),
+                product_ids: vec![first, second],
+                summary: None,
+            },
+        }
+    }
+
+    pub fn add_product(mut self, product_id: ProductIdT) -> Self {
+        self.group.product_ids.push(product_id);
+        self
+    }
+
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.group.summary = Some(summary.into());
+        self
+    }
+
+    pub fn build(self) -> ProductGroup {
+        self.group
+    }
+}
+
+impl ProductGroup {
+    /// Checks that every product in `product_ids` is defined in `tree`, reporting each that is not as a
+    /// [ValidationError::UndefinedGroupMember]. [Csaf::validate](crate::Csaf::validate) includes this check for every
+    /// group.
+    pub fn validate_members_exist(&self, tree: &ProductTree) -> Result<(), Vec<ValidationError>> {
+        let defined = tree
+            .defined_product_ids()
+            .into_iter()
+            .map(|id| id.0.as_str())
+            .collect();
+        self.validate_members_defined(&defined)
+    }
+
+    /// Like [ProductGroup::validate_members_exist], against a set of the IDs the tree defines collected beforehand
+    pub(crate) fn validate_members_defined(
+        &self,
+        defined: &HashSet<&str>,
+    ) -> Result<(), Vec<ValidationError>> {
+        let mut reported = HashSet::new();
+        let errors: Vec<_> = self
+            .product_ids
+            .iter()
+            .filter(|id| !defined.contains(id.0.as_str()) && reported.insert(*id))
+            .map(|id| ValidationError::UndefinedGroupMember {
+                group_id: self.group_id.clone(),
+                missing_product_id: id.clone(),
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
 /// [Relationships](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3224-product-tree-property---relationships)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Relationship {
     pub category: RelationshipCategory,
     pub full_product_name: FullProductName,
@@ -30,9 +335,104 @@ pub struct Relationship {
     pub relates_to_product_reference: ProductIdT,
 }
 
+impl Relationship {
+    /// Checks that `product_reference` and `relates_to_product_reference` are both defined in `tree`, reporting each
+    /// that is not as a [ValidationError::UndefinedProductId]. [Csaf::validate](crate::Csaf::validate) includes this
+    /// check for every relationship.
+    pub fn validate_references(&self, tree: &ProductTree) -> Result<(), Vec<ValidationError>> {
+        let defined = tree.defined_product_ids();
+        let errors: Vec<_> = [&self.product_reference, &self.relates_to_product_reference]
+            .into_iter()
+            .filter(|id| !defined.contains(id))
+            .map(|id| ValidationError::UndefinedProductId {
+                product_id: id.clone(),
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Checks that no product defined by a relationship in `tree` is defined from itself, directly or through other
+    /// relationships, reporting each circle found as a [ValidationError::CircularRelationship].
+    /// [Csaf::validate](crate::Csaf::validate) includes this check.
+    pub fn validate_no_circular_references(tree: &ProductTree) -> Result<(), Vec<ValidationError>> {
+        enum Visit {
+            InProgress,
+            Done,
+        }
+
+        // Each product defined by a relationship points to the two products it is defined from
+        let mut edges: HashMap<&ProductIdT, Vec<&ProductIdT>> = HashMap::new();
+        let relationships = tree.relationships.as_deref().unwrap_or_default();
+        for relationship in relationships {
+            edges
+                .entry(&relationship.full_product_name.product_id)
+                .or_default()
+                .extend([
+                    &relationship.product_reference,
+                    &relationship.relates_to_product_reference,
+                ]);
+        }
+
+        // Depth first search without recursion, as relationship chains in untrusted documents can be arbitrarily long
+        let mut visits = HashMap::new();
+        let mut errors = Vec::new();
+        for relationship in relationships {
+            let start = &relationship.full_product_name.product_id;
+            if visits.contains_key(start) {
+                continue;
+            }
+            visits.insert(start, Visit::InProgress);
+            let mut path = vec![(start, 0)];
+            while let Some(last) = path.len().checked_sub(1) {
+                let (product, next_edge) = path[last];
+                path[last].1 += 1;
+                match edges
+                    .get(product)
+                    .and_then(|targets| targets.get(next_edge))
+                {
+                    None => {
+                        visits.insert(product, Visit::Done);
+                        path.pop();
+                    }
+                    Some(&target) => match visits.get(target) {
+                        None => {
+                            visits.insert(target, Visit::InProgress);
+                            path.push((target, 0));
+                        }
+                        Some(Visit::InProgress) => {
+                            let position = path
+                                .iter()
+                                .position(|(product, _)| *product == target)
+                                .expect("Products in progress are on the path");
+                            errors.push(ValidationError::CircularRelationship {
+                                involved_products: path[position..]
+                                    .iter()
+                                    .map(|(product, _)| (*product).clone())
+                                    .collect(),
+                            });
+                        }
+                        Some(Visit::Done) => {}
+                    },
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
 /// [Relationships](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3224-product-tree-property---relationships)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum RelationshipCategory {
     DefaultComponentOf,
     ExternalComponentOf,
@@ -40,3 +440,186 @@ pub enum RelationshipCategory {
     InstalledWith,
     OptionalComponentOf,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(id: &str) -> FullProductName {
+        FullProductName {
+            name: id.to_string(),
+            product_id: ProductIdT(id.to_string()),
+            product_identification_helper: None,
+        }
+    }
+
+    fn branch(name: &str, category: BranchCategory, branches: Vec<Branch>) -> Branch {
+        Branch {
+            name: name.to_string(),
+            category,
+            product: None,
+            branches: Some(BranchesT(branches)),
+        }
+    }
+
+    fn tree() -> ProductTree {
+        ProductTree {
+            branches: Some(BranchesT(vec![branch(
+                "Vendor",
+                BranchCategory::Vendor,
+                vec![branch(
+                    "Product",
+                    BranchCategory::ProductName,
+                    vec![Branch {
+                        name: "1.0".to_string(),
+                        category: BranchCategory::ProductVersion,
+                        product: Some(product("NESTED")),
+                        branches: None,
+                    }],
+                )],
+            )])),
+            full_product_names: Some(vec![product("FULL")]),
+            product_groups: None,
+            relationships: Some(vec![Relationship {
+                category: RelationshipCategory::InstalledOn,
+                full_product_name: product("RELATIONSHIP"),
+                product_reference: ProductIdT("NESTED".to_string()),
+                relates_to_product_reference: ProductIdT("FULL".to_string()),
+            }]),
+        }
+    }
+
+    #[test]
+    fn counts_distinct_products() {
+        let mut tree = tree();
+        assert_eq!(tree.product_count(), 2);
+        assert_eq!(tree.relationship_count(), 1);
+        assert_eq!(tree.group_count(), 0);
+
+        tree.full_product_names
+            .as_mut()
+            .unwrap()
+            .push(product("NESTED"));
+        assert_eq!(tree.product_count(), 2);
+        assert_eq!(ProductTree::default().product_count(), 0);
+    }
+
+    #[test]
+    fn looks_up_products() {
+        let tree = tree();
+        for id in ["NESTED", "FULL", "RELATIONSHIP"] {
+            let id = ProductIdT(id.to_string());
+            assert_eq!(tree.lookup_product(&id).unwrap().product_id, id);
+        }
+        assert!(tree
+            .lookup_product(&ProductIdT("MISSING".to_string()))
+            .is_none());
+    }
+
+    #[test]
+    fn modifies_nested_product() {
+        let mut tree = tree();
+        let id = ProductIdT("NESTED".to_string());
+        tree.lookup_product_mut(&id).unwrap().name = "Renamed".to_string();
+        assert_eq!(tree.lookup_product(&id).unwrap().name, "Renamed");
+        assert!(tree
+            .lookup_product_mut(&ProductIdT("MISSING".to_string()))
+            .is_none());
+    }
+
+    #[test]
+    fn groups_cpes_by_vendor_and_product() {
+        let tree = ProductTree::from_cpe_list([
+            "cpe:2.3:a:vendor:product:1.0:*:*:*:*:*:*:*",
+            "cpe:2.3:a:vendor:product:2.0:*:*:*:*:*:*:*",
+            "cpe:2.3:a:vendor:product:1.0:*:*:*:*:*:*:*",
+            "cpe:2.3:a:vendor:other:*:*:*:*:*:*:*:*",
+        ])
+        .unwrap();
+
+        assert_eq!(tree.max_depth(), 3);
+        assert_eq!(ProductTree::default().max_depth(), 0);
+
+        let vendors = &tree.branches.as_ref().unwrap().0;
+        assert_eq!(vendors.len(), 1);
+        let products = &vendors[0].branches.as_ref().unwrap().0;
+        assert_eq!(products.len(), 2);
+        assert_eq!(products[0].branches.as_ref().unwrap().0.len(), 2);
+        let any_version = &products[1].branches.as_ref().unwrap().0[0];
+        assert_eq!(any_version.category, BranchCategory::ProductVersionRange);
+        assert_eq!(
+            any_version.product.as_ref().unwrap().product_id,
+            ProductIdT("CSAFPID-0003".to_string())
+        );
+
+        assert_eq!(
+            ProductTree::from_cpe_list(["not a cpe"]),
+            Err(CpeParseError::InvalidPrefix)
+        );
+    }
+
+    #[test]
+    fn detects_circular_relationships() {
+        let mut tree = tree();
+        assert_eq!(Relationship::validate_no_circular_references(&tree), Ok(()));
+
+        let relationship = |id: &str, reference: &str, relates_to: &str| Relationship {
+            category: RelationshipCategory::DefaultComponentOf,
+            full_product_name: product(id),
+            product_reference: ProductIdT(reference.to_string()),
+            relates_to_product_reference: ProductIdT(relates_to.to_string()),
+        };
+        let relationships = tree.relationships.as_mut().unwrap();
+        relationships.push(relationship("A", "B", "FULL"));
+        relationships.push(relationship("B", "A", "NESTED"));
+        relationships.push(relationship("SELF", "SELF", "FULL"));
+        assert_eq!(
+            Relationship::validate_no_circular_references(&tree),
+            Err(vec![
+                ValidationError::CircularRelationship {
+                    involved_products: vec![
+                        ProductIdT("A".to_string()),
+                        ProductIdT("B".to_string())
+                    ]
+                },
+                ValidationError::CircularRelationship {
+                    involved_products: vec![ProductIdT("SELF".to_string())]
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn validates_relationship_references() {
+        let mut tree = tree();
+        let relationship = tree.relationships.as_ref().unwrap()[0].clone();
+        assert_eq!(relationship.validate_references(&tree), Ok(()));
+
+        tree.full_product_names = None;
+        assert_eq!(
+            relationship.validate_references(&tree),
+            Err(vec![ValidationError::UndefinedProductId {
+                product_id: ProductIdT("FULL".to_string())
+            }])
+        );
+    }
+
+    #[test]
+    fn validates_group_members() {
+        let tree = tree();
+        let id = |id: &str| ProductIdT(id.to_string());
+        let group = ProductGroupBuilder::new("GROUP", id("NESTED"), id("RELATIONSHIP")).build();
+        assert_eq!(group.validate_members_exist(&tree), Ok(()));
+
+        let group = ProductGroupBuilder::new("GROUP", id("FULL"), id("MISSING"))
+            .add_product(id("MISSING"))
+            .build();
+        assert_eq!(
+            group.validate_members_exist(&tree),
+            Err(vec![ValidationError::UndefinedGroupMember {
+                group_id: "GROUP".to_string(),
+                missing_product_id: id("MISSING"),
+            }])
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! Human-readable renderings of a document, such as [Csaf::to_markdown_summary] for GitHub issues and release notes,
+//! and `Csaf::to_html_report` for advisory pages, enabled by the `html` feature
+
+use std::fmt::Write;
+
+#[cfg(feature = "html")]
+use crate::{
+    definitions::{BranchesT, FullProductName},
+    vulnerability::CvssSeverity,
+};
+use crate::{
+    document::Status,
+    vulnerability::{RemediationCategory, Vulnerability},
+    Csaf,
+};
+
+impl Csaf {
+    /// Summarizes the document as GitHub flavored Markdown: the title and tracking details, then tables of
+    /// vulnerabilities and affected products and a list of remediations. Sections with nothing to list are left out.
+    ///
+    /// ```
+    /// # use csaf::Csaf;
+    /// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../tests/CVE-2018-0171-modified.json")).unwrap();
+    /// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+    /// let csaf: Csaf = serde_json::from_value(json).unwrap();
+    /// let summary = csaf.to_markdown_summary();
+    /// assert!(summary.starts_with("## Cisco IOS and IOS XE Software Smart Install"));
+    /// assert!(summary.contains("| CVE-2018-0171 |"));
+    /// ```
+    pub fn to_markdown_summary(&self) -> String {
+        let mut output = String::new();
+        let tracking = &self.document.tracking;
+        // Writing to a String cannot fail
+        let _ = writeln!(output, "## {}\n", cell(&self.document.title));
+        let _ = writeln!(output, "**Tracking ID:** {}  ", tracking.id);
+        let _ = writeln!(
+            output,
+            "**Status:** {} (version {})",
+            status_name(&tracking.status),
+            tracking.version
+        );
+
+        let vulnerabilities = self.vulnerabilities.as_deref().unwrap_or_default();
+        if !vulnerabilities.is_empty() {
+            output
+                .push_str("\n### Vulnerabilities\n\n| CVE | Title | CVSS |\n| --- | --- | --- |\n");
+            for vulnerability in vulnerabilities {
+                let _ = writeln!(
+                    output,
+                    "| {} | {} | {} |",
+                    vulnerability.cve.as_deref().unwrap_or("-"),
+                    cell(vulnerability.title.as_deref().unwrap_or("-")),
+                    vulnerability
+                        .max_cvss_score()
+                        .map_or_else(|| "-".to_string(), |score| format!("{:.1}", score))
+                );
+            }
+        }
+
+        let mut affected = self.affected_products().peekable();
+        if affected.peek().is_some() {
+            output.push_str(
+                "\n### Affected products\n\n| Product | Product ID | Vulnerability |\n| --- | --- | --- |\n",
+            );
+            for (vulnerability, product) in affected {
+                let _ = writeln!(
+                    output,
+                    "| {} | {} | {} |",
+                    cell(&product.name),
+                    product.product_id.0,
+                    cell(&label(vulnerability))
+                );
+            }
+        }
+
+        let remediations: Vec<_> = vulnerabilities
+            .iter()
+            .flat_map(|vulnerability| {
+                vulnerability
+                    .remediations
+                    .iter()
+                    .flatten()
+                    .map(move |remediation| (vulnerability, remediation))
+            })
+            .collect();
+        if !remediations.is_empty() {
+            output.push_str("\n### Remediations\n\n");
+            for (vulnerability, remediation) in remediations {
+                let _ = write!(
+                    output,
+                    "- **{}** ({}): {}",
+                    remediation_name(&remediation.category),
+                    label(vulnerability),
+                    remediation.details.replace('\n', " ")
+                );
+                if let Some(url) = &remediation.url {
+                    let _ = write!(output, " <{}>", url);
+                }
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+}
+
+#[cfg(feature = "html")]
+impl Csaf {
+    /// Renders the document as a self-contained HTML page: the title, a table of tracking and publisher details, a
+    /// table of vulnerabilities with their highest CVSS score, and the product tree as nested lists. Styles are
+    /// inline, so the page has no external dependencies.
+    ///
+    /// ```
+    /// # use csaf::Csaf;
+    /// # let mut json: serde_json::Value = serde_json::from_str(include_str!("../tests/CVE-2018-0171-modified.json")).unwrap();
+    /// # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+    /// let csaf: Csaf = serde_json::from_value(json).unwrap();
+    /// let html = csaf.to_html_report();
+    /// assert!(html.starts_with("<!DOCTYPE html>"));
+    /// assert!(html.contains(">CVE-2018-0171</td>"));
+    /// ```
+    pub fn to_html_report(&self) -> String {
+        const TABLE: &str = r#"<table style="border-collapse: collapse; margin-bottom: 1.5em">"#;
+        const CELL: &str = r#"style="border: 1px solid #ccc; padding: 4px 8px; text-align: left""#;
+
+        let document = &self.document;
+        let tracking = &document.tracking;
+        let title = html(&document.title);
+        let mut output = String::new();
+        // Writing to a String cannot fail
+        let _ = write!(
+            output,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n\
+             <body style=\"font-family: sans-serif; max-width: 960px; margin: 2em auto; color: #222\">\n\
+             <h1>{}</h1>\n",
+            title, title
+        );
+
+        output.push_str(TABLE);
+        output.push('\n');
+        let metadata = [
+            ("ID", tracking.id.clone()),
+            ("Status", status_name(&tracking.status).to_string()),
+            ("Version", tracking.version.clone()),
+            (
+                "Initial release",
+                tracking.initial_release_date.to_rfc3339(),
+            ),
+            (
+                "Current release",
+                tracking.current_release_date.to_rfc3339(),
+            ),
+            ("Publisher", document.publisher.name.clone()),
+        ];
+        for (name, value) in metadata {
+            let _ = writeln!(
+                output,
+                "<tr><th {}>{}</th><td {}>{}</td></tr>",
+                CELL,
+                name,
+                CELL,
+                html(&value)
+            );
+        }
+        output.push_str("</table>\n");
+
+        let vulnerabilities = self.vulnerabilities.as_deref().unwrap_or_default();
+        if !vulnerabilities.is_empty() {
+            let _ = writeln!(
+                output,
+                "<h2>Vulnerabilities</h2>\n{}\n<tr><th {}>CVE</th><th {}>Title</th><th {}>CVSS</th></tr>",
+                TABLE, CELL, CELL, CELL
+            );
+            for vulnerability in vulnerabilities {
+                let score = vulnerability.max_cvss_score().map_or_else(
+                    || "-".to_string(),
+                    |score| format!("{:.1} ({})", score, CvssSeverity::from_score(score)),
+                );
+                let _ = writeln!(
+                    output,
+                    "<tr><td {}>{}</td><td {}>{}</td><td {}>{}</td></tr>",
+                    CELL,
+                    html(vulnerability.cve.as_deref().unwrap_or("-")),
+                    CELL,
+                    html(vulnerability.title.as_deref().unwrap_or("-")),
+                    CELL,
+                    score
+                );
+            }
+            output.push_str("</table>\n");
+        }
+
+        if let Some(tree) = &self.product_tree {
+            output.push_str("<h2>Products</h2>\n<ul>\n");
+            if let Some(branches) = &tree.branches {
+                html_branches(&mut output, branches);
+            }
+            for product in tree.full_product_names.iter().flatten() {
+                html_product(&mut output, product);
+            }
+            for relationship in tree.relationships.iter().flatten() {
+                html_product(&mut output, &relationship.full_product_name);
+            }
+            output.push_str("</ul>\n");
+        }
+
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+}
+
+#[cfg(feature = "html")]
+fn html_branches(output: &mut String, branches: &BranchesT) {
+    for branch in branches {
+        let category = serde_json::to_value(&branch.category)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let _ = write!(
+            output,
+            "<li>{} <span style=\"color: #666\">({})</span>",
+            html(&branch.name),
+            category
+        );
+        if let Some(product) = &branch.product {
+            output.push_str("\n<ul>\n");
+            html_product(output, product);
+            output.push_str("</ul>\n");
+        }
+        if let Some(children) = &branch.branches {
+            output.push_str("\n<ul>\n");
+            html_branches(output, children);
+            output.push_str("</ul>\n");
+        }
+        output.push_str("</li>\n");
+    }
+}
+
+#[cfg(feature = "html")]
+fn html_product(output: &mut String, product: &FullProductName) {
+    let _ = writeln!(
+        output,
+        "<li>{} <code>{}</code></li>",
+        html(&product.name),
+        html(&product.product_id.0)
+    );
+}
+
+/// Escapes text for HTML element content and quoted attribute values
+#[cfg(feature = "html")]
+fn html(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+/// Identifies the vulnerability by its CVE, first ID or title
+fn label(vulnerability: &Vulnerability) -> String {
+    vulnerability.key().unwrap_or_else(|| "-".to_string())
+}
+
+/// Escapes text for a single line table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn status_name(status: &Status) -> &'static str {
+    match status {
+        Status::Draft => "Draft",
+        Status::Final => "Final",
+        Status::Interim => "Interim",
+    }
+}
+
+fn remediation_name(category: &RemediationCategory) -> &'static str {
+    match category {
+        RemediationCategory::Mitigation => "Mitigation",
+        RemediationCategory::NoFixPlanned => "No fix planned",
+        RemediationCategory::NoneAvailable => "None available",
+        RemediationCategory::VendorFix => "Vendor fix",
+        RemediationCategory::Workaround => "Workaround",
+        #[cfg(feature = "csaf_2_1")]
+        RemediationCategory::FixPlanned => "Fix planned",
+        #[cfg(feature = "csaf_2_1")]
+        RemediationCategory::OptionalPatch => "Optional patch",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::fixtures::example;
+
+    #[test]
+    fn markdown_summary_sections() {
+        let mut csaf = example();
+        let summary = csaf.to_markdown_summary();
+        assert!(summary.contains("**Tracking ID:** cisco-sa-20180328-smi2"));
+        assert!(summary.contains("### Affected products"));
+        assert!(summary.contains("| CVE-2018-0171 | Cisco IOS and IOS XE Software Smart Install Remote Code Execution Vulnerability | 9.8 |"));
+
+        csaf.document.title = "A | B".to_string();
+        csaf.vulnerabilities = None;
+        let summary = csaf.to_markdown_summary();
+        assert!(summary.starts_with("## A \\| B\n"));
+        assert!(!summary.contains("### Vulnerabilities"));
+        assert!(!summary.contains("### Remediations"));
+    }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn html_report_escapes_and_nests() {
+        let mut csaf = example();
+        csaf.document.title = "<script>alert(1)</script>".to_string();
+        let report = csaf.to_html_report();
+        assert!(report.contains("<h1>&lt;script&gt;alert(1)&lt;/script&gt;</h1>"));
+        assert!(!report.contains("<script>"));
+        assert!(report.contains(">9.8 (Critical)</td>"));
+        assert!(report.contains("(vendor)</span>\n<ul>"));
+        assert!(report.ends_with("</html>\n"));
+    }
+}
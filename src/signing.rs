@@ -0,0 +1,159 @@
+//! Signing documents with a detached [JWS](https://www.rfc-editor.org/rfc/rfc7515), enabled by the `signing` feature
+//!
+//! Signatures use ES256 (ECDSA over P-256 with SHA-256) and cover the compact JSON serialization of the document, as
+//! produced by [Csaf::to_compact_json]. The payload is left out of the JWS as described in
+//! [Appendix F](https://www.rfc-editor.org/rfc/rfc7515#appendix-F), so it takes the form `header..signature`.
+//!
+//! ```
+//! # use csaf::Csaf;
+//! use p256::ecdsa::{SigningKey, VerifyingKey};
+//!
+//! # let mut json: serde_json::Value = serde_json::from_str(include_str!("../tests/CVE-2018-0171-modified.json")).unwrap();
+//! # json["vulnerabilities"][0].as_object_mut().unwrap().remove("id");
+//! let csaf: Csaf = serde_json::from_value(json).unwrap();
+//! let key = SigningKey::from_slice(&[7; 32]).unwrap();
+//! let signed = csaf.sign(&key);
+//! assert_eq!(signed.verify(&VerifyingKey::from(&key)).unwrap(), &csaf);
+//! ```
+
+use std::fmt::Display;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use p256::ecdsa::{
+    signature::{Signer, Verifier},
+    Signature,
+};
+
+use crate::Csaf;
+
+/// The protected JWS header, identifying the algorithm
+const HEADER: &str = r#"{"alg":"ES256"}"#;
+
+/// A document together with its detached JWS signature
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedCsaf {
+    pub csaf: Csaf,
+    /// JWS compact serialization with an empty payload, `header..signature`
+    pub signature: String,
+}
+
+/// Failure to verify the signature of a [SignedCsaf]
+#[derive(Debug)]
+pub enum SignatureError {
+    /// The signature is not a detached JWS in compact serialization
+    Malformed,
+    /// The JWS header names an algorithm other than ES256
+    UnsupportedAlgorithm(String),
+    /// The signature does not match the document and key
+    Invalid(p256::ecdsa::Error),
+}
+
+impl Display for SignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "signature is not a detached JWS"),
+            Self::UnsupportedAlgorithm(algorithm) => {
+                write!(f, "unsupported signature algorithm {:?}", algorithm)
+            }
+            Self::Invalid(source) => write!(f, "signature does not match: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Invalid(source) => Some(source),
+            Self::Malformed | Self::UnsupportedAlgorithm(_) => None,
+        }
+    }
+}
+
+impl Csaf {
+    /// Signs the document with an ES256 key, such as a [p256::ecdsa::SigningKey]
+    pub fn sign(&self, key: &impl Signer<Signature>) -> SignedCsaf {
+        let header = URL_SAFE_NO_PAD.encode(HEADER);
+        let signature: Signature = key.sign(signing_input(&header, self).as_bytes());
+        SignedCsaf {
+            csaf: self.clone(),
+            signature: format!(
+                "{}..{}",
+                header,
+                URL_SAFE_NO_PAD.encode(signature.to_bytes())
+            ),
+        }
+    }
+}
+
+impl SignedCsaf {
+    /// Checks the signature against the document with the matching public key, such as a
+    /// [p256::ecdsa::VerifyingKey], returning the document if it is valid
+    pub fn verify(&self, key: &impl Verifier<Signature>) -> Result<&Csaf, SignatureError> {
+        let (header, signature) = match self.signature.split_once("..") {
+            Some(parts) => parts,
+            None => return Err(SignatureError::Malformed),
+        };
+
+        let decoded_header = URL_SAFE_NO_PAD
+            .decode(header)
+            .map_err(|_| SignatureError::Malformed)?;
+        let header_json: serde_json::Value =
+            serde_json::from_slice(&decoded_header).map_err(|_| SignatureError::Malformed)?;
+        match header_json.get("alg").and_then(serde_json::Value::as_str) {
+            Some("ES256") => {}
+            Some(algorithm) => {
+                return Err(SignatureError::UnsupportedAlgorithm(algorithm.to_string()))
+            }
+            None => return Err(SignatureError::Malformed),
+        }
+
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| SignatureError::Malformed)?;
+        let signature = Signature::from_slice(&signature).map_err(|_| SignatureError::Malformed)?;
+        key.verify(signing_input(header, &self.csaf).as_bytes(), &signature)
+            .map_err(SignatureError::Invalid)?;
+        Ok(&self.csaf)
+    }
+}
+
+/// The JWS signing input, `header.payload`, for the encoded header and document
+fn signing_input(header: &str, csaf: &Csaf) -> String {
+    let payload = csaf
+        .to_compact_json()
+        .expect("Csaf always serializes to JSON");
+    format!("{}.{}", header, URL_SAFE_NO_PAD.encode(payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use p256::ecdsa::{SigningKey, VerifyingKey};
+
+    use super::*;
+
+    #[test]
+    fn tampered_documents_fail_verification() {
+        let csaf = crate::tests::fixtures::example();
+        let key = SigningKey::from_slice(&[7; 32]).unwrap();
+        let mut signed = csaf.sign(&key);
+        assert!(signed.signature.starts_with("eyJhbGciOiJFUzI1NiJ9.."));
+
+        let other_key = SigningKey::from_slice(&[8; 32]).unwrap();
+        assert!(matches!(
+            signed.verify(&VerifyingKey::from(&other_key)),
+            Err(SignatureError::Invalid(_))
+        ));
+
+        signed.csaf.document.title.push('!');
+        assert!(matches!(
+            signed.verify(&VerifyingKey::from(&key)),
+            Err(SignatureError::Invalid(_))
+        ));
+
+        signed.signature = "not a jws".to_string();
+        assert!(matches!(
+            signed.verify(&VerifyingKey::from(&key)),
+            Err(SignatureError::Malformed)
+        ));
+    }
+}
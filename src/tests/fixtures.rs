@@ -0,0 +1,23 @@
+//! The example documents shared by unit tests
+
+use crate::Csaf;
+
+/// tests/CVE-2018-0171-modified.json, which has the single vulnerability `id` of earlier drafts. That property is
+/// removed when the `strict` feature is enabled, as it would be rejected as an unknown field.
+pub(crate) fn example_json() -> String {
+    let json = include_str!("../../tests/CVE-2018-0171-modified.json");
+    if cfg!(feature = "strict") {
+        let mut value: serde_json::Value = serde_json::from_str(json).unwrap();
+        for vulnerability in value["vulnerabilities"].as_array_mut().unwrap() {
+            vulnerability.as_object_mut().unwrap().remove("id");
+        }
+        serde_json::to_string_pretty(&value).unwrap()
+    } else {
+        json.to_string()
+    }
+}
+
+/// [example_json], deserialized
+pub(crate) fn example() -> Csaf {
+    serde_json::from_str(&example_json()).unwrap()
+}
@@ -0,0 +1,375 @@
+//! Property tests that arbitrary documents survive serializing to JSON and back unchanged
+
+use chrono::{DateTime, TimeZone, Utc};
+use proptest::{option, prelude::*, sample::select};
+use url::Url;
+
+use crate::{
+    definitions::{
+        Branch, BranchCategory, BranchesT, FullProductName, Note, NoteCategory, ProductIdT,
+        ProductIdentificationHelper, Reference, ReferenceCategory,
+    },
+    document::{
+        Category, CsafVersion, Distribution, Document, Publisher, PublisherCategory, Revision,
+        Status, Tlp, TlpLabel, Tracking,
+    },
+    product_tree::ProductTree,
+    vulnerability::{
+        ProductStatus, Remediation, RemediationCategory, Remediations, Score, Vulnerability,
+    },
+    Csaf,
+};
+
+fn text() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9][a-zA-Z0-9 ]{0,15}"
+}
+
+fn date() -> impl Strategy<Value = DateTime<Utc>> {
+    (0i64..4_000_000_000, 0u32..1_000_000_000)
+        .prop_map(|(seconds, nanos)| Utc.timestamp_opt(seconds, nanos).unwrap())
+}
+
+fn url() -> impl Strategy<Value = Url> {
+    select(
+        &[
+            "https://example.com/",
+            "https://example.com/advisories/csaf-001.json",
+            "http://example.org/path?query=value#fragment",
+        ][..],
+    )
+    .prop_map(|url| url.parse().unwrap())
+}
+
+fn product_id() -> impl Strategy<Value = ProductIdT> {
+    "CSAFPID-[0-9]{4}".prop_map(ProductIdT)
+}
+
+fn category() -> impl Strategy<Value = Category> {
+    prop_oneof![
+        Just(Category::Base),
+        Just(Category::SecurityIncidentResponse),
+        Just(Category::InformationalAdvisory),
+        Just(Category::SecurityAdvisory),
+        Just(Category::Vex),
+        "x_[a-z_]{1,12}".prop_map(Category::Other),
+    ]
+}
+
+fn publisher() -> impl Strategy<Value = Publisher> {
+    (
+        select(
+            &[
+                PublisherCategory::Coordinator,
+                PublisherCategory::Discoverer,
+                PublisherCategory::Other,
+                PublisherCategory::Translator,
+                PublisherCategory::User,
+                PublisherCategory::Vendor,
+            ][..],
+        ),
+        text(),
+        url(),
+        option::of(text()),
+        option::of(text()),
+    )
+        .prop_map(
+            |(category, name, namespace, contact_details, issuing_authority)| Publisher {
+                category,
+                name,
+                namespace,
+                contact_details,
+                issuing_authority,
+            },
+        )
+}
+
+fn tracking() -> impl Strategy<Value = Tracking> {
+    let revision = (date(), option::of(text()), "[0-9]{1,3}", text()).prop_map(
+        |(date, legacy_version, number, summary)| Revision {
+            date,
+            legacy_version,
+            number,
+            summary,
+        },
+    );
+    (
+        date(),
+        "[A-Z]{2,6}-[0-9]{1,4}",
+        date(),
+        prop::collection::vec(revision, 1..4),
+        select(&[Status::Draft, Status::Final, Status::Interim][..]),
+        "[0-9]{1,3}",
+        option::of(prop::collection::vec(text(), 1..3)),
+    )
+        .prop_map(
+            |(
+                current_release_date,
+                id,
+                initial_release_date,
+                revision_history,
+                status,
+                version,
+                aliases,
+            )| Tracking {
+                current_release_date,
+                id,
+                initial_release_date,
+                revision_history,
+                status,
+                version,
+                aliases,
+                generator: None,
+            },
+        )
+}
+
+fn distribution() -> impl Strategy<Value = Distribution> {
+    let tlp = (
+        select(
+            &[
+                TlpLabel::AMBER,
+                TlpLabel::GREEN,
+                TlpLabel::RED,
+                TlpLabel::WHITE,
+                TlpLabel::Clear,
+                TlpLabel::AmberStrict,
+            ][..],
+        ),
+        option::of(url()),
+    )
+        .prop_map(|(label, url)| Tlp { label, url });
+    (option::of(text()), option::of(tlp)).prop_map(|(text, tlp)| Distribution { text, tlp })
+}
+
+fn note() -> impl Strategy<Value = Note> {
+    (
+        select(
+            &[
+                NoteCategory::Description,
+                NoteCategory::Details,
+                NoteCategory::Faq,
+                NoteCategory::General,
+                NoteCategory::LegalDisclaimer,
+                NoteCategory::Other,
+                NoteCategory::Summary,
+            ][..],
+        ),
+        text(),
+        option::of(text()),
+        option::of(text()),
+    )
+        .prop_map(|(category, text, audience, title)| Note {
+            category,
+            text,
+            audience,
+            title,
+        })
+}
+
+fn reference() -> impl Strategy<Value = Reference> {
+    (
+        url(),
+        text(),
+        option::of(select(
+            &[ReferenceCategory::External, ReferenceCategory::RefSelf][..],
+        )),
+    )
+        .prop_map(|(url, summary, category)| Reference {
+            url,
+            summary,
+            category,
+        })
+}
+
+fn document() -> impl Strategy<Value = Document> {
+    (
+        category(),
+        publisher(),
+        text(),
+        tracking(),
+        option::of(distribution()),
+        option::of(select(&["en", "de-DE", "zh-Hant-TW"][..])),
+        option::of(prop::collection::vec(note(), 1..3)),
+        option::of(prop::collection::vec(reference(), 1..3)),
+    )
+        .prop_map(
+            |(category, publisher, title, tracking, distribution, lang, notes, references)| {
+                Document {
+                    category,
+                    publisher,
+                    title,
+                    tracking,
+                    csaf_version: CsafVersion::TwoDotZero,
+                    acknowledgments: None,
+                    aggregate_severity: None,
+                    distribution,
+                    lang: lang.map(|lang| lang.parse().unwrap()),
+                    notes,
+                    references,
+                    source_lang: None,
+                    license_expression: None,
+                }
+            },
+        )
+}
+
+fn full_product_name() -> impl Strategy<Value = FullProductName> {
+    let helper = (
+        option::of(select(
+            &[
+                "cpe:2.3:a:vendor:product:1.0:*:*:*:*:*:*:*",
+                "cpe:/a:redhat:openshift:4.11::el8",
+            ][..],
+        )),
+        option::of(select(
+            &["pkg:cargo/csaf@0.5.0", "pkg:npm/%40angular/core@16.0.0"][..],
+        )),
+    )
+        .prop_map(|(cpe, purl)| ProductIdentificationHelper {
+            cpe: cpe.map(|cpe| cpe.parse().unwrap()),
+            purl: purl.map(|purl| purl.parse().unwrap()),
+            ..Default::default()
+        });
+    (text(), product_id(), option::of(helper)).prop_map(
+        |(name, product_id, product_identification_helper)| FullProductName {
+            name,
+            product_id,
+            product_identification_helper,
+        },
+    )
+}
+
+fn branches() -> impl Strategy<Value = BranchesT> {
+    let leaf = (text(), full_product_name()).prop_map(|(name, product)| Branch {
+        name,
+        category: BranchCategory::ProductVersion,
+        product: Some(product),
+        branches: None,
+    });
+    let branch = leaf.prop_recursive(3, 16, 3, |inner| {
+        (
+            text(),
+            select(&[BranchCategory::Vendor, BranchCategory::ProductName][..]),
+            prop::collection::vec(inner, 1..3),
+        )
+            .prop_map(|(name, category, children)| Branch {
+                name,
+                category,
+                product: None,
+                branches: Some(BranchesT(children)),
+            })
+    });
+    prop::collection::vec(branch, 1..3).prop_map(BranchesT)
+}
+
+fn product_tree() -> impl Strategy<Value = ProductTree> {
+    (
+        option::of(branches()),
+        option::of(prop::collection::vec(full_product_name(), 1..3)),
+    )
+        .prop_map(|(branches, full_product_names)| ProductTree {
+            branches,
+            full_product_names,
+            product_groups: None,
+            relationships: None,
+        })
+}
+
+fn score() -> impl Strategy<Value = Score> {
+    (
+        prop::collection::vec(product_id(), 1..3),
+        option::of(select(
+            &[
+                "AV:N/AC:L/Au:N/C:P/I:N/A:N",
+                "AV:L/AC:H/Au:S/C:C/I:C/A:C",
+                "AV:N/AC:L/Au:N/C:N/I:N/A:C/E:F/RL:OF/RC:C/CDP:H/TD:H/CR:M/IR:M/AR:H",
+            ][..],
+        )),
+        option::of(select(
+            &[
+                "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H",
+                "CVSS:3.0/AV:L/AC:H/PR:L/UI:R/S:C/C:L/I:N/A:N",
+            ][..],
+        )),
+        option::of(select(
+            &["CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"][..],
+        )),
+    )
+        .prop_map(|(products, cvss_v2, cvss_v3, cvss_v4)| Score {
+            products,
+            cvss_v2: cvss_v2.map(|vector| vector.parse().unwrap()),
+            cvss_v3: cvss_v3.map(|vector| vector.parse().unwrap()),
+            cvss_v4: cvss_v4.map(|vector| vector.parse().unwrap()),
+        })
+}
+
+fn vulnerability() -> impl Strategy<Value = Vulnerability> {
+    let ids = || option::of(prop::collection::vec(product_id(), 1..3));
+    let product_status =
+        (ids(), ids(), ids()).prop_map(|(known_affected, fixed, under_investigation)| {
+            ProductStatus {
+                known_affected,
+                fixed,
+                under_investigation,
+                ..Default::default()
+            }
+        });
+    let remediation =
+        (text(), date(), ids(), option::of(url())).prop_map(|(details, date, product_ids, url)| {
+            Remediation {
+                category: RemediationCategory::VendorFix,
+                details,
+                date: Some(date),
+                entitlements: None,
+                group_ids: None,
+                product_ids,
+                restart_required: None,
+                url,
+            }
+        });
+    (
+        option::of("CVE-20[0-9]{2}-[0-9]{4,5}"),
+        option::of(text()),
+        option::of(date()),
+        option::of(prop::collection::vec(note(), 1..3)),
+        option::of(product_status),
+        option::of(prop::collection::vec(remediation, 1..3)),
+        option::of(prop::collection::vec(score(), 1..3)),
+    )
+        .prop_map(
+            |(cve, title, release_date, notes, product_status, remediations, scores)| {
+                Vulnerability {
+                    cve,
+                    title,
+                    release_date,
+                    notes,
+                    product_status,
+                    remediations: remediations.map(Remediations),
+                    scores,
+                    ..Default::default()
+                }
+            },
+        )
+}
+
+fn csaf() -> impl Strategy<Value = Csaf> {
+    (
+        document(),
+        option::of(product_tree()),
+        option::of(prop::collection::vec(vulnerability(), 1..3)),
+    )
+        .prop_map(|(document, product_tree, vulnerabilities)| Csaf {
+            document,
+            product_tree,
+            vulnerabilities,
+        })
+}
+
+proptest! {
+    #[test]
+    fn csaf_roundtrips_through_json(csaf in csaf()) {
+        let json = serde_json::to_string(&csaf).unwrap();
+        let parsed: Csaf = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(parsed, csaf);
+    }
+}
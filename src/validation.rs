@@ -0,0 +1,839 @@
+//! Validation of a [Csaf] document against the [mandatory tests](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#61-mandatory-tests)
+//! of the spec.
+//!
+//! Deserialization is deliberately lenient, so a document that parsed successfully is not necessarily valid CSAF. Use
+//! [Csaf::validate] to check a document before publishing it.
+
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    definitions::{ProductGroupIdT, ProductIdT, VersionT},
+    document::Tracking,
+    product_tree::{ProductTree, Relationship},
+    vulnerability::{FlagLabel, ProductStatus, ProductStatusKind},
+    Csaf,
+};
+
+/// The pattern the spec mandates for `document.tracking.id`
+pub const TRACKING_ID_PATTERN: &str = r"^[\S]{1,}$";
+
+/// A stricter pattern for `document.tracking.id` that avoids lowercase and punctuation, see [ValidationConfig]
+pub const STRICT_TRACKING_ID_PATTERN: &str = r"^[A-Z][A-Z0-9_\-]{0,199}$";
+
+/// Optional checks beyond the mandatory tests, used by [Csaf::validate_with]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationConfig {
+    /// Also require `document.tracking.id` to match [STRICT_TRACKING_ID_PATTERN]
+    pub strict_tracking_id: bool,
+    /// Also report [ValidationError::ScoreProductNotInAffectedStatus]. Off by default, as publishers such as Red Hat
+    /// also score their fixed and `known_not_affected` products, and documents like that pass the reference
+    /// validators.
+    pub score_products_affected: bool,
+}
+
+/// A single violation of a CSAF spec constraint, as reported by [Csaf::validate]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// `document.tracking.id` does not match `expected_pattern`
+    InvalidTrackingId {
+        found: String,
+        expected_pattern: &'static str,
+    },
+    /// `document.tracking.revision_history` has no entries
+    EmptyRevisionHistory,
+    /// A `revision_history` entry does not have a greater version than the entry before it, including repeating its
+    /// version
+    RevisionHistoryNotMonotonic { previous: VersionT, next: VersionT },
+    /// A `revision_history` entry is dated earlier than the entry before it.
+    /// [6.1.14](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#6114-sorted-revision-history)
+    RevisionHistoryDateNotMonotonic { previous: VersionT, next: VersionT },
+    /// `document.tracking.version` does not match the number of the latest revision.
+    /// [6.1.16](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#6116-latest-document-version)
+    TrackingVersionMismatch { version: VersionT, latest: VersionT },
+    /// `document.tracking.current_release_date` is earlier than `document.tracking.initial_release_date`
+    CurrentReleaseBeforeInitialRelease {
+        initial_release_date: DateTime<Utc>,
+        current_release_date: DateTime<Utc>,
+    },
+    /// A product ID is referenced but never defined in the `product_tree`.
+    /// [6.1.1](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#611-missing-definition-of-product-id)
+    UndefinedProductId { product_id: ProductIdT },
+    /// A product defined by a relationship refers back to itself through the products it is defined from. The
+    /// products are in the order the references are followed, starting from the first one found in the cycle.
+    /// [6.1.3](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#613-circular-definition-of-product-id)
+    CircularRelationship { involved_products: Vec<ProductIdT> },
+    /// A product ID is defined more than once in the `product_tree`.
+    /// [6.1.2](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#612-multiple-definition-of-product-id)
+    DuplicateProductId { product_id: ProductIdT },
+    /// A product group ID is referenced but never defined in the `product_tree`.
+    /// [6.1.4](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#614-missing-definition-of-product-group-id)
+    UndefinedProductGroupId { group_id: ProductGroupIdT },
+    /// A [ProductGroup](crate::product_tree::ProductGroup) lists a product ID that is not defined in the
+    /// `product_tree`
+    UndefinedGroupMember {
+        group_id: ProductGroupIdT,
+        missing_product_id: ProductIdT,
+    },
+    /// An [Acknowledgment](crate::definitions::Acknowledgment) has none of its properties set
+    EmptyAcknowledgment,
+    /// A [Note](crate::definitions::Note) has an empty `text`, or an `audience` or `title` that is set but empty.
+    /// Whitespace alone counts as empty.
+    EmptyNoteField { field: &'static str },
+    /// `document.distribution` has neither `text` nor `tlp` set
+    EmptyDistribution,
+    /// `product_tree` is present but has none of its properties set, which the schema forbids
+    EmptyProductTree,
+    /// A vulnerability's `product_status` is present but has none of its properties set, which the schema forbids
+    EmptyProductStatus { vulnerability: Option<String> },
+    /// A [HashValue](crate::definitions::HashValue) `algorithm` is not one of
+    /// [HASH_ALGORITHMS](crate::definitions::HASH_ALGORITHMS)
+    UnknownHashAlgorithm { algorithm: String },
+    /// A [HashValue](crate::definitions::HashValue) `value` does not match `^[0-9a-fA-F]{32,}$`
+    InvalidHashValue { value: String },
+    /// A product is listed in more than one of the affected, not affected, fixed and under investigation groups of a
+    /// vulnerability's `product_status`, listing each status it appears under. `recommended` is not a group.
+    /// [6.1.6](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#616-contradicting-product-status)
+    DuplicateProductInStatus {
+        vulnerability: Option<String>,
+        product_id: ProductIdT,
+        statuses: Vec<ProductStatusKind>,
+    },
+    /// A product in a vulnerability's `flags` does not have the status required by the flag's label, see
+    /// [FlagLabel::required_status]
+    FlagInconsistentWithStatus {
+        vulnerability: Option<String>,
+        product_id: ProductIdT,
+        label: FlagLabel,
+    },
+    /// A product in a vulnerability's `scores` is not listed as affected or `under_investigation` in that
+    /// vulnerability's `product_status`, only checked with [ValidationConfig::score_products_affected]
+    ScoreProductNotInAffectedStatus {
+        vulnerability: Option<String>,
+        product_id: ProductIdT,
+    },
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidTrackingId {
+                found,
+                expected_pattern,
+            } => {
+                write!(
+                    f,
+                    "tracking id {:?} does not match {}",
+                    found, expected_pattern
+                )
+            }
+            Self::EmptyRevisionHistory => write!(f, "revision history must not be empty"),
+            Self::RevisionHistoryNotMonotonic { previous, next } => write!(
+                f,
+                "revision {} follows revision {} but is not a greater version",
+                next, previous
+            ),
+            Self::RevisionHistoryDateNotMonotonic { previous, next } => write!(
+                f,
+                "revision {} follows revision {} but is dated earlier",
+                next, previous
+            ),
+            Self::TrackingVersionMismatch { version, latest } => write!(
+                f,
+                "tracking version {} does not match latest revision {}",
+                version, latest
+            ),
+            Self::CurrentReleaseBeforeInitialRelease {
+                initial_release_date,
+                current_release_date,
+            } => write!(
+                f,
+                "current release date {} is before initial release date {}",
+                current_release_date, initial_release_date
+            ),
+            Self::CircularRelationship { involved_products } => write!(
+                f,
+                "relationships define products in a circle: {}",
+                involved_products
+                    .iter()
+                    .map(|id| id.0.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            ),
+            Self::UndefinedProductId { product_id } => {
+                write!(
+                    f,
+                    "product id {} is referenced but not defined",
+                    product_id.0
+                )
+            }
+            Self::DuplicateProductId { product_id } => {
+                write!(f, "product id {} is defined more than once", product_id.0)
+            }
+            Self::UndefinedProductGroupId { group_id } => {
+                write!(
+                    f,
+                    "product group id {} is referenced but not defined",
+                    group_id
+                )
+            }
+            Self::UndefinedGroupMember {
+                group_id,
+                missing_product_id,
+            } => {
+                write!(
+                    f,
+                    "product group {} contains product id {} which is not defined",
+                    group_id, missing_product_id.0
+                )
+            }
+            Self::EmptyAcknowledgment => {
+                write!(f, "acknowledgment must have at least one property")
+            }
+            Self::EmptyNoteField { field } => {
+                write!(f, "note {} must not be empty", field)
+            }
+            Self::EmptyDistribution => {
+                write!(f, "distribution must have at least one of text and tlp")
+            }
+            Self::EmptyProductTree => {
+                write!(f, "product tree must have at least one property")
+            }
+            Self::EmptyProductStatus { vulnerability } => write!(
+                f,
+                "product status of vulnerability {} must have at least one property",
+                vulnerability.as_deref().unwrap_or("-")
+            ),
+            Self::UnknownHashAlgorithm { algorithm } => {
+                write!(f, "unknown hash algorithm {:?}", algorithm)
+            }
+            Self::InvalidHashValue { value } => {
+                write!(
+                    f,
+                    "hash value {:?} must be at least 32 hexadecimal digits",
+                    value
+                )
+            }
+            Self::DuplicateProductInStatus {
+                vulnerability,
+                product_id,
+                statuses,
+            } => write!(
+                f,
+                "product id {} has contradicting statuses {} in vulnerability {}",
+                product_id.0,
+                statuses
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                vulnerability.as_deref().unwrap_or("-")
+            ),
+            Self::FlagInconsistentWithStatus {
+                vulnerability,
+                product_id,
+                label,
+            } => write!(
+                f,
+                "product id {} is flagged {:?} in vulnerability {} but not {}",
+                product_id.0,
+                label,
+                vulnerability.as_deref().unwrap_or("-"),
+                label.required_status()
+            ),
+            Self::ScoreProductNotInAffectedStatus {
+                vulnerability,
+                product_id,
+            } => write!(
+                f,
+                "product id {} is scored in vulnerability {} but not affected or under investigation",
+                product_id.0,
+                vulnerability.as_deref().unwrap_or("-")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+pub(crate) fn validate(csaf: &Csaf, config: &ValidationConfig) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    validate_tracking(&csaf.document.tracking, config, &mut errors);
+    if let Some(distribution) = &csaf.document.distribution {
+        if let Err(error) = distribution.validate() {
+            errors.push(error);
+        }
+    }
+    validate_product_references(csaf, &mut errors);
+    if let Some(tree) = &csaf.product_tree {
+        if *tree == ProductTree::default() {
+            errors.push(ValidationError::EmptyProductTree);
+        }
+        if let Err(circular) = Relationship::validate_no_circular_references(tree) {
+            errors.extend(circular);
+        }
+    }
+    validate_acknowledgments(csaf, &mut errors);
+    validate_notes(csaf, &mut errors);
+    validate_hashes(csaf, &mut errors);
+    validate_product_status(csaf, &mut errors);
+    validate_flags(csaf, &mut errors);
+    if config.score_products_affected {
+        validate_score_products(csaf, &mut errors);
+    }
+
+    errors
+}
+
+fn validate_tracking(
+    tracking: &Tracking,
+    config: &ValidationConfig,
+    errors: &mut Vec<ValidationError>,
+) {
+    let id_format = if config.strict_tracking_id {
+        tracking.validate_id_format_strict()
+    } else {
+        tracking.validate_id_format()
+    };
+    if let Err(error) = id_format {
+        errors.push(error);
+    }
+
+    if tracking.current_release_date < tracking.initial_release_date {
+        errors.push(ValidationError::CurrentReleaseBeforeInitialRelease {
+            initial_release_date: tracking.initial_release_date,
+            current_release_date: tracking.current_release_date,
+        });
+    }
+
+    if tracking.revision_history.is_empty() {
+        errors.push(ValidationError::EmptyRevisionHistory);
+        return;
+    }
+
+    for pair in tracking.revision_history.windows(2) {
+        let (previous, next) = (&pair[0], &pair[1]);
+        if compare_versions(&previous.number, &next.number) != Some(Ordering::Less) {
+            errors.push(ValidationError::RevisionHistoryNotMonotonic {
+                previous: previous.number.clone(),
+                next: next.number.clone(),
+            });
+        }
+        if next.date < previous.date {
+            errors.push(ValidationError::RevisionHistoryDateNotMonotonic {
+                previous: previous.number.clone(),
+                next: next.number.clone(),
+            });
+        }
+    }
+
+    let latest = tracking.latest_revision().expect("Checked non-empty above");
+    if compare_versions(&tracking.version, &latest.number) != Some(Ordering::Equal) {
+        errors.push(ValidationError::TrackingVersionMismatch {
+            version: tracking.version.clone(),
+            latest: latest.number.clone(),
+        });
+    }
+}
+
+/// Orders two [VersionT]s, which must either both use integer versioning or both use semantic versioning
+pub(crate) fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => Some(a.cmp(&b)),
+        (Err(_), Err(_)) => {
+            let a = semver::Version::parse(a).ok()?;
+            let b = semver::Version::parse(b).ok()?;
+            // Build metadata is ignored when determining precedence
+            Some(a.cmp_precedence(&b))
+        }
+        _ => None,
+    }
+}
+
+fn validate_product_references(csaf: &Csaf, errors: &mut Vec<ValidationError>) {
+    let mut defined = HashSet::new();
+    let mut groups = HashSet::new();
+
+    for id in csaf.all_product_ids() {
+        if !defined.insert(id.0.as_str()) {
+            errors.push(ValidationError::DuplicateProductId {
+                product_id: id.clone(),
+            });
+        }
+    }
+
+    if let Some(tree) = &csaf.product_tree {
+        for group in tree.product_groups.iter().flatten() {
+            groups.insert(group.group_id.as_str());
+        }
+    }
+
+    let mut references: Vec<&ProductIdT> = Vec::new();
+    let mut group_references: Vec<&ProductGroupIdT> = Vec::new();
+
+    if let Some(tree) = &csaf.product_tree {
+        for group in tree.product_groups.iter().flatten() {
+            if let Err(group_errors) = group.validate_members_defined(&defined) {
+                errors.extend(group_errors);
+            }
+        }
+        for relationship in tree.relationships.iter().flatten() {
+            references.push(&relationship.product_reference);
+            references.push(&relationship.relates_to_product_reference);
+        }
+    }
+
+    references.extend(csaf.all_referenced_product_ids());
+
+    for vulnerability in csaf.vulnerabilities.iter().flatten() {
+        for remediation in vulnerability.remediations.iter().flatten() {
+            group_references.extend(remediation.group_ids.iter().flatten());
+        }
+        for flag in vulnerability.flags.iter().flatten() {
+            group_references.extend(flag.group_ids.iter().flatten());
+        }
+        for threat in vulnerability.threats.iter().flatten() {
+            group_references.extend(threat.group_ids.iter().flatten());
+        }
+    }
+
+    let mut reported = HashSet::new();
+    for id in references {
+        if !defined.contains(id.0.as_str()) && reported.insert(id.0.as_str()) {
+            errors.push(ValidationError::UndefinedProductId {
+                product_id: id.clone(),
+            });
+        }
+    }
+
+    let mut reported = HashSet::new();
+    for id in group_references {
+        if !groups.contains(id.as_str()) && reported.insert(id.as_str()) {
+            errors.push(ValidationError::UndefinedProductGroupId {
+                group_id: id.clone(),
+            });
+        }
+    }
+}
+
+fn validate_acknowledgments(csaf: &Csaf, errors: &mut Vec<ValidationError>) {
+    let vulnerability_acknowledgments = csaf
+        .vulnerabilities
+        .iter()
+        .flatten()
+        .flat_map(|vulnerability| vulnerability.acknowledgments.iter().flatten());
+    for acknowledgment in csaf
+        .document
+        .acknowledgments
+        .iter()
+        .flatten()
+        .chain(vulnerability_acknowledgments)
+    {
+        if let Err(error) = acknowledgment.validate() {
+            errors.push(error);
+        }
+    }
+}
+
+fn validate_notes(csaf: &Csaf, errors: &mut Vec<ValidationError>) {
+    let vulnerability_notes = csaf
+        .vulnerabilities
+        .iter()
+        .flatten()
+        .flat_map(|vulnerability| vulnerability.notes.iter().flatten());
+    for note in csaf
+        .document
+        .notes
+        .iter()
+        .flatten()
+        .chain(vulnerability_notes)
+    {
+        if let Err(error) = note.validate() {
+            errors.push(error);
+        }
+    }
+}
+
+fn validate_hashes(csaf: &Csaf, errors: &mut Vec<ValidationError>) {
+    let products = csaf
+        .product_tree
+        .iter()
+        .flat_map(|tree| tree.defined_products());
+    for product in products {
+        let hashes = product
+            .product_identification_helper
+            .iter()
+            .flat_map(|helper| helper.hashes.iter().flatten())
+            .flat_map(|hashes| &hashes.file_hashes);
+        for hash in hashes {
+            if let Err(error) = hash.validate() {
+                errors.push(error);
+            }
+        }
+    }
+}
+
+fn validate_product_status(csaf: &Csaf, errors: &mut Vec<ValidationError>) {
+    /// The group of statuses a status belongs to, of which a product may only be in one
+    fn group(kind: ProductStatusKind) -> Option<u8> {
+        if kind.is_affected() {
+            Some(0)
+        } else if kind.is_not_affected() {
+            Some(1)
+        } else if kind.is_fixed() {
+            Some(2)
+        } else if kind.is_under_investigation() {
+            Some(3)
+        } else {
+            None
+        }
+    }
+
+    for vulnerability in csaf.vulnerabilities.iter().flatten() {
+        let status = match &vulnerability.product_status {
+            Some(status) => status,
+            None => continue,
+        };
+        if *status == ProductStatus::default() {
+            errors.push(ValidationError::EmptyProductStatus {
+                vulnerability: vulnerability.key(),
+            });
+        }
+
+        // Products in order of first appearance, so errors are reported in a stable order
+        let mut products: Vec<(&ProductIdT, Vec<ProductStatusKind>)> = Vec::new();
+        let mut indices = HashMap::new();
+        for (kind, product_id) in status.entries() {
+            if group(kind).is_none() {
+                continue;
+            }
+            let index = *indices.entry(product_id).or_insert_with(|| {
+                products.push((product_id, Vec::new()));
+                products.len() - 1
+            });
+            let kinds = &mut products[index].1;
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+
+        for (product_id, kinds) in products {
+            let groups: HashSet<_> = kinds.iter().copied().filter_map(group).collect();
+            if groups.len() > 1 {
+                errors.push(ValidationError::DuplicateProductInStatus {
+                    vulnerability: vulnerability.key(),
+                    product_id: product_id.clone(),
+                    statuses: kinds,
+                });
+            }
+        }
+    }
+}
+
+fn validate_flags(csaf: &Csaf, errors: &mut Vec<ValidationError>) {
+    let empty = ProductStatus::default();
+    for vulnerability in csaf.vulnerabilities.iter().flatten() {
+        let status = vulnerability.product_status.as_ref().unwrap_or(&empty);
+        for flag in vulnerability.flags.iter().flatten() {
+            for product_id in flag.inconsistent_products(status) {
+                errors.push(ValidationError::FlagInconsistentWithStatus {
+                    vulnerability: vulnerability.key(),
+                    product_id: product_id.clone(),
+                    label: flag.label.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn validate_score_products(csaf: &Csaf, errors: &mut Vec<ValidationError>) {
+    for vulnerability in csaf.vulnerabilities.iter().flatten() {
+        let product_ids = vulnerability
+            .scores
+            .iter()
+            .flatten()
+            .flat_map(|score| &score.products);
+        for product_id in product_ids {
+            let affected = vulnerability
+                .product_status
+                .as_ref()
+                .map_or(false, |status| {
+                    status.has_status(product_id, |kind| {
+                        kind.is_affected() || kind.is_under_investigation()
+                    })
+                });
+            if !affected {
+                errors.push(ValidationError::ScoreProductNotInAffectedStatus {
+                    vulnerability: vulnerability.key(),
+                    product_id: product_id.clone(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixtures::example;
+    use crate::vulnerability::Flag;
+
+    #[test]
+    fn example_documents_validate() {
+        for example in [
+            crate::tests::fixtures::example_json().as_str(),
+            include_str!("../tests/cvrf-rhba-2018-0489-modified.json"),
+            include_str!("../tests/rhba-2023_0564.json"),
+        ] {
+            let document: Csaf = serde_json::from_str(example).unwrap();
+            assert_eq!(document.validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn strict_tracking_id_is_opt_in() {
+        let document = example();
+        let strict = ValidationConfig {
+            strict_tracking_id: true,
+            ..Default::default()
+        };
+        assert_eq!(document.validate(), Ok(()));
+        assert_eq!(
+            document.validate_with(&strict),
+            Err(vec![ValidationError::InvalidTrackingId {
+                found: "cisco-sa-20180328-smi2".to_string(),
+                expected_pattern: STRICT_TRACKING_ID_PATTERN,
+            }])
+        );
+
+        let mut tracking = document.document.tracking;
+        tracking.id = "CISCO-SA-20180328-SMI2".to_string();
+        assert_eq!(tracking.validate_id_format_strict(), Ok(()));
+        tracking.id = String::new();
+        assert!(tracking.validate_id_format().is_err());
+    }
+
+    #[test]
+    fn reports_all_errors() {
+        let mut document = example();
+        document.document.tracking.id = "has whitespace".to_string();
+        document.document.tracking.revision_history.swap(0, 1);
+        document.document.tracking.initial_release_date = Utc::now();
+        document.vulnerabilities.as_mut().unwrap()[0]
+            .scores
+            .as_mut()
+            .unwrap()[0]
+            .products
+            .push(ProductIdT("CSAFPID-MISSING".to_string()));
+
+        let errors = document.validate().unwrap_err();
+        assert!(errors.contains(&ValidationError::InvalidTrackingId {
+            found: "has whitespace".to_string(),
+            expected_pattern: TRACKING_ID_PATTERN,
+        }));
+        assert!(
+            errors.contains(&ValidationError::RevisionHistoryNotMonotonic {
+                previous: "1.1.0".to_string(),
+                next: "1.0.0".to_string()
+            })
+        );
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            ValidationError::CurrentReleaseBeforeInitialRelease { .. }
+        )));
+        assert!(errors.contains(&ValidationError::UndefinedProductId {
+            product_id: ProductIdT("CSAFPID-MISSING".to_string())
+        }));
+    }
+
+    #[test]
+    fn empty_acknowledgment() {
+        let mut document = example();
+        document.vulnerabilities.as_mut().unwrap()[0].acknowledgments =
+            Some(vec![Default::default()]);
+        assert_eq!(
+            document.validate(),
+            Err(vec![ValidationError::EmptyAcknowledgment])
+        );
+    }
+
+    #[test]
+    fn undefined_group_members() {
+        let mut document = example();
+        let id = |id: &str| ProductIdT(id.to_string());
+        document.product_tree.as_mut().unwrap().product_groups =
+            Some(vec![crate::product_tree::ProductGroupBuilder::new(
+                "CSAFGID-0001",
+                id("CVRFPID-103559"),
+                id("CVRFPID-MISSING"),
+            )
+            .build()]);
+        assert_eq!(
+            document.validate(),
+            Err(vec![ValidationError::UndefinedGroupMember {
+                group_id: "CSAFGID-0001".to_string(),
+                missing_product_id: id("CVRFPID-MISSING"),
+            }])
+        );
+    }
+
+    #[test]
+    fn empty_product_tree() {
+        let mut document = example();
+        document.vulnerabilities = None;
+        document.product_tree = Some(ProductTree::default());
+        assert_eq!(
+            document.validate(),
+            Err(vec![ValidationError::EmptyProductTree])
+        );
+    }
+
+    #[test]
+    fn empty_product_status() {
+        let mut document = example();
+        document.vulnerabilities.as_mut().unwrap()[0].product_status =
+            Some(ProductStatus::default());
+        assert_eq!(
+            document.validate(),
+            Err(vec![ValidationError::EmptyProductStatus {
+                vulnerability: Some("CVE-2018-0171".to_string())
+            }])
+        );
+    }
+
+    #[test]
+    fn empty_revision_history() {
+        let mut document = example();
+        document.document.tracking.revision_history.clear();
+        assert_eq!(
+            document.validate(),
+            Err(vec![ValidationError::EmptyRevisionHistory])
+        );
+    }
+
+    #[test]
+    fn revision_history_must_increase() {
+        let mut document = example();
+        let history = &mut document.document.tracking.revision_history;
+        history[1].number = history[0].number.clone();
+        history[1].date = history[0].date - chrono::Duration::days(1);
+        let version = history[1].number.clone();
+        document.document.tracking.version = version.clone();
+
+        let errors = document.validate().unwrap_err();
+        assert!(
+            errors.contains(&ValidationError::RevisionHistoryNotMonotonic {
+                previous: version.clone(),
+                next: version.clone(),
+            })
+        );
+        assert!(
+            errors.contains(&ValidationError::RevisionHistoryDateNotMonotonic {
+                previous: version.clone(),
+                next: version,
+            })
+        );
+    }
+
+    #[test]
+    fn compares_integer_and_semantic_versions() {
+        assert_eq!(compare_versions("2", "10"), Some(Ordering::Less));
+        assert_eq!(compare_versions("1.10.0", "1.9.0"), Some(Ordering::Greater));
+        assert_eq!(
+            compare_versions("1.0.0+build", "1.0.0"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(compare_versions("1", "1.0.0"), None);
+    }
+
+    #[test]
+    fn score_products_must_be_affected() {
+        let mut document = example();
+        let vulnerability = &mut document.vulnerabilities.as_mut().unwrap()[0];
+        let product_id = vulnerability.scores.as_ref().unwrap()[0].products[0].clone();
+        vulnerability.product_status = Some(ProductStatus {
+            known_not_affected: Some(vec![product_id.clone()]),
+            ..Default::default()
+        });
+
+        assert_eq!(document.validate(), Ok(()));
+        let config = ValidationConfig {
+            score_products_affected: true,
+            ..Default::default()
+        };
+        let errors = document.validate_with(&config).unwrap_err();
+        assert!(
+            errors.contains(&ValidationError::ScoreProductNotInAffectedStatus {
+                vulnerability: Some("CVE-2018-0171".to_string()),
+                product_id,
+            })
+        );
+
+        let red_hat: Csaf =
+            serde_json::from_str(include_str!("../tests/rhba-2023_0564.json")).unwrap();
+        assert_eq!(red_hat.validate(), Ok(()));
+        assert!(red_hat.validate_with(&config).is_err());
+    }
+
+    #[test]
+    fn contradicting_product_status() {
+        let mut document = example();
+        let status = document.vulnerabilities.as_mut().unwrap()[0]
+            .product_status
+            .as_mut()
+            .unwrap();
+        let product_id = status.known_affected.as_ref().unwrap()[0].clone();
+        status.known_not_affected = Some(vec![product_id.clone()]);
+        status.recommended = Some(vec![product_id.clone()]);
+
+        let errors = document.validate().unwrap_err();
+        assert_eq!(
+            errors,
+            vec![ValidationError::DuplicateProductInStatus {
+                vulnerability: Some("CVE-2018-0171".to_string()),
+                product_id,
+                statuses: vec![
+                    ProductStatusKind::KnownAffected,
+                    ProductStatusKind::KnownNotAffected
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn flags_require_not_affected_status() {
+        let mut document = example();
+        let vulnerability = &mut document.vulnerabilities.as_mut().unwrap()[0];
+        let product_id = vulnerability
+            .product_status
+            .as_ref()
+            .unwrap()
+            .known_affected
+            .as_ref()
+            .unwrap()[0]
+            .clone();
+        let flag = Flag {
+            label: FlagLabel::VulnerableCodeNotPresent,
+            date: None,
+            group_ids: None,
+            product_ids: Some(vec![product_id.clone()]),
+        };
+        assert!(!flag.is_consistent_with(vulnerability.product_status.as_ref().unwrap()));
+        vulnerability.flags = Some(vec![flag]);
+
+        assert_eq!(
+            document.validate(),
+            Err(vec![ValidationError::FlagInconsistentWithStatus {
+                vulnerability: Some("CVE-2018-0171".to_string()),
+                product_id,
+                label: FlagLabel::VulnerableCodeNotPresent,
+            }])
+        );
+    }
+}
@@ -1,14 +1,26 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, FromInto};
+use serde_with::{serde_as, DisplayFromStr, FromInto, TryFromInto};
 use url::Url;
 
-use crate::definitions::{AcknowledgmentsT, NotesT, ProductGroupsT, ProductsT, ReferencesT};
+use crate::definitions::{
+    AcknowledgmentsT, Note, NotesT, ProductGroupIdT, ProductGroupsT, ProductIdT, ProductsT,
+    Reference, ReferencesT,
+};
+use crate::product_tree::ProductTree;
 
 /// [Vulnerabilities](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#323-vulnerabilities-property)
 // TODO: At least one of these must be set
 #[serde_with::skip_serializing_none]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Vulnerability {
     pub acknowledgments: Option<AcknowledgmentsT>,
     pub cve: Option<String>,
@@ -21,24 +33,541 @@ pub struct Vulnerability {
     pub product_status: Option<ProductStatus>,
     pub references: Option<ReferencesT>,
     pub release_date: Option<DateTime<Utc>>,
-    pub remediations: Option<Vec<Remediation>>,
+    pub remediations: Option<Remediations>,
     pub scores: Option<Vec<Score>>,
     pub threats: Option<Vec<Threat>>,
     pub title: Option<String>,
+    /// When the vulnerability was first disclosed, added in CSAF 2.1 to replace `release_date`
+    pub disclosure_date: Option<DateTime<Utc>>,
+    /// Scores and other metrics, added in CSAF 2.1 to replace `scores`
+    pub metrics: Option<Vec<Metric>>,
+}
+
+impl Vulnerability {
+    pub fn builder() -> VulnerabilityBuilder {
+        VulnerabilityBuilder::new()
+    }
+
+    /// Identifies the vulnerability across documents by its CVE, falling back to its first ID, then its title
+    pub(crate) fn key(&self) -> Option<String> {
+        self.cve
+            .clone()
+            .or_else(|| {
+                self.ids
+                    .as_ref()
+                    .and_then(|ids| ids.first())
+                    .map(|id| id.text.clone())
+            })
+            .or_else(|| self.title.clone())
+    }
+
+    /// The National Vulnerability Database page for the CVE, if there is one
+    pub fn nvd_url(&self) -> Option<Url> {
+        let cve = self.cve.as_ref()?;
+        Url::parse(&format!("https://nvd.nist.gov/vuln/detail/{}", cve)).ok()
+    }
+
+    /// The MITRE CVE list page for the CVE, if there is one
+    pub fn mitre_url(&self) -> Option<Url> {
+        let cve = self.cve.as_ref()?;
+        let mut url = Url::parse("https://cve.mitre.org/cgi-bin/cvename.cgi").ok()?;
+        url.query_pairs_mut().append_pair("name", cve);
+        Some(url)
+    }
+
+    /// Every product ID mentioned in `product_status`, `scores`, `remediations`, `flags` and `threats`, each yielded once
+    /// in the order first mentioned
+    pub fn all_product_ids(&self) -> impl Iterator<Item = &ProductIdT> {
+        let mut seen = HashSet::new();
+        self.referenced_product_ids()
+            .filter(move |id| seen.insert(*id))
+    }
+
+    /// Every product ID listed in `product_status`, whatever its status, once each and sorted. Useful for building a
+    /// product tree for the vulnerability.
+    pub fn deduplicated_product_ids(&self) -> Vec<ProductIdT> {
+        let mut ids: Vec<_> = self
+            .product_status
+            .iter()
+            .flat_map(|status| status.entries().map(|(_, id)| id))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .cloned()
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Like [Vulnerability::all_product_ids], but yielding IDs mentioned more than once each time
+    pub(crate) fn referenced_product_ids(&self) -> impl Iterator<Item = &ProductIdT> {
+        let status = self
+            .product_status
+            .iter()
+            .flat_map(|status| status.entries().map(|(_, id)| id));
+        let scores = self
+            .scores
+            .iter()
+            .flatten()
+            .flat_map(|score| &score.products);
+        let remediations = self
+            .remediations
+            .iter()
+            .flatten()
+            .flat_map(|remediation| remediation.product_ids.iter().flatten());
+        let flags = self
+            .flags
+            .iter()
+            .flatten()
+            .flat_map(|flag| flag.product_ids.iter().flatten());
+        let threats = self
+            .threats
+            .iter()
+            .flatten()
+            .flat_map(|threat| threat.product_ids.iter().flatten());
+        status
+            .chain(scores)
+            .chain(remediations)
+            .chain(flags)
+            .chain(threats)
+    }
+
+    /// Threats listing the product, or scoped to no products or groups and so applying to all of them
+    ///
+    /// Product groups are not resolved, as that needs the document's product tree.
+    pub fn threats_for_product<'a>(
+        &'a self,
+        id: &'a ProductIdT,
+    ) -> impl Iterator<Item = &'a Threat> + 'a {
+        self.threats
+            .iter()
+            .flatten()
+            .filter(move |t| applies_to(&t.product_ids, &t.group_ids, id))
+    }
+
+    /// Remediations applying to the product, in the same way as [Vulnerability::threats_for_product]
+    pub fn remediations_for_product<'a>(
+        &'a self,
+        id: &'a ProductIdT,
+    ) -> impl Iterator<Item = &'a Remediation> + 'a {
+        self.remediations
+            .iter()
+            .flatten()
+            .filter(move |r| applies_to(&r.product_ids, &r.group_ids, id))
+    }
+
+    /// Flags applying to the product, in the same way as [Vulnerability::threats_for_product]
+    pub fn flags_for_product<'a>(
+        &'a self,
+        id: &'a ProductIdT,
+    ) -> impl Iterator<Item = &'a Flag> + 'a {
+        self.flags
+            .iter()
+            .flatten()
+            .filter(move |f| applies_to(&f.product_ids, &f.group_ids, id))
+    }
+
+    /// Scores listing the product, which every score must do
+    pub fn scores_for_product<'a>(
+        &'a self,
+        id: &'a ProductIdT,
+    ) -> impl Iterator<Item = &'a Score> + 'a {
+        self.scores
+            .iter()
+            .flatten()
+            .filter(move |s| s.products.contains(id))
+    }
+
+    /// The first score listing the product
+    pub fn score_for_product(&self, id: &ProductIdT) -> Option<&Score> {
+        self.scores
+            .iter()
+            .flatten()
+            .find(|score| score.products.contains(id))
+    }
+
+    /// The number of products listed as `known_affected`
+    pub fn known_affected_count(&self) -> usize {
+        self.status_count(|status| &status.known_affected)
+    }
+
+    /// The number of products listed as `fixed`
+    pub fn fixed_count(&self) -> usize {
+        self.status_count(|status| &status.fixed)
+    }
+
+    /// The number of products listed as `known_not_affected`
+    pub fn known_not_affected_count(&self) -> usize {
+        self.status_count(|status| &status.known_not_affected)
+    }
+
+    /// The number of products listed as `under_investigation`
+    pub fn under_investigation_count(&self) -> usize {
+        self.status_count(|status| &status.under_investigation)
+    }
+
+    fn status_count(&self, list: impl Fn(&ProductStatus) -> &Option<ProductsT>) -> usize {
+        self.product_status
+            .as_ref()
+            .and_then(|status| list(status).as_ref())
+            .map_or(0, Vec::len)
+    }
+
+    /// The highest [Score::base_score] across every score, regardless of the products it covers
+    pub fn max_cvss_score(&self) -> Option<f64> {
+        self.scores
+            .iter()
+            .flatten()
+            .filter_map(Score::base_score)
+            .reduce(f64::max)
+    }
+
+    /// The earliest of `release_date`, `discovery_date`, `disclosure_date` and the dates of every remediation and
+    /// threat, taken as the first public disclosure of the vulnerability
+    pub fn earliest_disclosure_date(&self) -> Option<DateTime<Utc>> {
+        let remediation_dates = self
+            .remediations
+            .iter()
+            .flatten()
+            .map(|remediation| remediation.date);
+        let threat_dates = self.threats.iter().flatten().map(|threat| threat.date);
+        [self.release_date, self.discovery_date, self.disclosure_date]
+            .into_iter()
+            .chain(remediation_dates)
+            .chain(threat_dates)
+            .flatten()
+            .min()
+    }
+
+    /// Checks the vulnerability against the requirements of the
+    /// [VEX profile](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#45-profile-5-vex),
+    /// returning every requirement that is not met
+    ///
+    /// Statements scoped to product groups count for every product in those groups, as listed in the `product_groups`
+    /// of the document's `product_tree`.
+    pub fn is_vex_compliant(
+        &self,
+        product_tree: Option<&ProductTree>,
+    ) -> Result<(), Vec<VexComplianceError>> {
+        let groups = product_tree
+            .and_then(|tree| tree.product_groups.as_deref())
+            .unwrap_or_default();
+        let in_groups = |group_ids: &Option<ProductGroupsT>, id: &ProductIdT| {
+            group_ids.iter().flatten().any(|group_id| {
+                groups
+                    .iter()
+                    .any(|group| group.group_id == *group_id && group.product_ids.contains(id))
+            })
+        };
+
+        let mut errors = Vec::new();
+        if self.cve.is_none() && self.ids.is_none() {
+            errors.push(VexComplianceError::MissingIdentification);
+        }
+
+        if self.notes.as_ref().map_or(true, Vec::is_empty) {
+            errors.push(VexComplianceError::MissingNotes);
+        }
+
+        let status = match &self.product_status {
+            Some(status)
+                if [
+                    &status.fixed,
+                    &status.known_affected,
+                    &status.known_not_affected,
+                    &status.under_investigation,
+                ]
+                .into_iter()
+                .any(|list| list.as_ref().map_or(false, |list| !list.is_empty())) =>
+            {
+                status
+            }
+            _ => {
+                errors.push(VexComplianceError::MissingProductStatus);
+                return Err(errors);
+            }
+        };
+
+        for product_id in status.known_not_affected.iter().flatten() {
+            let flagged = self.flags.iter().flatten().any(|flag| {
+                applies_to(&flag.product_ids, &flag.group_ids, product_id)
+                    || in_groups(&flag.group_ids, product_id)
+            });
+            let impact = self.threats.iter().flatten().any(|threat| {
+                threat.category == ThreatCategory::Impact
+                    && (applies_to(&threat.product_ids, &threat.group_ids, product_id)
+                        || in_groups(&threat.group_ids, product_id))
+            });
+            if !flagged && !impact {
+                errors.push(VexComplianceError::MissingImpactStatement {
+                    product_id: product_id.clone(),
+                });
+            }
+        }
+        for product_id in status.known_affected.iter().flatten() {
+            let remediated = self.remediations.iter().flatten().any(|remediation| {
+                applies_to(&remediation.product_ids, &remediation.group_ids, product_id)
+                    || in_groups(&remediation.group_ids, product_id)
+            });
+            if !remediated {
+                errors.push(VexComplianceError::MissingActionStatement {
+                    product_id: product_id.clone(),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn applies_to(
+    product_ids: &Option<ProductsT>,
+    group_ids: &Option<ProductGroupsT>,
+    id: &ProductIdT,
+) -> bool {
+    match (product_ids, group_ids) {
+        (Some(product_ids), _) => product_ids.contains(id),
+        (None, None) => true,
+        (None, Some(_)) => false,
+    }
+}
+
+/// Builder for [Vulnerability] which requires a CVE, ID or title to identify it
+///
+/// ```
+/// # use csaf::vulnerability::{Vulnerability, VulnerabilityBuilderError};
+/// let vulnerability = Vulnerability::builder().cve("CVE-2018-0171").build().unwrap();
+/// assert_eq!(vulnerability.cve.as_deref(), Some("CVE-2018-0171"));
+/// assert_eq!(
+///     Vulnerability::builder().build(),
+///     Err(VulnerabilityBuilderError::NoIdentification)
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VulnerabilityBuilder {
+    vulnerability: Vulnerability,
+}
+
+impl VulnerabilityBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cve(mut self, cve: impl Into<String>) -> Self {
+        self.vulnerability.cve = Some(cve.into());
+        self
+    }
+
+    /// Adds an ID from a tracking system other than CVE
+    pub fn id(mut self, id: VulnerabilityId) -> Self {
+        self.vulnerability.ids.get_or_insert_with(Vec::new).push(id);
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.vulnerability.title = Some(title.into());
+        self
+    }
+
+    pub fn cwe(mut self, cwe: Cwe) -> Self {
+        self.vulnerability.cwe = Some(cwe);
+        self
+    }
+
+    pub fn product_status(mut self, product_status: ProductStatus) -> Self {
+        self.vulnerability.product_status = Some(product_status);
+        self
+    }
+
+    pub fn note(mut self, note: Note) -> Self {
+        self.vulnerability
+            .notes
+            .get_or_insert_with(Vec::new)
+            .push(note);
+        self
+    }
+
+    pub fn reference(mut self, reference: Reference) -> Self {
+        self.vulnerability
+            .references
+            .get_or_insert_with(Vec::new)
+            .push(reference);
+        self
+    }
+
+    pub fn score(mut self, score: Score) -> Self {
+        self.vulnerability
+            .scores
+            .get_or_insert_with(Vec::new)
+            .push(score);
+        self
+    }
+
+    pub fn remediation(mut self, remediation: Remediation) -> Self {
+        self.vulnerability
+            .remediations
+            .get_or_insert_with(Remediations::default)
+            .push(remediation);
+        self
+    }
+
+    pub fn threat(mut self, threat: Threat) -> Self {
+        self.vulnerability
+            .threats
+            .get_or_insert_with(Vec::new)
+            .push(threat);
+        self
+    }
+
+    pub fn build(self) -> Result<Vulnerability, VulnerabilityBuilderError> {
+        if self.vulnerability.cve.is_none()
+            && self.vulnerability.ids.is_none()
+            && self.vulnerability.title.is_none()
+        {
+            return Err(VulnerabilityBuilderError::NoIdentification);
+        }
+        Ok(self.vulnerability)
+    }
+}
+
+/// Reasons a [VulnerabilityBuilder] could not produce a [Vulnerability]
+#[derive(Debug, Clone, PartialEq)]
+pub enum VulnerabilityBuilderError {
+    /// None of `cve`, `ids` or `title` was set
+    NoIdentification,
+}
+
+impl Display for VulnerabilityBuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoIdentification => {
+                write!(f, "vulnerability needs a cve, id or title to identify it")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VulnerabilityBuilderError {}
+
+/// A requirement of the VEX profile that a [Vulnerability] does not meet, see [Vulnerability::is_vex_compliant]
+#[derive(Debug, Clone, PartialEq)]
+pub enum VexComplianceError {
+    /// Neither `cve` nor `ids` is set
+    MissingIdentification,
+    /// `notes` is missing or empty
+    MissingNotes,
+    /// None of `fixed`, `known_affected`, `known_not_affected` or `under_investigation` lists a product
+    MissingProductStatus,
+    /// A `known_not_affected` product has no flag or `impact` threat explaining why it is not affected
+    MissingImpactStatement { product_id: ProductIdT },
+    /// A `known_affected` product has no remediation
+    MissingActionStatement { product_id: ProductIdT },
 }
 
+impl Display for VexComplianceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingIdentification => write!(f, "VEX vulnerability needs a cve or ids"),
+            Self::MissingNotes => write!(f, "VEX vulnerability needs notes"),
+            Self::MissingProductStatus => write!(
+                f,
+                "VEX vulnerability needs a fixed, known_affected, known_not_affected or under_investigation product"
+            ),
+            Self::MissingImpactStatement { product_id } => write!(
+                f,
+                "known_not_affected product {} has no flag or impact threat",
+                product_id.0
+            ),
+            Self::MissingActionStatement { product_id } => write!(
+                f,
+                "known_affected product {} has no remediation",
+                product_id.0
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VexComplianceError {}
+
 /// [CWE](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3233-vulnerabilities-property---cwe)
+#[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Cwe {
-    // TODO: Validation - The Weakness ID (id) has value type string with pattern (regular expression):
-    pub id: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: CweId,
     // TODO: Should be a static map from ID to name for CWE's, ideally sourced from upstream
     pub name: String,
 }
 
+impl Cwe {
+    pub fn try_new(id: &str, name: &str) -> Result<Self, CweParseError> {
+        Ok(Self {
+            id: id.parse()?,
+            name: name.to_string(),
+        })
+    }
+}
+
+/// A CWE weakness ID such as `CWE-79`, validated against `^CWE-[1-9]\d{0,5}$` on parsing
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CweId(String);
+
+impl CweId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The numeric part of the ID
+    pub fn number(&self) -> u32 {
+        self.0["CWE-".len()..]
+            .parse()
+            .expect("Validated to be at most six digits")
+    }
+}
+
+impl FromStr for CweId {
+    type Err = CweParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valid = s.strip_prefix("CWE-").map_or(false, |number| {
+            (1..=6).contains(&number.len())
+                && !number.starts_with('0')
+                && number.chars().all(|c| c.is_ascii_digit())
+        });
+        if valid {
+            Ok(Self(s.to_string()))
+        } else {
+            Err(CweParseError(s.to_string()))
+        }
+    }
+}
+
+impl Display for CweId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A string that is not a valid [CweId]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CweParseError(pub String);
+
+impl Display for CweParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid CWE ID {:?}, expected CWE- and a number", self.0)
+    }
+}
+
+impl std::error::Error for CweParseError {}
+
 /// [Flags](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3235-vulnerabilities-property---flags)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Flag {
     pub label: FlagLabel,
     pub date: Option<DateTime<Utc>>,
@@ -46,8 +575,32 @@ pub struct Flag {
     pub product_ids: Option<ProductsT>,
 }
 
+impl Flag {
+    /// Whether every product the flag lists has the status its label requires in `status`, see
+    /// [FlagLabel::required_status]
+    ///
+    /// Product groups are not resolved, as that needs the document's product tree.
+    pub fn is_consistent_with(&self, status: &ProductStatus) -> bool {
+        self.inconsistent_products(status).next().is_none()
+    }
+
+    /// The products the flag lists that do not have the status its label requires
+    pub(crate) fn inconsistent_products<'a>(
+        &'a self,
+        status: &'a ProductStatus,
+    ) -> impl Iterator<Item = &'a ProductIdT> + 'a {
+        let required = self.label.required_status();
+        self.product_ids
+            .iter()
+            .flatten()
+            .filter(move |id| !status.has_status(id, |kind| kind == required))
+    }
+}
+
+/// [Flag Label](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#32351-vulnerabilities-property---flags---label)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum FlagLabel {
     ComponentNotPresent,
     InlineMitigationsAlreadyExist,
@@ -56,16 +609,46 @@ pub enum FlagLabel {
     VulnerableCodeNotPresent,
 }
 
+impl FlagLabel {
+    /// The product status a flagged product must have. Every label is a justification for a product not being
+    /// affected, so this is always `known_not_affected`.
+    pub fn required_status(&self) -> ProductStatusKind {
+        match self {
+            Self::ComponentNotPresent
+            | Self::InlineMitigationsAlreadyExist
+            | Self::VulnerableCodeCannotBeControlledByAdversary
+            | Self::VulnerableCodeNotInExecutePath
+            | Self::VulnerableCodeNotPresent => ProductStatusKind::KnownNotAffected,
+        }
+    }
+}
+
 /// [Vulnerability ID](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3235-vulnerabilities-property---id)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct VulnerabilityId {
     pub system_name: String,
     pub text: String,
 }
 
+impl VulnerabilityId {
+    /// The advisory page for IDs from well-known systems: NVD for `CVE`, the GitHub Advisory Database for `GHSA` and
+    /// the RustSec Advisory Database for `RUSTSEC`. The system name is matched case-insensitively.
+    pub fn well_known_url(&self) -> Option<Url> {
+        let url = match self.system_name.to_ascii_uppercase().as_str() {
+            "CVE" => format!("https://nvd.nist.gov/vuln/detail/{}", self.text),
+            "GHSA" => format!("https://github.com/advisories/{}", self.text),
+            "RUSTSEC" => format!("https://rustsec.org/advisories/{}.html", self.text),
+            _ => return None,
+        };
+        Url::parse(&url).ok()
+    }
+}
+
 /// [Involvements](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3236-vulnerabilities-property---involvements)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Involvement {
     pub party: InvolvementParty,
     pub status: InvolvementStatus,
@@ -76,6 +659,7 @@ pub struct Involvement {
 /// [Involvements](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3236-vulnerabilities-property---involvements)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
+#[non_exhaustive]
 pub enum InvolvementParty {
     Coordinator,
     Discoverer,
@@ -87,6 +671,7 @@ pub enum InvolvementParty {
 /// [Involvements](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3236-vulnerabilities-property---involvements)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum InvolvementStatus {
     Completed,
     ContactAttempted,
@@ -98,7 +683,8 @@ pub enum InvolvementStatus {
 
 /// [Product Status](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3238-vulnerabilities-property---product-status)
 #[serde_with::skip_serializing_none]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct ProductStatus {
     pub first_affected: Option<ProductsT>,
     pub first_fixed: Option<ProductsT>,
@@ -110,9 +696,150 @@ pub struct ProductStatus {
     pub under_investigation: Option<ProductsT>,
 }
 
+impl ProductStatus {
+    /// Every product ID listed, paired with the status it is listed under. A product may appear under several statuses.
+    pub fn entries(&self) -> impl Iterator<Item = (ProductStatusKind, &ProductIdT)> {
+        [
+            (ProductStatusKind::FirstAffected, &self.first_affected),
+            (ProductStatusKind::FirstFixed, &self.first_fixed),
+            (ProductStatusKind::Fixed, &self.fixed),
+            (ProductStatusKind::KnownAffected, &self.known_affected),
+            (
+                ProductStatusKind::KnownNotAffected,
+                &self.known_not_affected,
+            ),
+            (ProductStatusKind::LastAffected, &self.last_affected),
+            (ProductStatusKind::Recommended, &self.recommended),
+            (
+                ProductStatusKind::UnderInvestigation,
+                &self.under_investigation,
+            ),
+        ]
+        .into_iter()
+        .flat_map(|(kind, ids)| ids.iter().flatten().map(move |id| (kind, id)))
+    }
+
+    /// The first status the product is listed under, in the order of [ProductStatus::entries]
+    pub fn status_of(&self, id: &ProductIdT) -> Option<ProductStatusKind> {
+        self.entries()
+            .find(|(_, listed)| *listed == id)
+            .map(|(kind, _)| kind)
+    }
+
+    pub(crate) fn has_status(
+        &self,
+        id: &ProductIdT,
+        predicate: impl Fn(ProductStatusKind) -> bool,
+    ) -> bool {
+        self.entries()
+            .any(|(kind, listed)| listed == id && predicate(kind))
+    }
+
+    /// Whether the product is listed as `first_affected`, `known_affected` or `last_affected`
+    pub fn is_affected(&self, id: &ProductIdT) -> bool {
+        self.has_status(id, ProductStatusKind::is_affected)
+    }
+
+    /// Whether the product is listed as `first_fixed` or `fixed`
+    pub fn is_fixed(&self, id: &ProductIdT) -> bool {
+        self.has_status(id, ProductStatusKind::is_fixed)
+    }
+
+    /// Whether the product is listed as `known_not_affected`
+    pub fn is_not_affected(&self, id: &ProductIdT) -> bool {
+        self.has_status(id, ProductStatusKind::is_not_affected)
+    }
+
+    /// Whether the product is listed as `under_investigation`
+    pub fn is_under_investigation(&self, id: &ProductIdT) -> bool {
+        self.has_status(id, ProductStatusKind::is_under_investigation)
+    }
+
+    /// The list of products with the given status
+    pub(crate) fn list_mut(&mut self, kind: ProductStatusKind) -> &mut Option<ProductsT> {
+        match kind {
+            ProductStatusKind::FirstAffected => &mut self.first_affected,
+            ProductStatusKind::FirstFixed => &mut self.first_fixed,
+            ProductStatusKind::Fixed => &mut self.fixed,
+            ProductStatusKind::KnownAffected => &mut self.known_affected,
+            ProductStatusKind::KnownNotAffected => &mut self.known_not_affected,
+            ProductStatusKind::LastAffected => &mut self.last_affected,
+            ProductStatusKind::Recommended => &mut self.recommended,
+            ProductStatusKind::UnderInvestigation => &mut self.under_investigation,
+        }
+    }
+
+    /// The statuses each listed product is listed under
+    pub(crate) fn by_product(&self) -> BTreeMap<ProductIdT, Vec<ProductStatusKind>> {
+        let mut output: BTreeMap<ProductIdT, Vec<ProductStatusKind>> = BTreeMap::new();
+        for (kind, id) in self.entries() {
+            output.entry(id.clone()).or_default().push(kind);
+        }
+        output
+    }
+}
+
+/// The [Product Status](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#3238-vulnerabilities-property---product-status)
+/// list a product is listed under
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ProductStatusKind {
+    FirstAffected,
+    FirstFixed,
+    Fixed,
+    KnownAffected,
+    KnownNotAffected,
+    LastAffected,
+    Recommended,
+    UnderInvestigation,
+}
+
+impl ProductStatusKind {
+    /// `first_affected`, `known_affected` or `last_affected`
+    pub fn is_affected(self) -> bool {
+        matches!(
+            self,
+            Self::FirstAffected | Self::KnownAffected | Self::LastAffected
+        )
+    }
+
+    /// `first_fixed` or `fixed`
+    pub fn is_fixed(self) -> bool {
+        matches!(self, Self::FirstFixed | Self::Fixed)
+    }
+
+    /// `known_not_affected`
+    pub fn is_not_affected(self) -> bool {
+        self == Self::KnownNotAffected
+    }
+
+    /// `under_investigation`
+    pub fn is_under_investigation(self) -> bool {
+        self == Self::UnderInvestigation
+    }
+}
+
+impl Display for ProductStatusKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::FirstAffected => "first_affected",
+            Self::FirstFixed => "first_fixed",
+            Self::Fixed => "fixed",
+            Self::KnownAffected => "known_affected",
+            Self::KnownNotAffected => "known_not_affected",
+            Self::LastAffected => "last_affected",
+            Self::Recommended => "recommended",
+            Self::UnderInvestigation => "under_investigation",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 /// [Remediations](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#32311-vulnerabilities-property---remediations)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Remediation {
     pub category: RemediationCategory,
     pub details: String,
@@ -124,20 +851,107 @@ pub struct Remediation {
     pub url: Option<Url>,
 }
 
+/// The [Remediations](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#32311-vulnerabilities-property---remediations)
+/// of a vulnerability, dereferencing to the underlying `Vec`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Remediations(pub Vec<Remediation>);
+
+impl Remediations {
+    /// Remediations of the given category
+    pub fn by_category(
+        &self,
+        category: RemediationCategory,
+    ) -> impl Iterator<Item = &Remediation> + '_ {
+        self.0.iter().filter(move |r| r.category == category)
+    }
+
+    /// Remediations listing the product, or scoped to no products or groups and so applying to all of them
+    ///
+    /// Product groups are not resolved, as that needs the document's product tree.
+    pub fn for_product<'a>(
+        &'a self,
+        id: &'a ProductIdT,
+    ) -> impl Iterator<Item = &'a Remediation> + 'a {
+        self.0
+            .iter()
+            .filter(move |r| applies_to(&r.product_ids, &r.group_ids, id))
+    }
+
+    /// Remediations listing the product group, or scoped to no products or groups
+    pub fn for_group<'a>(
+        &'a self,
+        id: &'a ProductGroupIdT,
+    ) -> impl Iterator<Item = &'a Remediation> + 'a {
+        self.0
+            .iter()
+            .filter(move |r| match (&r.product_ids, &r.group_ids) {
+                (_, Some(group_ids)) => group_ids.contains(id),
+                (None, None) => true,
+                (Some(_), None) => false,
+            })
+    }
+}
+
+impl Deref for Remediations {
+    type Target = Vec<Remediation>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Remediations {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Vec<Remediation>> for Remediations {
+    fn from(remediations: Vec<Remediation>) -> Self {
+        Self(remediations)
+    }
+}
+
+impl<'a> IntoIterator for &'a Remediations {
+    type Item = &'a Remediation;
+    type IntoIter = std::slice::Iter<'a, Remediation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for Remediations {
+    type Item = Remediation;
+    type IntoIter = std::vec::IntoIter<Remediation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
 /// [Remediation Category](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#323111-vulnerabilities-property---remediations---category)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum RemediationCategory {
     Mitigation,
     NoFixPlanned,
     NoneAvailable,
     VendorFix,
     Workaround,
+    /// Added in CSAF 2.1, enabled by the `csaf_2_1` feature
+    #[cfg(feature = "csaf_2_1")]
+    FixPlanned,
+    /// Added in CSAF 2.1, enabled by the `csaf_2_1` feature
+    #[cfg(feature = "csaf_2_1")]
+    OptionalPatch,
 }
 
 /// [Restart Required](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#323117-vulnerabilities-property---remediations---restart-required)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RestartRequired {
     pub category: RestartCategory,
     pub details: Option<String>,
@@ -146,6 +960,7 @@ pub struct RestartRequired {
 /// [Restart Required](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#323117-vulnerabilities-property---remediations---restart-required)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum RestartCategory {
     Connected,
     Dependencies,
@@ -162,20 +977,164 @@ pub enum RestartCategory {
 #[serde_as]
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Score {
     pub products: ProductsT,
-    // TODO: A CVSS v2 representation, or just document it as out of scope
     // TODO: Should have at least one of:
-    pub cvss_v2: Option<serde_json::Value>,
+    #[serde_as(as = "Option<TryFromInto<cvss_json::Cvss2>>")]
+    pub cvss_v2: Option<crate::cvss_v2::Vector>,
+    #[serde_as(as = "Option<FromInto<cvss_json::Cvss3>>")]
+    pub cvss_v3: Option<cvss::v3::Base>,
+    #[serde_as(as = "Option<TryFromInto<cvss_json::Cvss4>>")]
+    pub cvss_v4: Option<cvss::v4::Vector>,
+}
+
+/// A metric of the CSAF 2.1 draft, which replaces [Score]
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct Metric {
+    pub content: MetricContent,
+    pub products: ProductsT,
+    /// Where the metric was taken from, if not calculated by the publisher
+    pub source: Option<Url>,
+}
+
+/// The scores of a [Metric], of which at least one should be given
+#[serde_as]
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct MetricContent {
+    #[serde_as(as = "Option<TryFromInto<cvss_json::Cvss2>>")]
+    pub cvss_v2: Option<crate::cvss_v2::Vector>,
     #[serde_as(as = "Option<FromInto<cvss_json::Cvss3>>")]
     pub cvss_v3: Option<cvss::v3::Base>,
+    #[serde_as(as = "Option<TryFromInto<cvss_json::Cvss4>>")]
+    pub cvss_v4: Option<cvss::v4::Vector>,
+    pub epss: Option<Epss>,
+}
+
+/// An [EPSS](https://www.first.org/epss/) probability of exploitation, kept as the decimal strings of the schema
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct Epss {
+    pub percentile: String,
+    pub probability: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A version of CVSS that a [Score] may be given in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CvssVersion {
+    V2,
+    V3,
+    V4,
+}
+
+/// The qualitative severity rating of a CVSS v3.1 base score, ordered from least to most severe
+///
+/// Its [Display] form, such as `Critical`, is suitable for [crate::document::AggregateSeverity]'s `text`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CvssSeverity {
+    None,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl CvssSeverity {
+    /// Rates a base score: 0.0 is none, up to 3.9 low, up to 6.9 medium, up to 8.9 high and anything above critical
+    pub fn from_score(score: f64) -> Self {
+        if score <= 0.0 {
+            Self::None
+        } else if score < 4.0 {
+            Self::Low
+        } else if score < 7.0 {
+            Self::Medium
+        } else if score < 9.0 {
+            Self::High
+        } else {
+            Self::Critical
+        }
+    }
+}
+
+impl Display for CvssSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::None => "None",
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+            Self::Critical => "Critical",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Score {
+    /// The base score of the most recent CVSS version given, preferring v4, then v3, then v2
+    pub fn base_score(&self) -> Option<f64> {
+        [CvssVersion::V4, CvssVersion::V3, CvssVersion::V2]
+            .into_iter()
+            .find_map(|version| self.base_score_for_version(version))
+    }
+
+    /// The base score of the given CVSS version, if the score includes it
+    pub fn base_score_for_version(&self, version: CvssVersion) -> Option<f64> {
+        match version {
+            CvssVersion::V2 => self.cvss_v2.as_ref().map(|vector| vector.base_score()),
+            CvssVersion::V3 => self.cvss_v3.as_ref().map(|base| base.score().value()),
+            CvssVersion::V4 => self.cvss_v4.as_ref().map(|vector| vector.score().value()),
+        }
+    }
+
+    /// The severity rating of the CVSS v3 base score, if the score includes one
+    pub fn cvss_v3_severity(&self) -> Option<CvssSeverity> {
+        self.base_score_for_version(CvssVersion::V3)
+            .map(CvssSeverity::from_score)
+    }
+
+    /// Looks up a single metric of the CVSS v4 vector, if there is one and the metric is present in it.
+    ///
+    /// ```
+    /// # use csaf::vulnerability::Score;
+    /// use cvss::v4::metric::base::AttackVector;
+    ///
+    /// let score = Score {
+    ///     products: vec![],
+    ///     cvss_v2: None,
+    ///     cvss_v3: None,
+    ///     cvss_v4: Some("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N".parse().unwrap()),
+    /// };
+    /// assert_eq!(score.cvss_v4_metric::<AttackVector>(), Some(AttackVector::Network));
+    /// ```
+    pub fn cvss_v4_metric<M: cvss::v4::metric::Metric>(&self) -> Option<M> {
+        // The cvss crate keeps the parsed metrics of a v4 vector private and has no typed getter, only exposing them
+        // as `dyn Debug` through `Vector::metrics`, so the metric is read back from the canonical vector string.
+        let vector = self.cvss_v4.as_ref()?.to_string();
+        vector
+            .split('/')
+            .skip(1)
+            .filter_map(|component| component.split_once(':'))
+            .find(|(metric, _)| *metric == M::TYPE.name())
+            .and_then(|(_, value)| value.parse().ok())
+    }
 }
 
 mod cvss_json {
-    use std::str::FromStr;
+    use std::{convert::TryFrom, str::FromStr};
 
     use serde::{Deserialize, Serialize, Serializer};
 
+    use crate::cvss_v2::{
+        AccessComplexity, AccessVector, Authentication, CollateralDamagePotential, Exploitability,
+        Impact, ParseError, RemediationLevel, ReportConfidence, Requirement, TargetDistribution,
+        Vector,
+    };
+
     /// CVSSv3 JSON Representation
     ///
     /// An internal representation of a CVSS score, meant to be serializable to JSON as specified in
@@ -227,11 +1186,127 @@ mod cvss_json {
         #[serde(rename = "3.0")]
         ThreeDotZero,
     }
+
+    /// CVSSv2 JSON Representation
+    ///
+    /// An internal representation of a CVSS v2 score, meant to be serializable to JSON as specified in
+    /// [https://www.first.org/cvss/cvss-v2.0.json](https://www.first.org/cvss/cvss-v2.0.json).
+    ///
+    /// Use with [crate::cvss_v2::Vector] and the provided `TryFrom` implementation. The individual metrics and the
+    /// scores are always derived from the vector string when deserializing.
+    #[serde_with::skip_serializing_none]
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(crate) struct Cvss2 {
+        version: Cvss2Version,
+        vector_string: String,
+        base_score: f64,
+        access_vector: Option<AccessVector>,
+        access_complexity: Option<AccessComplexity>,
+        authentication: Option<Authentication>,
+        confidentiality_impact: Option<Impact>,
+        integrity_impact: Option<Impact>,
+        availability_impact: Option<Impact>,
+        exploitability: Option<Exploitability>,
+        remediation_level: Option<RemediationLevel>,
+        report_confidence: Option<ReportConfidence>,
+        temporal_score: Option<f64>,
+        collateral_damage_potential: Option<CollateralDamagePotential>,
+        target_distribution: Option<TargetDistribution>,
+        confidentiality_requirement: Option<Requirement>,
+        integrity_requirement: Option<Requirement>,
+        availability_requirement: Option<Requirement>,
+        environmental_score: Option<f64>,
+    }
+
+    impl From<Vector> for Cvss2 {
+        fn from(v: Vector) -> Self {
+            let b = v.base;
+            Self {
+                version: Cvss2Version::TwoDotZero,
+                vector_string: v.to_string(),
+                base_score: b.score(),
+                access_vector: Some(b.access_vector),
+                access_complexity: Some(b.access_complexity),
+                authentication: Some(b.authentication),
+                confidentiality_impact: Some(b.confidentiality_impact),
+                integrity_impact: Some(b.integrity_impact),
+                availability_impact: Some(b.availability_impact),
+                exploitability: v.temporal.map(|t| t.exploitability),
+                remediation_level: v.temporal.map(|t| t.remediation_level),
+                report_confidence: v.temporal.map(|t| t.report_confidence),
+                temporal_score: v.temporal_score(),
+                collateral_damage_potential: v.environmental.map(|e| e.collateral_damage_potential),
+                target_distribution: v.environmental.map(|e| e.target_distribution),
+                confidentiality_requirement: v.environmental.map(|e| e.confidentiality_requirement),
+                integrity_requirement: v.environmental.map(|e| e.integrity_requirement),
+                availability_requirement: v.environmental.map(|e| e.availability_requirement),
+                environmental_score: v.environmental_score(),
+            }
+        }
+    }
+
+    impl TryFrom<Cvss2> for Vector {
+        type Error = ParseError;
+
+        fn try_from(v: Cvss2) -> Result<Self, Self::Error> {
+            Vector::from_str(&v.vector_string)
+        }
+    }
+
+    /// CVSSv4 JSON Representation
+    ///
+    /// An internal representation of a CVSS v4 score, meant to be serializable to JSON as specified in
+    /// [https://www.first.org/cvss/cvss-v4.0.json](https://www.first.org/cvss/cvss-v4.0.json).
+    ///
+    /// Use with [cvss::v4::Vector] and the provided `TryFrom` implementation.
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub(crate) struct Cvss4 {
+        version: Cvss4Version,
+        vector_string: String,
+        base_score: f64,
+        #[serde(serialize_with = "severity_to_upper")]
+        base_severity: cvss::Severity,
+    }
+
+    impl From<cvss::v4::Vector> for Cvss4 {
+        fn from(v: cvss::v4::Vector) -> Self {
+            let score = v.score();
+            Self {
+                version: Cvss4Version::FourDotZero,
+                vector_string: v.to_string(),
+                base_score: score.clone().value(),
+                base_severity: score.severity(),
+            }
+        }
+    }
+
+    impl TryFrom<Cvss4> for cvss::v4::Vector {
+        type Error = cvss::Error;
+
+        fn try_from(v: Cvss4) -> Result<Self, Self::Error> {
+            cvss::v4::Vector::from_str(&v.vector_string)
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    enum Cvss4Version {
+        #[serde(rename = "4.0")]
+        FourDotZero,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    enum Cvss2Version {
+        #[serde(rename = "2.0")]
+        TwoDotZero,
+    }
 }
 
 /// [Threats](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#32313-vulnerabilities-property---threats)
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Threat {
     pub category: ThreatCategory,
     pub details: String,
@@ -243,8 +1318,649 @@ pub struct Threat {
 /// [Threats](https://github.com/oasis-tcs/csaf/blob/master/csaf_2.0/prose/csaf-v2-editor-draft.md#32313-vulnerabilities-property---threats)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum ThreatCategory {
     ExploitStatus,
     Impact,
     TargetSet,
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn cvss_v2_score_roundtrips() {
+        // CVE-2014-0160 as scored by NVD
+        let json = r#"{
+            "products": ["CSAFPID-0001"],
+            "cvss_v2": {
+                "accessComplexity": "LOW",
+                "accessVector": "NETWORK",
+                "authentication": "NONE",
+                "availabilityImpact": "NONE",
+                "baseScore": 5.0,
+                "confidentialityImpact": "PARTIAL",
+                "integrityImpact": "NONE",
+                "vectorString": "AV:N/AC:L/Au:N/C:P/I:N/A:N",
+                "version": "2.0"
+            }
+        }"#;
+        let score: Score = serde_json::from_str(json).unwrap();
+        let vector = score.cvss_v2.unwrap();
+        assert_eq!(
+            vector.base.access_vector,
+            crate::cvss_v2::AccessVector::Network
+        );
+        assert_eq!(vector.base_score(), 5.0);
+
+        let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_value(&score).unwrap(), expected);
+    }
+
+    #[test]
+    fn cvss_v2_temporal_and_environmental_roundtrip() {
+        let json = r#"{
+            "products": ["CSAFPID-0001"],
+            "cvss_v2": {
+                "accessComplexity": "LOW",
+                "accessVector": "NETWORK",
+                "authentication": "NONE",
+                "availabilityImpact": "COMPLETE",
+                "availabilityRequirement": "HIGH",
+                "baseScore": 7.8,
+                "collateralDamagePotential": "HIGH",
+                "confidentialityImpact": "NONE",
+                "confidentialityRequirement": "MEDIUM",
+                "environmentalScore": 9.2,
+                "exploitability": "FUNCTIONAL",
+                "integrityImpact": "NONE",
+                "integrityRequirement": "MEDIUM",
+                "remediationLevel": "OFFICIAL_FIX",
+                "reportConfidence": "CONFIRMED",
+                "targetDistribution": "HIGH",
+                "temporalScore": 6.4,
+                "vectorString": "AV:N/AC:L/Au:N/C:N/I:N/A:C/E:F/RL:OF/RC:C/CDP:H/TD:H/CR:M/IR:M/AR:H",
+                "version": "2.0"
+            }
+        }"#;
+        let score: Score = serde_json::from_str(json).unwrap();
+        let vector = score.cvss_v2.unwrap();
+        assert_eq!(vector.temporal_score(), Some(6.4));
+        assert_eq!(score.base_score(), Some(7.8));
+
+        let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_value(&score).unwrap(), expected);
+    }
+
+    #[test]
+    fn cvss_v4_score_roundtrips() {
+        let json = r#"{
+            "products": ["CSAFPID-0001"],
+            "cvss_v4": {
+                "version": "4.0",
+                "vectorString": "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N",
+                "baseScore": 9.3,
+                "baseSeverity": "CRITICAL"
+            }
+        }"#;
+        let score: Score = serde_json::from_str(json).unwrap();
+        assert!(score.cvss_v3.is_none());
+        assert_eq!(
+            score.cvss_v4_metric::<cvss::v4::metric::base::AttackRequirements>(),
+            Some(cvss::v4::metric::base::AttackRequirements::None)
+        );
+
+        let full = Score {
+            products: vec![],
+            cvss_v2: None,
+            cvss_v3: None,
+            cvss_v4: Some(
+                "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N/E:P/MAV:L/AU:Y"
+                    .parse()
+                    .unwrap(),
+            ),
+        };
+        assert_eq!(
+            full.cvss_v4_metric::<cvss::v4::metric::threat::ExploitMaturity>(),
+            Some(cvss::v4::metric::threat::ExploitMaturity::ProofOfConcept)
+        );
+        assert_eq!(
+            full.cvss_v4_metric::<cvss::v4::metric::environmental::ModifiedAttackVector>(),
+            Some(cvss::v4::metric::environmental::ModifiedAttackVector::Local)
+        );
+        assert_eq!(
+            full.cvss_v4_metric::<cvss::v4::metric::supplemental::Automatable>(),
+            Some(cvss::v4::metric::supplemental::Automatable::Yes)
+        );
+        assert_eq!(
+            full.cvss_v4_metric::<cvss::v4::metric::environmental::AvailabilityRequirements>(),
+            None
+        );
+
+        let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_value(&score).unwrap(), expected);
+    }
+
+    #[test]
+    fn cvss_v2_invalid_vector_is_an_error() {
+        let json = r#"{
+            "products": ["CSAFPID-0001"],
+            "cvss_v2": {"baseScore": 5.0, "vectorString": "AV:N/AC:L", "version": "2.0"}
+        }"#;
+        assert!(serde_json::from_str::<Score>(json).is_err());
+    }
+
+    #[test]
+    fn cve_urls() {
+        let vulnerability = Vulnerability {
+            cve: Some("CVE-2018-0171".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            vulnerability.nvd_url().unwrap().as_str(),
+            "https://nvd.nist.gov/vuln/detail/CVE-2018-0171"
+        );
+        assert_eq!(
+            vulnerability.mitre_url().unwrap().as_str(),
+            "https://cve.mitre.org/cgi-bin/cvename.cgi?name=CVE-2018-0171"
+        );
+        assert_eq!(Vulnerability::default().nvd_url(), None);
+        assert_eq!(Vulnerability::default().mitre_url(), None);
+    }
+
+    #[test]
+    fn base_score_prefers_latest_version() {
+        let mut score = Score {
+            products: vec![],
+            cvss_v2: Some("AV:N/AC:L/Au:N/C:P/I:P/A:P".parse().unwrap()),
+            cvss_v3: None,
+            cvss_v4: None,
+        };
+        assert_eq!(score.base_score(), Some(7.5));
+
+        score.cvss_v3 = Some(
+            "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(score.base_score(), Some(9.8));
+
+        score.cvss_v4 = Some(
+            "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(score.base_score(), Some(9.3));
+        assert_eq!(score.base_score_for_version(CvssVersion::V2), Some(7.5));
+        assert_eq!(
+            Score {
+                products: vec![],
+                cvss_v2: None,
+                cvss_v3: None,
+                cvss_v4: None
+            }
+            .base_score(),
+            None
+        );
+    }
+
+    #[test]
+    fn product_status_queries() {
+        let product = |id: &str| ProductIdT(id.to_string());
+        let status = ProductStatus {
+            last_affected: Some(vec![product("A")]),
+            first_fixed: Some(vec![product("B")]),
+            fixed: Some(vec![product("A")]),
+            known_not_affected: Some(vec![product("C")]),
+            under_investigation: Some(vec![product("D")]),
+            ..Default::default()
+        };
+
+        assert!(status.is_affected(&product("A")));
+        assert!(status.is_fixed(&product("A")));
+        assert!(status.is_fixed(&product("B")));
+        assert!(!status.is_affected(&product("B")));
+        assert!(status.is_not_affected(&product("C")));
+        assert!(status.is_under_investigation(&product("D")));
+        assert_eq!(
+            status.status_of(&product("A")),
+            Some(ProductStatusKind::Fixed)
+        );
+        assert_eq!(status.status_of(&product("E")), None);
+    }
+
+    #[test]
+    fn threats_for_product() {
+        let product = |id: &str| ProductIdT(id.to_string());
+        let threat = |product_ids: Option<Vec<ProductIdT>>, group_ids| Threat {
+            category: ThreatCategory::Impact,
+            details: "Details".to_string(),
+            date: None,
+            group_ids,
+            product_ids,
+        };
+        let vulnerability = Vulnerability {
+            threats: Some(vec![
+                threat(None, None),
+                threat(Some(vec![product("A")]), None),
+                threat(Some(vec![product("B")]), None),
+                threat(None, Some(vec!["G".to_string()])),
+            ]),
+            ..Default::default()
+        };
+
+        let a = product("A");
+        let threats: Vec<_> = vulnerability.threats_for_product(&a).collect();
+        assert_eq!(threats.len(), 2);
+        assert_eq!(threats[1].product_ids, Some(vec![a.clone()]));
+        assert_eq!(vulnerability.scores_for_product(&a).count(), 0);
+    }
+
+    #[test]
+    fn max_cvss_score_across_products() {
+        let score = |id: &str, vector: &str| Score {
+            products: vec![ProductIdT(id.to_string())],
+            cvss_v2: None,
+            cvss_v3: Some(vector.parse().unwrap()),
+            cvss_v4: None,
+        };
+        let vulnerability = Vulnerability {
+            scores: Some(vec![
+                score("A", "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:L/I:N/A:N"),
+                score("B", "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(vulnerability.max_cvss_score(), Some(9.8));
+        assert_eq!(
+            vulnerability
+                .score_for_product(&ProductIdT("A".to_string()))
+                .and_then(Score::base_score),
+            Some(5.3)
+        );
+        assert_eq!(Vulnerability::default().max_cvss_score(), None);
+    }
+
+    #[test]
+    fn earliest_disclosure_date_across_dates() {
+        let date = |day| Utc.with_ymd_and_hms(2023, 3, day, 0, 0, 0).unwrap();
+        let mut vulnerability = Vulnerability {
+            release_date: Some(date(10)),
+            discovery_date: Some(date(8)),
+            threats: Some(vec![Threat {
+                category: ThreatCategory::ExploitStatus,
+                details: "Exploited in the wild".to_string(),
+                date: Some(date(5)),
+                group_ids: None,
+                product_ids: None,
+            }]),
+            ..Default::default()
+        };
+        assert_eq!(vulnerability.earliest_disclosure_date(), Some(date(5)));
+
+        vulnerability.threats = None;
+        assert_eq!(vulnerability.earliest_disclosure_date(), Some(date(8)));
+        vulnerability.disclosure_date = Some(date(7));
+        assert_eq!(vulnerability.earliest_disclosure_date(), Some(date(7)));
+        assert_eq!(Vulnerability::default().earliest_disclosure_date(), None);
+    }
+
+    #[test]
+    fn status_counts() {
+        let product = |id: &str| ProductIdT(id.to_string());
+        let vulnerability = Vulnerability {
+            product_status: Some(ProductStatus {
+                known_affected: Some(vec![product("A"), product("B")]),
+                fixed: Some(vec![]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(vulnerability.known_affected_count(), 2);
+        assert_eq!(vulnerability.fixed_count(), 0);
+        assert_eq!(vulnerability.under_investigation_count(), 0);
+        assert_eq!(Vulnerability::default().known_not_affected_count(), 0);
+    }
+
+    #[test]
+    fn all_product_ids_are_deduplicated() {
+        let product = |id: &str| ProductIdT(id.to_string());
+        let vulnerability = Vulnerability {
+            product_status: Some(ProductStatus {
+                known_affected: Some(vec![product("A"), product("B")]),
+                fixed: Some(vec![product("C")]),
+                ..Default::default()
+            }),
+            scores: Some(vec![Score {
+                products: vec![product("B"), product("D")],
+                cvss_v2: None,
+                cvss_v3: None,
+                cvss_v4: None,
+            }]),
+            ..Default::default()
+        };
+        let ids: Vec<_> = vulnerability
+            .all_product_ids()
+            .map(|id| id.0.as_str())
+            .collect();
+        assert_eq!(ids, ["C", "A", "B", "D"]);
+    }
+
+    #[test]
+    fn remediations_filters() {
+        let remediation = |category, product_ids, group_ids| Remediation {
+            category,
+            details: "Details".to_string(),
+            date: None,
+            entitlements: None,
+            group_ids,
+            product_ids,
+            restart_required: None,
+            url: None,
+        };
+        let remediations = Remediations(vec![
+            remediation(
+                RemediationCategory::VendorFix,
+                Some(vec![ProductIdT("A".to_string())]),
+                None,
+            ),
+            remediation(
+                RemediationCategory::Workaround,
+                None,
+                Some(vec!["G".to_string()]),
+            ),
+            remediation(RemediationCategory::Mitigation, None, None),
+        ]);
+
+        assert_eq!(remediations.len(), 3);
+        assert_eq!(
+            remediations
+                .by_category(RemediationCategory::VendorFix)
+                .count(),
+            1
+        );
+        assert_eq!(
+            remediations
+                .for_product(&ProductIdT("A".to_string()))
+                .count(),
+            2
+        );
+        let group = "G".to_string();
+        let categories: Vec<_> = remediations
+            .for_group(&group)
+            .map(|r| &r.category)
+            .collect();
+        assert_eq!(
+            categories,
+            [
+                &RemediationCategory::Workaround,
+                &RemediationCategory::Mitigation
+            ]
+        );
+
+        let json = serde_json::to_value(&remediations).unwrap();
+        assert!(json.is_array());
+    }
+
+    #[test]
+    fn cvss_v3_severity_ratings() {
+        assert_eq!(CvssSeverity::from_score(0.0), CvssSeverity::None);
+        assert_eq!(CvssSeverity::from_score(3.9), CvssSeverity::Low);
+        assert_eq!(CvssSeverity::from_score(4.0), CvssSeverity::Medium);
+        assert_eq!(CvssSeverity::from_score(8.9), CvssSeverity::High);
+        assert_eq!(CvssSeverity::from_score(10.0), CvssSeverity::Critical);
+
+        let score = Score {
+            products: vec![],
+            cvss_v2: None,
+            cvss_v3: Some(
+                "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+                    .parse()
+                    .unwrap(),
+            ),
+            cvss_v4: None,
+        };
+        assert_eq!(score.cvss_v3_severity(), Some(CvssSeverity::Critical));
+        assert_eq!(CvssSeverity::Critical.to_string(), "Critical");
+    }
+
+    #[test]
+    fn well_known_vulnerability_id_urls() {
+        let id = |system_name: &str, text: &str| VulnerabilityId {
+            system_name: system_name.to_string(),
+            text: text.to_string(),
+        };
+        assert_eq!(
+            id("RUSTSEC", "RUSTSEC-2021-0093")
+                .well_known_url()
+                .unwrap()
+                .as_str(),
+            "https://rustsec.org/advisories/RUSTSEC-2021-0093.html"
+        );
+        assert_eq!(
+            id("ghsa", "GHSA-jfh8-c2jp-5v3q")
+                .well_known_url()
+                .unwrap()
+                .as_str(),
+            "https://github.com/advisories/GHSA-jfh8-c2jp-5v3q"
+        );
+        assert_eq!(id("Talos", "TALOS-2022-1234").well_known_url(), None);
+    }
+
+    #[test]
+    fn cwe_ids_are_validated() {
+        let cwe = Cwe::try_new("CWE-79", "Cross-site Scripting").unwrap();
+        assert_eq!(cwe.id.number(), 79);
+        assert_eq!(
+            serde_json::to_value(&cwe).unwrap(),
+            serde_json::json!({"id": "CWE-79", "name": "Cross-site Scripting"})
+        );
+        for invalid in ["CWE-0", "CWE-079", "CWE-1234567", "cwe-79", "79", "CWE-"] {
+            assert_eq!(
+                Cwe::try_new(invalid, "Name"),
+                Err(CweParseError(invalid.to_string()))
+            );
+        }
+        assert!(serde_json::from_str::<Cwe>(r#"{"id": "CWE-x", "name": "Name"}"#).is_err());
+    }
+
+    #[test]
+    fn vex_requires_impact_and_action_statements() {
+        let product = |id: &str| ProductIdT(id.to_string());
+        let mut vulnerability = Vulnerability::builder()
+            .cve("CVE-2018-0171")
+            .note(description())
+            .product_status(ProductStatus {
+                known_affected: Some(vec![product("AFFECTED")]),
+                known_not_affected: Some(vec![product("FLAGGED"), product("UNEXPLAINED")]),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        vulnerability.flags = Some(vec![Flag {
+            label: FlagLabel::ComponentNotPresent,
+            date: None,
+            group_ids: None,
+            product_ids: Some(vec![product("FLAGGED")]),
+        }]);
+        assert_eq!(
+            vulnerability.is_vex_compliant(None),
+            Err(vec![
+                VexComplianceError::MissingImpactStatement {
+                    product_id: product("UNEXPLAINED")
+                },
+                VexComplianceError::MissingActionStatement {
+                    product_id: product("AFFECTED")
+                },
+            ])
+        );
+
+        assert_eq!(
+            Vulnerability::builder()
+                .title("Untracked")
+                .build()
+                .unwrap()
+                .is_vex_compliant(None),
+            Err(vec![
+                VexComplianceError::MissingIdentification,
+                VexComplianceError::MissingNotes,
+                VexComplianceError::MissingProductStatus,
+            ])
+        );
+    }
+
+    fn description() -> Note {
+        Note {
+            category: crate::definitions::NoteCategory::Description,
+            text: "Remote code execution in Smart Install".to_string(),
+            audience: None,
+            title: None,
+        }
+    }
+
+    #[test]
+    fn vex_requires_notes_and_vex_statuses() {
+        let product = ProductIdT("FIRST".to_string());
+        let mut vulnerability = Vulnerability::builder()
+            .cve("CVE-2018-0171")
+            .note(description())
+            .product_status(ProductStatus {
+                first_affected: Some(vec![product.clone()]),
+                last_affected: Some(vec![product.clone()]),
+                first_fixed: Some(vec![product.clone()]),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(
+            vulnerability.is_vex_compliant(None),
+            Err(vec![VexComplianceError::MissingProductStatus])
+        );
+
+        vulnerability.product_status = Some(ProductStatus {
+            under_investigation: Some(vec![product]),
+            ..Default::default()
+        });
+        assert_eq!(vulnerability.is_vex_compliant(None), Ok(()));
+        vulnerability.notes = Some(vec![]);
+        assert_eq!(
+            vulnerability.is_vex_compliant(None),
+            Err(vec![VexComplianceError::MissingNotes])
+        );
+    }
+
+    #[test]
+    fn vex_resolves_product_groups() {
+        let product = |id: &str| ProductIdT(id.to_string());
+        let group = "GROUP".to_string();
+        let mut vulnerability = Vulnerability::builder()
+            .cve("CVE-2018-0171")
+            .note(description())
+            .product_status(ProductStatus {
+                known_affected: Some(vec![product("AFFECTED")]),
+                known_not_affected: Some(vec![product("NOT_AFFECTED")]),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        vulnerability.flags = Some(vec![Flag {
+            label: FlagLabel::ComponentNotPresent,
+            date: None,
+            group_ids: Some(vec![group.clone()]),
+            product_ids: None,
+        }]);
+        vulnerability.remediations = Some(Remediations(vec![Remediation {
+            category: RemediationCategory::VendorFix,
+            details: "Upgrade".to_string(),
+            date: None,
+            entitlements: None,
+            group_ids: Some(vec![group.clone()]),
+            product_ids: None,
+            restart_required: None,
+            url: None,
+        }]));
+        let tree = ProductTree {
+            product_groups: Some(vec![crate::product_tree::ProductGroup {
+                group_id: group,
+                product_ids: vec![product("AFFECTED"), product("NOT_AFFECTED")],
+                summary: None,
+            }]),
+            ..Default::default()
+        };
+
+        assert_eq!(vulnerability.is_vex_compliant(Some(&tree)), Ok(()));
+        assert_eq!(
+            vulnerability.is_vex_compliant(None),
+            Err(vec![
+                VexComplianceError::MissingImpactStatement {
+                    product_id: product("NOT_AFFECTED")
+                },
+                VexComplianceError::MissingActionStatement {
+                    product_id: product("AFFECTED")
+                },
+            ])
+        );
+    }
+
+    #[cfg(feature = "csaf_2_1")]
+    #[test]
+    fn csaf_2_1_metrics_roundtrip() {
+        let json = r#"{
+            "cve": "CVE-2021-44228",
+            "disclosure_date": "2021-12-10T00:00:00Z",
+            "metrics": [
+                {
+                    "content": {
+                        "epss": {
+                            "percentile": "0.99999",
+                            "probability": "0.97565",
+                            "timestamp": "2024-01-01T00:00:00Z"
+                        }
+                    },
+                    "products": ["CSAFPID-0001"],
+                    "source": "https://api.first.org/data/v1/epss?cve=CVE-2021-44228"
+                }
+            ],
+            "remediations": [
+                { "category": "fix_planned", "details": "Fixed in the next release", "product_ids": ["CSAFPID-0001"] }
+            ]
+        }"#;
+        let vulnerability: Vulnerability = serde_json::from_str(json).unwrap();
+        let metrics = vulnerability.metrics.as_ref().unwrap();
+        assert_eq!(
+            metrics[0].content.epss.as_ref().unwrap().probability,
+            "0.97565"
+        );
+        assert_eq!(
+            vulnerability.remediations.as_ref().unwrap()[0].category,
+            RemediationCategory::FixPlanned
+        );
+
+        let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_value(&vulnerability).unwrap(), expected);
+    }
+
+    #[test]
+    fn deduplicated_product_ids_are_sorted() {
+        let product = |id: &str| ProductIdT(id.to_string());
+        let vulnerability = Vulnerability {
+            product_status: Some(ProductStatus {
+                fixed: Some(vec![product("C"), product("A")]),
+                recommended: Some(vec![product("A")]),
+                under_investigation: Some(vec![product("B"), product("C")]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            vulnerability.deduplicated_product_ids(),
+            [product("A"), product("B"), product("C")]
+        );
+        assert!(Vulnerability::default()
+            .deduplicated_product_ids()
+            .is_empty());
+    }
+}